@@ -25,7 +25,7 @@ that uses GlyphBuffer and fillGlyphRun!"#;
             font.shape(&mut gb)?;
             let tm = font.get_text_metrics(&mut gb)?;
             p.x = (480.0 - (tm.bounding_box.x1 as f64 - tm.bounding_box.x0 as f64)) / 2.0;
-            ctx.fill_glyph_run(p, &font, gb.glyph_run())?;
+            ctx.fill_glyph_buffer(p, &font, &gb)?;
             p.y += (fm.horizontal_ascent + fm.horizontal_descent + fm.line_gap) as f64;
         }
 
@@ -2,10 +2,11 @@
 use std::borrow::Borrow;
 use std::iter::FromIterator;
 use std::marker::PhantomData;
-use std::{fmt, io, ops, ptr, slice};
+use std::path::Path;
+use std::{fmt, io, ops, ptr, slice, vec};
 
 use crate::codec::ImageCodec;
-use crate::error::{errcode_to_result, expect_mem_err, OutOfMemory, Result};
+use crate::error::{errcode_to_result, expect_mem_err, out_of_memory, OutOfMemory, Result};
 use crate::util::range_to_tuple;
 use crate::variant::WrappedBlCore;
 
@@ -64,9 +65,12 @@ impl<T: ArrayType> Array<T> {
     ///
     /// Panics if blend2d returns an
     /// [`OutOfMemory`](../error/enum.Error.html#variant.OutOfMemory) error
+    /// with the default [`OomPolicy`](../error/enum.OomPolicy.html).
     #[inline]
     pub fn reserve(&mut self, n: usize) {
-        self.try_reserve(n).expect("memory allocation failed");
+        if self.try_reserve(n).is_err() {
+            out_of_memory();
+        }
     }
 
     /// Reserves capacity for at least n items.
@@ -122,6 +126,29 @@ impl<T: ArrayType> Array<T> {
         self
     }
 
+    /// Returns the array as a mutable slice, copying the data first if it is
+    /// shared with another array.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Retains only the elements for which `f` returns true, preserving their
+    /// order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut kept = 0;
+        {
+            let data = self.as_mut_slice();
+            for i in 0..data.len() {
+                if f(&data[i]) {
+                    data.swap(kept, i);
+                    kept += 1;
+                }
+            }
+        }
+        self.truncate(kept);
+    }
+
     /// Returns the length of the array.
     #[inline]
     pub fn len(&self) -> usize {
@@ -162,6 +189,25 @@ where
         };
     }
 
+    /// Like [`extend_from_slice`], but returns a failed allocation instead of
+    /// handling it according to the [`OomPolicy`].
+    ///
+    /// [`extend_from_slice`]: #method.extend_from_slice
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
+    pub fn try_extend_from_slice<S: AsRef<[T]>>(
+        &mut self,
+        data: S,
+    ) -> std::result::Result<(), OutOfMemory> {
+        unsafe {
+            let data = data.as_ref();
+            OutOfMemory::from_errcode(ffi::blArrayAppendView(
+                self.core_mut(),
+                data.as_ptr() as *const _,
+                data.len(),
+            ))
+        }
+    }
+
     /// Inserts all items in the slice into the array at the given index.
     pub fn insert_from_slice<S: AsRef<[T]>>(&mut self, index: usize, data: S) {
         unsafe {
@@ -175,6 +221,53 @@ where
         };
     }
 
+    /// Removes the last element and returns it, or `None` if the array is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.last()?.clone();
+        self.truncate(self.len() - 1);
+        Some(item)
+    }
+
+    /// Removes the element at the given index and returns it, replacing it
+    /// with the last element of the array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(index < len, "swap_remove index out of bounds");
+        self.swap(index, len - 1);
+        self.pop().unwrap()
+    }
+
+    /// Removes the elements in the range from the array and returns them.
+    ///
+    /// Unlike [`Vec::drain`] the elements are removed right away instead of
+    /// when the iterator is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// [`Vec::drain`]: https://doc.rust-lang.org/std/vec/struct.Vec.html#method.drain
+    pub fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> vec::IntoIter<T> {
+        let start = match range.start_bound() {
+            ops::Bound::Included(&n) => n,
+            ops::Bound::Excluded(&n) => n + 1,
+            ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ops::Bound::Included(&n) => n + 1,
+            ops::Bound::Excluded(&n) => n,
+            ops::Bound::Unbounded => self.len(),
+        };
+        let items = self[start..end].to_vec();
+        unsafe { expect_mem_err(ffi::blArrayRemoveRange(self.core_mut(), start, end)) };
+        items.into_iter()
+    }
+
     /// Replaces the elements specified by the range of indices with the given
     /// slice.
     pub fn replace_from_slice<R, S>(&mut self, range: R, data: S)
@@ -357,6 +450,20 @@ where
     pub fn push(&mut self, item: T) {
         unsafe { T::push(self.core_mut(), item) };
     }
+
+    /// Like [`push`], but returns a failed allocation instead of handling it
+    /// according to the [`OomPolicy`].
+    ///
+    /// [`push`]: #method.push
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
+    #[inline]
+    pub fn try_push(&mut self, item: T) -> std::result::Result<(), OutOfMemory> {
+        // with the capacity reserved pushing can't fail anymore
+        self.try_reserve(self.len() + 1)?;
+        self.push(item);
+        Ok(())
+    }
+
     #[inline]
     pub fn insert(&mut self, index: usize, item: T) {
         unsafe { T::insert(self.core_mut(), index, item) };
@@ -378,6 +485,14 @@ impl Array<ImageCodec> {
         self.into_iter()
             .max_by_key(|codec| codec.inspect_data(data.as_ref()))
     }
+
+    /// Searches for an image codec in the array that handles files with the
+    /// extension of the given path, ignoring ASCII case.
+    pub fn find_codec_by_extension<P: AsRef<Path>>(&self, path: P) -> Option<&ImageCodec> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        self.iter()
+            .find(|c| c.extensions().any(|e| e.eq_ignore_ascii_case(ext)))
+    }
 }
 
 use crate::variant::ImplType;
@@ -518,7 +633,7 @@ impl_array_type! {
     blArrayAppendU32, blArrayInsertU32, blArrayInsertU32 for (i32 = ImplType::ArrayI32), (u32 = ImplType::ArrayU32);
     blArrayAppendU64, blArrayInsertU64, blArrayInsertU64 for (i64 = ImplType::ArrayI64), (u64 = ImplType::ArrayU64);
     blArrayAppendF32, blArrayInsertF32, blArrayInsertF32 for (f32 = ImplType::ArrayF32);
-    blArrayAppendF64, blArrayInsertF64, blArrayInsertF64 for (f64 = ImplType::ArrayF32);
+    blArrayAppendF64, blArrayInsertF64, blArrayInsertF64 for (f64 = ImplType::ArrayF64);
 }
 
 #[cfg(target_pointer_width = "32")]
@@ -539,7 +654,15 @@ mod scope {
 
 #[cfg(test)]
 mod test_array {
-    use crate::{array::Array, image::Image, path::Path};
+    use crate::{array::Array, font_defs::FontFeature, image::Image, path::Path, Tag};
+
+    #[test]
+    fn test_try_variants() {
+        let mut arr = Array::<i32>::new();
+        arr.try_push(1).unwrap();
+        arr.try_extend_from_slice(&[2, 3]).unwrap();
+        assert_eq!(&*arr, &[1, 2, 3]);
+    }
 
     #[test]
     fn test_array_resize() {
@@ -566,6 +689,18 @@ mod test_array {
         assert_eq!(&[32, 0, 24, 8], &*arr);
     }
 
+    #[test]
+    fn test_array_f64() {
+        let data = [0.5, -1.25, 3.0e100, 4.0];
+        let mut arr = Array::<f64>::from(&data[..]);
+        assert_eq!(&data, &*arr);
+        arr.push(5.5);
+        arr.insert(0, -0.125);
+        assert_eq!(&[-0.125, 0.5, -1.25, 3.0e100, 4.0, 5.5], &*arr);
+        assert_eq!(arr.pop(), Some(5.5));
+        assert_eq!(arr.clone(), arr);
+    }
+
     #[test]
     fn test_array_ops_objects() {
         let img = [
@@ -602,4 +737,40 @@ mod test_array {
         }
         assert_eq!(&[5, 4, 3, 2, 1, 0], &*arr);
     }
+
+    #[test]
+    fn test_array_removal() {
+        let mut arr = Array::<i32>::from(&[0, 1, 2, 3, 4, 5, 6, 7][..]);
+        assert_eq!(arr.pop(), Some(7));
+        assert_eq!(arr.swap_remove(1), 1);
+        assert_eq!(&[0, 6, 2, 3, 4, 5], &*arr);
+        assert_eq!(arr.drain(1..=2).collect::<Vec<_>>(), [6, 2]);
+        assert_eq!(&[0, 3, 4, 5], &*arr);
+        arr.retain(|&i| i % 2 == 1);
+        assert_eq!(&[3, 5], &*arr);
+        arr.as_mut_slice()[0] = 1;
+        assert_eq!(arr.drain(..).collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(arr.pop(), None);
+
+        let mut path = Path::new();
+        path.move_to(1.0, 2.0);
+        let mut paths = Array::<Path>::from(vec![Path::new(), path.clone(), Path::new()]);
+        paths.retain(|p| !p.is_empty());
+        assert_eq!(paths.pop(), Some(path));
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn test_array_from_struct_slice() {
+        let tags = [Tag::from_bytes(*b"kern"), Tag::from_bytes(*b"liga")];
+        let arr = Array::<Tag>::from(&tags[..]);
+        assert_eq!(&tags[..], &*arr);
+
+        let features = [
+            FontFeature::new(*b"kern", 1),
+            FontFeature::new(*b"liga", 0),
+        ];
+        let arr = Array::<FontFeature>::from(&features[..]);
+        assert_eq!(&features[..], &*arr);
+    }
 }
@@ -0,0 +1,264 @@
+//! A high level drawing surface owning its image and rendering context.
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+use crate::codec::ImageCodec;
+use crate::context::{CompOp, Context, ContextCookie};
+use crate::error::{Error, Result};
+use crate::font::Font;
+use crate::geometry::{Geometry, Line, PointD, Rect};
+use crate::image::{Image, ImageFormat};
+use crate::matrix::{Matrix2DOp, MatrixTransform};
+use crate::rgba::Rgba32;
+use crate::style::FillStrokeStyle;
+
+/// An image together with a [`Context`] rendering to it.
+///
+/// Unlike the context, whose state is set up once and then used by the
+/// drawing operations, every drawing operation of a canvas takes the style it
+/// paints with. Transformations and clipping stay part of the state and can
+/// be scoped with [`layer`] or [`save`].
+///
+/// [`Context`]: ../context/struct.Context.html
+/// [`layer`]: #method.layer
+/// [`save`]: #method.save
+#[derive(Debug)]
+pub struct Canvas {
+    ctx: Context<'static>,
+    image: Image,
+}
+
+impl Canvas {
+    /// Creates a transparent canvas of the given size in the
+    /// [`ImageFormat::PRgb32`] format.
+    ///
+    /// [`ImageFormat::PRgb32`]: ../image/enum.ImageFormat.html#variant.PRgb32
+    pub fn new(width: i32, height: i32) -> Result<Canvas> {
+        Self::with_format(width, height, ImageFormat::PRgb32)
+    }
+
+    /// Creates a cleared canvas of the given size and format.
+    pub fn with_format(width: i32, height: i32, format: ImageFormat) -> Result<Canvas> {
+        let mut image = Image::new(width, height, format)?;
+        let mut ctx = Context::new(&mut image)?;
+        ctx.clear_all()?;
+        // The context holds its own reference to the image data, its lifetime
+        // only prevents the image from being accessed while it is rendered
+        // to. The canvas owns both, drops the context first and only hands
+        // out the image after synchronizing the context. The context itself
+        // is never handed out by value or as `&mut Context<'static>`, see
+        // `with_context`, so no other context can take its place.
+        let ctx = unsafe { mem::transmute::<Context<'_>, Context<'static>>(ctx) };
+        Ok(Canvas { ctx, image })
+    }
+
+    /// The width of the canvas.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.ctx.target_width() as i32
+    }
+
+    /// The height of the canvas.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.ctx.target_height() as i32
+    }
+
+    /// Runs the given closure with the underlying context, for everything
+    /// the canvas doesn't cover.
+    #[inline]
+    pub fn with_context<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Context<'_>) -> R,
+    {
+        // the closure has to work for any lifetime of the context, so it can
+        // neither move the context out nor swap in one with a shorter lived
+        // target
+        f(&mut self.ctx)
+    }
+
+    /// Waits until everything has been rendered and returns the image.
+    pub fn image(&mut self) -> Result<&Image> {
+        self.ctx.sync()?;
+        Ok(&self.image)
+    }
+
+    /// Finishes rendering and returns the image.
+    pub fn into_image(self) -> Result<Image> {
+        let Canvas { ctx, image } = self;
+        ctx.end()?;
+        Ok(image)
+    }
+
+    /// Waits until everything has been rendered and writes the image to a
+    /// PNG file.
+    ///
+    /// Returns [`Error::ImageNoMatchingCodec`] if blend2d was built without
+    /// the PNG codec.
+    ///
+    /// [`Error::ImageNoMatchingCodec`]: ../error/enum.Error.html#variant.ImageNoMatchingCodec
+    pub fn to_png<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let codecs = ImageCodec::built_in_codecs();
+        let codec = codecs
+            .find_codec_by_name("PNG")
+            .ok_or(Error::ImageNoMatchingCodec)?;
+        self.image()?.write_to_file(path, codec)
+    }
+
+    /// Saves the current state, which is restored once the returned guard is
+    /// dropped.
+    #[inline]
+    pub fn save(&mut self) -> CanvasLayer<'_> {
+        let cookie = self.ctx.save_cookie();
+        CanvasLayer {
+            canvas: self,
+            cookie,
+        }
+    }
+
+    /// Runs the given closure with a saved state that is restored afterwards,
+    /// even if the closure panics.
+    #[inline]
+    pub fn layer<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Canvas) -> T,
+    {
+        let mut layer = self.save();
+        f(&mut layer)
+    }
+
+    /// Replaces the whole canvas with the given color, ignoring the clip.
+    pub fn clear<C: Into<Rgba32>>(&mut self, color: C) -> Result<()> {
+        let cookie = self.ctx.save_cookie();
+        self.ctx.restore_clipping();
+        self.ctx.set_comp_op(CompOp::SrcCopy);
+        self.ctx.set_fill_style_rgba32(color);
+        let result = self.ctx.fill_all();
+        self.ctx.restore_cookie(cookie).and(result)
+    }
+
+    /// Fills the geometry with the given style.
+    pub fn fill<G, S>(&mut self, geometry: &G, style: &S) -> Result<()>
+    where
+        G: Geometry + ?Sized,
+        S: FillStrokeStyle + ?Sized,
+    {
+        self.ctx.set_fill_style(style);
+        self.ctx.fill_geometry(geometry)
+    }
+
+    /// Strokes the outline of the geometry with the given style and width.
+    pub fn stroke<G, S>(&mut self, geometry: &G, style: &S, width: f64) -> Result<()>
+    where
+        G: Geometry + ?Sized,
+        S: FillStrokeStyle + ?Sized,
+    {
+        self.ctx.set_stroke_style(style);
+        self.ctx.set_stroke_width(width);
+        self.ctx.stroke_geometry(geometry)
+    }
+
+    /// Strokes a line between two points with the given style and width.
+    pub fn line<S>(&mut self, from: PointD, to: PointD, style: &S, width: f64) -> Result<()>
+    where
+        S: FillStrokeStyle + ?Sized,
+    {
+        self.stroke(&Line::new(from.x, from.y, to.x, to.y), style, width)
+    }
+
+    /// Fills the text with its baseline starting at the given point.
+    pub fn text<S>(&mut self, at: PointD, font: &Font, text: &str, style: &S) -> Result<()>
+    where
+        S: FillStrokeStyle + ?Sized,
+    {
+        self.ctx.set_fill_style(style);
+        self.ctx.fill_utf8_text(at, font, text)
+    }
+
+    /// Restricts drawing to the given rectangle, intersected with the current
+    /// clip.
+    #[inline]
+    pub fn clip<R: Rect>(&mut self, rect: &R) {
+        self.ctx.clip_to_rect(rect);
+    }
+
+    /// Sets the opacity everything is drawn with.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.ctx.set_global_alpha(alpha);
+    }
+}
+
+impl MatrixTransform for Canvas {
+    #[inline]
+    #[doc(hidden)]
+    fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
+        self.ctx.apply_matrix_op(op, data);
+    }
+}
+
+/// A saved state of a [`Canvas`] that is restored when dropped, created by
+/// [`Canvas::save`].
+///
+/// [`Canvas`]: struct.Canvas.html
+/// [`Canvas::save`]: struct.Canvas.html#method.save
+#[derive(Debug)]
+pub struct CanvasLayer<'c> {
+    canvas: &'c mut Canvas,
+    cookie: ContextCookie,
+}
+
+impl Deref for CanvasLayer<'_> {
+    type Target = Canvas;
+
+    #[inline]
+    fn deref(&self) -> &Canvas {
+        self.canvas
+    }
+}
+
+impl DerefMut for CanvasLayer<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Canvas {
+        self.canvas
+    }
+}
+
+impl Drop for CanvasLayer<'_> {
+    fn drop(&mut self) {
+        // the cookie is always valid, states saved inside of the layer are
+        // restored along with it
+        let _ = self.canvas.ctx.restore_cookie(self.cookie);
+    }
+}
+
+#[cfg(test)]
+mod test_canvas {
+    use crate::canvas::Canvas;
+    use crate::geometry::{PointD, RectD};
+    use crate::matrix::MatrixTransform;
+    use crate::rgba::Rgba32;
+
+    #[test]
+    fn test_canvas_layer() {
+        let mut canvas = Canvas::new(32, 32).unwrap();
+        let red = Rgba32::new(0xFF_FF_00_00);
+        canvas.layer(|canvas| {
+            canvas.translate(16.0, 16.0);
+            canvas.clip(&RectD::new(0.0, 0.0, 8.0, 8.0));
+            canvas
+                .fill(&RectD::new(0.0, 0.0, 16.0, 16.0), &red)
+                .unwrap();
+        });
+        canvas
+            .line(PointD::new(0.0, 2.0), PointD::new(8.0, 2.0), &red, 2.0)
+            .unwrap();
+        assert_eq!(canvas.with_context(|ctx| ctx.saved_state_count()), 0);
+
+        let image = canvas.into_image().unwrap();
+        assert_eq!(image.pixel(20, 20), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(28, 28), Some(0));
+        assert_eq!(image.pixel(4, 2), Some(0xFF_FF_00_00));
+    }
+}
@@ -1,5 +1,12 @@
 //! Functionality for decoding and encoding images.
+mod custom;
+mod frames;
+
+pub use self::custom::{CustomCodec, CustomDecoder, CustomEncoder};
+pub use self::frames::{FrameInfo, Frames};
+
 use std::ffi::CStr;
+use std::path::Path;
 use std::{fmt, mem, ptr, str};
 
 use ffi::BLImageCodecFeatures::*;
@@ -101,7 +108,20 @@ impl ImageCodec {
         WrappedBlCore::from_core(core)
     }
 
+    /// Searches the built in codecs for one that handles files with the
+    /// extension of the given path, see [`Array::find_codec_by_extension`].
+    ///
+    /// [`Array::find_codec_by_extension`]: ../array/struct.Array.html#method.find_codec_by_extension
+    pub fn find_by_extension<P: AsRef<Path>>(path: P) -> Option<ImageCodec> {
+        Self::built_in_codecs()
+            .find_codec_by_extension(path)
+            .cloned()
+    }
+
     /// Adds a codec to the built in codecs list.
+    ///
+    /// The built in codecs list is global, see [`BuiltInCodecsGuard`] for a
+    /// registration that is undone automatically.
     #[inline]
     pub fn add_to_built_in(codec: &ImageCodec) {
         unsafe { expect_mem_err(ffi::blImageCodecAddToBuiltIn(codec.core())) };
@@ -146,6 +166,13 @@ impl ImageCodec {
     pub fn features(&self) -> ImageCodecFeatures {
         (self.impl_().features as u32).into()
     }
+
+    /// Checks whether the codec has the given feature, e.g. whether it can
+    /// write images.
+    #[inline]
+    pub fn has_feature(&self, feature: ImageCodecFeatures) -> bool {
+        self.impl_().features as u32 & u32::from(feature) != 0
+    }
 }
 
 impl fmt::Debug for ImageCodec {
@@ -179,6 +206,45 @@ impl Drop for ImageCodec {
     }
 }
 
+/// Registers an [`ImageCodec`] in the global built in codecs list for as long
+/// as the guard is alive.
+///
+/// The built in codecs list is shared by the whole process, so a registration
+/// is visible to every [`ImageCodec::built_in_codecs`] call made while the
+/// guard is alive, including calls from other threads and unrelated code.
+/// Arrays returned by [`ImageCodec::built_in_codecs`] are snapshots and are
+/// not affected by later registrations or removals.
+///
+/// [`ImageCodec::built_in_codecs`]: struct.ImageCodec.html#method.built_in_codecs
+#[derive(Debug)]
+#[must_use = "the codec is unregistered again when the guard is dropped"]
+pub struct BuiltInCodecsGuard {
+    codec: ImageCodec,
+}
+
+impl BuiltInCodecsGuard {
+    /// Adds the codec to the built in codecs list, removing it again once the
+    /// returned guard is dropped.
+    pub fn register(codec: &ImageCodec) -> Self {
+        ImageCodec::add_to_built_in(codec);
+        BuiltInCodecsGuard {
+            codec: codec.clone(),
+        }
+    }
+
+    /// The registered codec.
+    #[inline]
+    pub fn codec(&self) -> &ImageCodec {
+        &self.codec
+    }
+}
+
+impl Drop for BuiltInCodecsGuard {
+    fn drop(&mut self) {
+        ImageCodec::remove_from_built_in(&self.codec);
+    }
+}
+
 /// An image encoder belonging to a certain [`ImageCodec`].
 #[repr(transparent)]
 pub struct ImageEncoder {
@@ -0,0 +1,452 @@
+use std::ffi::{c_void, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::{mem, slice};
+
+use ffi::BLImageCodecFeatures::*;
+
+use crate::array::Array;
+use crate::error::{error_to_errcode, Error, Result};
+use crate::image::{Image, ImageInfo};
+use crate::util::cast_ref;
+use crate::variant::{ImplTraits, ImplType, WrappedBlCore};
+
+use super::{ImageCodec, ImageDecoder, ImageEncoder};
+
+/// An image codec implemented in Rust.
+///
+/// Custom codecs are turned into an [`ImageCodec`] with
+/// [`ImageCodec::from_custom`] and behave like any other codec from then on.
+/// Registering them with [`ImageCodec::add_to_built_in`] makes them available
+/// to [`Image::from_path`] and the other functions that pick a codec by
+/// inspecting the data.
+///
+/// [`ImageCodec`]: struct.ImageCodec.html
+/// [`ImageCodec::from_custom`]: struct.ImageCodec.html#method.from_custom
+/// [`ImageCodec::add_to_built_in`]: struct.ImageCodec.html#method.add_to_built_in
+/// [`Image::from_path`]: ../image/struct.Image.html#method.from_path
+pub trait CustomCodec: Send + Sync + 'static {
+    /// The codec's name, e.g. `"WEBP"`.
+    fn name(&self) -> &str;
+
+    /// The codec's vendor.
+    fn vendor(&self) -> &str;
+
+    /// The codec's mime-type, e.g. `"image/webp"`.
+    fn mime_type(&self) -> &str;
+
+    /// The codec's file extensions separated by `|`, e.g. `"webp"`.
+    fn extensions(&self) -> &str;
+
+    /// Inspects the start of the given data and returns how likely it is that
+    /// the data belongs to this codec, `0` meaning not at all and `100`
+    /// meaning a certain match.
+    fn inspect_data(&self, data: &[u8]) -> u32;
+
+    /// Creates a decoder, `None` if this codec can't decode images.
+    fn create_decoder(&self) -> Option<Box<dyn CustomDecoder>> {
+        None
+    }
+
+    /// Creates an encoder, `None` if this codec can't encode images.
+    fn create_encoder(&self) -> Option<Box<dyn CustomEncoder>> {
+        None
+    }
+}
+
+/// An image decoder created by a [`CustomCodec`].
+///
+/// Each call receives the whole data passed to the [`ImageDecoder`].
+///
+/// [`CustomCodec`]: trait.CustomCodec.html
+/// [`ImageDecoder`]: struct.ImageDecoder.html
+pub trait CustomDecoder: Send + 'static {
+    /// Resets the decoder to decode a new image.
+    fn restart(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reads the image information from the given data.
+    fn read_info(&mut self, data: &[u8]) -> Result<ImageInfo>;
+
+    /// Decodes the next frame from the given data.
+    fn read_frame(&mut self, data: &[u8]) -> Result<Image>;
+}
+
+/// An image encoder created by a [`CustomCodec`].
+///
+/// [`CustomCodec`]: trait.CustomCodec.html
+pub trait CustomEncoder: Send + 'static {
+    /// Resets the encoder to encode a new image.
+    fn restart(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Encodes the image as the next frame and returns the encoded data.
+    fn write_frame(&mut self, image: &Image) -> Result<Vec<u8>>;
+}
+
+impl ImageCodec {
+    /// Creates an image codec that forwards to the given Rust implementation.
+    ///
+    /// The read and write features of the codec are determined by creating a
+    /// decoder and an encoder once. Returns [`Error::InvalidValue`] if one of
+    /// the codec's names contains a nul byte.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn from_custom<C: CustomCodec>(codec: C) -> Result<ImageCodec> {
+        let c_string = |s: &str| CString::new(s).map_err(|_| Error::InvalidValue);
+        let name = c_string(codec.name())?;
+        let vendor = c_string(codec.vendor())?;
+        let mime_type = c_string(codec.mime_type())?;
+        let extensions = c_string(codec.extensions())?;
+        let mut features = 0;
+        if codec.create_decoder().is_some() {
+            features |= BL_IMAGE_CODEC_FEATURE_READ;
+        }
+        if codec.create_encoder().is_some() {
+            features |= BL_IMAGE_CODEC_FEATURE_WRITE;
+        }
+
+        let mut impl_ = Box::new(CustomCodecImpl {
+            base: unsafe { mem::zeroed() },
+            virt: ffi::BLImageCodecVirt {
+                destroy: Some(codec_destroy),
+                inspectData: Some(codec_inspect_data),
+                createDecoder: Some(codec_create_decoder),
+                createEncoder: Some(codec_create_encoder),
+            },
+            name,
+            vendor,
+            mime_type,
+            extensions,
+            codec: Box::new(codec),
+        });
+        impl_.base.virt = &impl_.virt;
+        impl_.base.name = impl_.name.as_ptr();
+        impl_.base.vendor = impl_.vendor.as_ptr();
+        impl_.base.mimeType = impl_.mime_type.as_ptr();
+        impl_.base.extensions = impl_.extensions.as_ptr();
+        impl_.base.features = features as _;
+        impl_.base.refCount = 1;
+        impl_.base.implType = ImplType::ImageCodec as u8;
+        impl_.base.implTraits = ImplTraits::VIRTUAL.bits();
+        Ok(ImageCodec::from_core(ffi::BLImageCodecCore {
+            impl_: Box::into_raw(impl_) as *mut _,
+        }))
+    }
+}
+
+/// The impl of a custom codec, `base` has to stay the first field so that
+/// blend2d can treat a pointer to this struct as a `BLImageCodecImpl`.
+#[repr(C)]
+struct CustomCodecImpl {
+    base: ffi::BLImageCodecImpl,
+    virt: ffi::BLImageCodecVirt,
+    name: CString,
+    vendor: CString,
+    mime_type: CString,
+    extensions: CString,
+    codec: Box<dyn CustomCodec>,
+}
+
+#[repr(C)]
+struct CustomDecoderImpl {
+    base: ffi::BLImageDecoderImpl,
+    virt: ffi::BLImageDecoderVirt,
+    decoder: Box<dyn CustomDecoder>,
+}
+
+#[repr(C)]
+struct CustomEncoderImpl {
+    base: ffi::BLImageEncoderImpl,
+    virt: ffi::BLImageEncoderVirt,
+    encoder: Box<dyn CustomEncoder>,
+}
+
+/// Runs a callback, turning errors and panics into result codes as unwinding
+/// into blend2d is undefined behavior.
+fn call_guarded<F: FnOnce() -> Result<()>>(f: F) -> u32 {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => ffi::BLResultCode::BL_SUCCESS as u32,
+        Ok(Err(e)) => error_to_errcode(&e),
+        Err(_) => ffi::BLResultCode::BL_ERROR_INVALID_STATE as u32,
+    }
+}
+
+#[inline]
+unsafe fn data_slice<'a>(data: *const u8, size: usize) -> &'a [u8] {
+    if data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(data, size)
+    }
+}
+
+/// Creates a new reference to the codec behind `impl_`, used as the codec of
+/// the decoders and encoders it creates.
+unsafe fn codec_core(impl_: *const ffi::BLImageCodecImpl) -> ffi::BLImageCodecCore {
+    let this = mem::ManuallyDrop::new(ImageCodec::from_core(ffi::BLImageCodecCore {
+        impl_: impl_ as *mut _,
+    }));
+    let codec = mem::ManuallyDrop::new((*this).clone());
+    codec.core
+}
+
+unsafe extern "C" fn codec_destroy(impl_: *mut c_void) -> u32 {
+    drop(Box::from_raw(impl_ as *mut CustomCodecImpl));
+    ffi::BLResultCode::BL_SUCCESS as u32
+}
+
+unsafe extern "C" fn codec_inspect_data(
+    impl_: *const ffi::BLImageCodecImpl,
+    data: *const u8,
+    size: usize,
+) -> u32 {
+    let this = &*(impl_ as *const CustomCodecImpl);
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        this.codec.inspect_data(data_slice(data, size))
+    }))
+    .unwrap_or(0)
+}
+
+unsafe extern "C" fn codec_create_decoder(
+    impl_: *const ffi::BLImageCodecImpl,
+    dst: *mut ffi::BLImageDecoderCore,
+) -> u32 {
+    let this = &*(impl_ as *const CustomCodecImpl);
+    call_guarded(|| {
+        let decoder = this
+            .codec
+            .create_decoder()
+            .ok_or(Error::ImageDecoderNotProvided)?;
+        let mut decoder_impl = Box::new(CustomDecoderImpl {
+            base: mem::zeroed(),
+            virt: ffi::BLImageDecoderVirt {
+                destroy: Some(decoder_destroy),
+                restart: Some(decoder_restart),
+                readInfo: Some(decoder_read_info),
+                readFrame: Some(decoder_read_frame),
+            },
+            decoder,
+        });
+        decoder_impl.base.virt = &decoder_impl.virt;
+        decoder_impl.base.codec = codec_core(impl_);
+        decoder_impl.base.refCount = 1;
+        decoder_impl.base.implType = ImplType::ImageDecoder as u8;
+        decoder_impl.base.implTraits = ImplTraits::VIRTUAL.bits();
+        *(dst as *mut ImageDecoder) = ImageDecoder::from_core(ffi::BLImageDecoderCore {
+            impl_: Box::into_raw(decoder_impl) as *mut _,
+        });
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn codec_create_encoder(
+    impl_: *const ffi::BLImageCodecImpl,
+    dst: *mut ffi::BLImageEncoderCore,
+) -> u32 {
+    let this = &*(impl_ as *const CustomCodecImpl);
+    call_guarded(|| {
+        let encoder = this
+            .codec
+            .create_encoder()
+            .ok_or(Error::ImageEncoderNotProvided)?;
+        let mut encoder_impl = Box::new(CustomEncoderImpl {
+            base: mem::zeroed(),
+            virt: ffi::BLImageEncoderVirt {
+                destroy: Some(encoder_destroy),
+                restart: Some(encoder_restart),
+                writeFrame: Some(encoder_write_frame),
+            },
+            encoder,
+        });
+        encoder_impl.base.virt = &encoder_impl.virt;
+        encoder_impl.base.codec = codec_core(impl_);
+        encoder_impl.base.refCount = 1;
+        encoder_impl.base.implType = ImplType::ImageEncoder as u8;
+        encoder_impl.base.implTraits = ImplTraits::VIRTUAL.bits();
+        *(dst as *mut ImageEncoder) = ImageEncoder::from_core(ffi::BLImageEncoderCore {
+            impl_: Box::into_raw(encoder_impl) as *mut _,
+        });
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn decoder_destroy(impl_: *mut c_void) -> u32 {
+    let mut impl_ = Box::from_raw(impl_ as *mut CustomDecoderImpl);
+    ffi::blImageCodecReset(&mut impl_.base.codec);
+    ffi::BLResultCode::BL_SUCCESS as u32
+}
+
+unsafe extern "C" fn decoder_restart(impl_: *mut ffi::BLImageDecoderImpl) -> u32 {
+    let this = &mut *(impl_ as *mut CustomDecoderImpl);
+    let result = call_guarded(|| this.decoder.restart());
+    this.base.lastResult = result;
+    this.base.frameIndex = 0;
+    this.base.bufferIndex = 0;
+    result
+}
+
+unsafe extern "C" fn decoder_read_info(
+    impl_: *mut ffi::BLImageDecoderImpl,
+    info_out: *mut ffi::BLImageInfo,
+    data: *const u8,
+    size: usize,
+) -> u32 {
+    let this = &mut *(impl_ as *mut CustomDecoderImpl);
+    let decoder = &mut this.decoder;
+    let result = call_guarded(|| {
+        *(info_out as *mut ImageInfo) = decoder.read_info(data_slice(data, size))?;
+        Ok(())
+    });
+    this.base.lastResult = result;
+    result
+}
+
+unsafe extern "C" fn decoder_read_frame(
+    impl_: *mut ffi::BLImageDecoderImpl,
+    image_out: *mut ffi::BLImageCore,
+    data: *const u8,
+    size: usize,
+) -> u32 {
+    let this = &mut *(impl_ as *mut CustomDecoderImpl);
+    let decoder = &mut this.decoder;
+    let result = call_guarded(|| {
+        *(image_out as *mut Image) = decoder.read_frame(data_slice(data, size))?;
+        Ok(())
+    });
+    this.base.lastResult = result;
+    if result == ffi::BLResultCode::BL_SUCCESS as u32 {
+        this.base.frameIndex += 1;
+        this.base.bufferIndex = size;
+    }
+    result
+}
+
+unsafe extern "C" fn encoder_destroy(impl_: *mut c_void) -> u32 {
+    let mut impl_ = Box::from_raw(impl_ as *mut CustomEncoderImpl);
+    ffi::blImageCodecReset(&mut impl_.base.codec);
+    ffi::BLResultCode::BL_SUCCESS as u32
+}
+
+unsafe extern "C" fn encoder_restart(impl_: *mut ffi::BLImageEncoderImpl) -> u32 {
+    let this = &mut *(impl_ as *mut CustomEncoderImpl);
+    let result = call_guarded(|| this.encoder.restart());
+    this.base.lastResult = result;
+    this.base.frameIndex = 0;
+    this.base.bufferIndex = 0;
+    result
+}
+
+unsafe extern "C" fn encoder_write_frame(
+    impl_: *mut ffi::BLImageEncoderImpl,
+    dst: *mut ffi::BLArrayCore,
+    image: *const ffi::BLImageCore,
+) -> u32 {
+    let this = &mut *(impl_ as *mut CustomEncoderImpl);
+    let encoder = &mut this.encoder;
+    let result = call_guarded(|| {
+        let data = encoder.write_frame(cast_ref(&*image))?;
+        let dst = &mut *(dst as *mut Array<u8>);
+        dst.clear();
+        dst.extend_from_slice(&data);
+        Ok(())
+    });
+    this.base.lastResult = result;
+    if result == ffi::BLResultCode::BL_SUCCESS as u32 {
+        this.base.frameIndex += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test_custom_codec {
+    use crate::codec::{BuiltInCodecsGuard, CustomCodec, CustomDecoder, ImageCodec};
+    use crate::error::{Error, Result};
+    use crate::geometry::SizeI;
+    use crate::image::{Image, ImageFormat, ImageInfo};
+
+    /// Decodes "GRAY" followed by a width and height byte and the pixels.
+    struct GrayCodec;
+
+    struct GrayDecoder;
+
+    fn parse(data: &[u8]) -> Result<(i32, i32, &[u8])> {
+        match data {
+            [b'G', b'R', b'A', b'Y', w, h, pixels @ ..]
+                if pixels.len() == usize::from(*w) * usize::from(*h) =>
+            {
+                Ok((i32::from(*w), i32::from(*h), pixels))
+            },
+            _ => Err(Error::InvalidData),
+        }
+    }
+
+    impl CustomCodec for GrayCodec {
+        fn name(&self) -> &str {
+            "GRAY"
+        }
+
+        fn vendor(&self) -> &str {
+            "blend2d-rs"
+        }
+
+        fn mime_type(&self) -> &str {
+            "image/x-gray"
+        }
+
+        fn extensions(&self) -> &str {
+            "gray"
+        }
+
+        fn inspect_data(&self, data: &[u8]) -> u32 {
+            if data.starts_with(b"GRAY") {
+                100
+            } else {
+                0
+            }
+        }
+
+        fn create_decoder(&self) -> Option<Box<dyn CustomDecoder>> {
+            Some(Box::new(GrayDecoder))
+        }
+    }
+
+    impl CustomDecoder for GrayDecoder {
+        fn read_info(&mut self, data: &[u8]) -> Result<ImageInfo> {
+            let (w, h, _) = parse(data)?;
+            Ok(ImageInfo::new(SizeI::new(w, h), 8))
+        }
+
+        fn read_frame(&mut self, data: &[u8]) -> Result<Image> {
+            let (w, h, pixels) = parse(data)?;
+            let mut image = Image::new(w, h, ImageFormat::A8)?;
+            for (i, &p) in pixels.iter().enumerate() {
+                let w = w as usize;
+                image.set_pixel(i % w, i / w, u32::from(p) << 24);
+            }
+            Ok(image)
+        }
+    }
+
+    #[test]
+    fn test_custom_codec() {
+        let codec = ImageCodec::from_custom(GrayCodec).unwrap();
+        assert_eq!(codec.name(), "GRAY");
+        assert_eq!(codec.extensions().collect::<Vec<_>>(), ["gray"]);
+        assert!(codec.create_encoder().is_none());
+
+        let data = b"GRAY\x02\x01\x10\x20";
+        assert_eq!(codec.inspect_data(&data[..]), 100);
+        let mut decoder = codec.create_decoder().unwrap();
+        assert_eq!(decoder.codec(), &codec);
+        assert_eq!(decoder.read_info(&data[..]).unwrap().size, SizeI::new(2, 1));
+        assert!(decoder.read_frame(b"GRAY\x02").is_err());
+
+        let _guard = BuiltInCodecsGuard::register(&codec);
+        let codecs = ImageCodec::built_in_codecs();
+        let image = Image::from_data(2, 1, ImageFormat::A8, data, &codecs).unwrap();
+        assert_eq!(image.size(), SizeI::new(2, 1));
+        assert_eq!(image.pixel(1, 0), Some(0x2000_0000));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::{Error, Result};
+use crate::image::{Image, ImageInfo};
+
+use super::ImageDecoder;
+
+/// Metadata of a frame yielded by [`Frames`].
+///
+/// Blend2D's decoders don't report frame durations or disposal, so only the
+/// position of the frame is known.
+///
+/// [`Frames`]: struct.Frames.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// The index of the frame.
+    pub index: u64,
+}
+
+/// An iterator decoding the frames of a possibly multi-frame image, created
+/// by [`ImageDecoder::frames`].
+///
+/// The iterator ends after the number of frames given by the image
+/// information or, if that is unknown, once the decoder runs out of data.
+/// An error ends the iteration after being yielded.
+///
+/// [`ImageDecoder::frames`]: struct.ImageDecoder.html#method.frames
+#[derive(Debug)]
+pub struct Frames<'a, 'd> {
+    decoder: &'a mut ImageDecoder,
+    data: &'d [u8],
+    info: ImageInfo,
+    done: bool,
+}
+
+impl<'a, 'd> Frames<'a, 'd> {
+    /// The image information read before decoding the first frame.
+    #[inline]
+    pub fn info(&self) -> &ImageInfo {
+        &self.info
+    }
+
+    /// The number of frames of the image, `None` if the codec doesn't know.
+    #[inline]
+    pub fn frame_count(&self) -> Option<u64> {
+        match self.info.frame_count {
+            0 => None,
+            count => Some(count),
+        }
+    }
+}
+
+impl<'a, 'd> Iterator for Frames<'a, 'd> {
+    type Item = Result<(Image, FrameInfo)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.decoder.frame_index();
+        if self.done || self.frame_count().map_or(false, |count| index >= count) {
+            return None;
+        }
+        match self.decoder.read_frame(self.data) {
+            Ok(image) => Some(Ok((image, FrameInfo { index }))),
+            Err(Error::NoMoreData) if index > 0 => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.frame_count() {
+            _ if self.done => (0, Some(0)),
+            Some(count) => {
+                let remaining = count.saturating_sub(self.decoder.frame_index()) as usize;
+                (0, Some(remaining))
+            },
+            None => (0, None),
+        }
+    }
+}
+
+impl ImageDecoder {
+    /// Restarts the decoder and returns an iterator over the frames of the
+    /// image in the given data.
+    ///
+    /// Fails if the image information can't be read from the data.
+    pub fn frames<'a, 'd>(&'a mut self, data: &'d [u8]) -> Result<Frames<'a, 'd>> {
+        self.restart()?;
+        let info = self.read_info(data)?;
+        Ok(Frames {
+            decoder: self,
+            data,
+            info,
+            done: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_frames {
+    use crate::array::Array;
+    use crate::codec::ImageCodec;
+    use crate::geometry::SizeI;
+    use crate::image::Image;
+
+    #[test]
+    fn test_frames_single_frame() {
+        let image = Image::new(4, 3, Default::default()).unwrap();
+        let codecs = ImageCodec::built_in_codecs();
+        let codec = codecs.find_codec_by_name("PNG").unwrap();
+        let mut data = Array::<u8>::new();
+        image.write_to_data(&mut data, codec).unwrap();
+
+        let mut decoder = codec.create_decoder().unwrap();
+        let frames = decoder.frames(&data).unwrap();
+        assert_eq!(frames.info().size, SizeI::new(4, 3));
+        let frames = frames.collect::<Vec<_>>();
+        assert_eq!(frames.len(), 1);
+        let (frame, info) = frames.into_iter().next().unwrap().unwrap();
+        assert_eq!(frame.size(), SizeI::new(4, 3));
+        assert_eq!(info.index, 0);
+    }
+}
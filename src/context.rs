@@ -1,26 +1,49 @@
+mod budget;
+mod builder;
+mod chart;
+mod debug;
+mod pipeline;
+mod recorder;
+mod state_guard;
+mod template;
+
+pub use self::budget::BudgetedContext;
+pub use self::builder::ContextBuilder;
+pub use self::chart::{axis_ticks, snap_to_pixel, AxisOrientation, AxisTick};
+pub use self::debug::DebugOverlayOptions;
+pub use self::pipeline::{PipelineInfo, PipelineKind, SimdLevel};
+pub use self::recorder::RecordingContext;
+pub use self::state_guard::StateGuard;
+pub use self::template::StyleTemplate;
+
 use bitflags::bitflags;
 
+use std::ffi::c_void;
+use std::marker::PhantomData;
 use std::{fmt, ptr};
 
 use crate::array::Array;
-use crate::error::{errcode_to_result, expect_mem_err, Result};
-use crate::font::Font;
+use crate::error::{errcode_to_result, expect_mem_err, Error, Result};
+use crate::font::{ColorGlyphFormats, ColorLayer, Font, TextLayout};
 use crate::font_defs::GlyphRun;
 use crate::geometry::{
-    Arc, BoxD, Chord, Circle, Ellipse, FillRule, GeoViewArray, Geometry, Line, Pie, Point, Rect,
-    RectD, RectI, RoundRect, SizeD, Triangle,
+    Arc, BoxD, Chord, Circle, Ellipse, FillRule, GeoViewArray, Geometry, Line, Pie, Point, PointD,
+    PointI, Rect, RectD, RectI, RoundRect, SizeD, Triangle,
 };
-use crate::gradient::{Gradient, GradientType};
-use crate::image::Image;
+use crate::glyph_buffer::{GlyphBuffer, TextSpacing};
+use crate::gradient::{DynamicGradient, Gradient, GradientType, LinearGradient};
+use crate::image::{scale_prgb32, Image, ImageFormat, PixelsMut};
 use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
 use crate::path::{
     ApproximationOptions, FlattenMode, Path, StrokeCap, StrokeCapPosition, StrokeJoin,
     StrokeOptions, StrokeTransformOrder,
 };
 use crate::pattern::Pattern;
+use crate::rgba::{Rgba32, Rgba64};
+use crate::style::{FillStrokeStyle, Style};
+use crate::util::cast_ref;
 use crate::variant::{BlVariantCore, BlVariantImpl, WrappedBlCore};
 use crate::StyleType;
-use crate::util::cast_ref;
 
 use ffi::BLContextType::*;
 bl_enum! {
@@ -60,6 +83,7 @@ bitflags! {
 
 use ffi::BLContextCreateFlags;
 bitflags! {
+    #[derive(Default)]
     pub struct ContextCreateFlags: u32 {
         const FORCE_THREADS = BLContextCreateFlags::BL_CONTEXT_CREATE_FLAG_FORCE_THREADS as u32;
         const FALLBACK_TO_SYNC = BLContextCreateFlags::BL_CONTEXT_CREATE_FLAG_FALLBACK_TO_SYNC as u32;
@@ -139,7 +163,7 @@ bl_enum! {
     Default => AntiAliasing
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct ContextCreateInfo {
     pub flags: ContextCreateFlags,
     pub thread_count: u32,
@@ -158,12 +182,61 @@ pub struct ContextHints {
     pub pattern_quality: u8,
 }
 
-#[repr(transparent)]
-pub struct Context {
+/// A snapshot of all stroke parameters of a [`Context`].
+///
+/// Retrieved with [`Context::stroke_state`] and applied again with
+/// [`Context::set_stroke_state`].
+///
+/// [`Context::stroke_state`]: struct.Context.html#method.stroke_state
+/// [`Context::set_stroke_state`]: struct.Context.html#method.set_stroke_state
+#[derive(Clone, Debug)]
+pub struct StrokeState {
+    pub width: f64,
+    pub miter_limit: f64,
+    pub join: StrokeJoin,
+    pub start_cap: StrokeCap,
+    pub end_cap: StrokeCap,
+    pub dash_offset: f64,
+    pub dash_array: Array<f64>,
+    pub transform_order: StrokeTransformOrder,
+}
+
+/// A rendering context that renders into an [`Image`].
+///
+/// # A8 Targets
+///
+/// When rendering into an [`ImageFormat::A8`] image only the alpha component
+/// of the composited result is stored. Solid colors contribute their alpha
+/// component, gradients and patterns the alpha component of each of their
+/// pixels, while all color components are ignored. Fill and stroke alphas as
+/// well as the global alpha scale the coverage as usual, so filling with
+/// `0x80_FF_00_00` and with `0x80_00_00_FF` produces the same mask value of
+/// `0x80`. [`set_fill_alpha_value`] and [`set_stroke_alpha_value`] can be used
+/// to set a style from a mask value directly.
+///
+/// [`set_fill_alpha_value`]: #method.set_fill_alpha_value
+/// [`set_stroke_alpha_value`]: #method.set_stroke_alpha_value
+///
+/// # Asynchronous Rendering
+///
+/// A context created with [`ContextCreateFlags::FORCE_THREADS`] may queue
+/// render commands and execute them on worker threads, so the target image
+/// can still be written to after a render call returned. The context
+/// therefore mutably borrows its target for its whole lifetime, the target
+/// can only be accessed again after the context has been ended or dropped,
+/// both of which wait for all queued commands to finish. [`flush`] and
+/// [`sync`] can be used to wait for queued commands while keeping the
+/// context attached.
+///
+/// [`flush`]: #method.flush
+/// [`sync`]: #method.sync
+pub struct Context<'a> {
     core: ffi::BLContextCore,
+    saved_state_limit: Option<usize>,
+    _pd: PhantomData<&'a mut Image>,
 }
 
-impl fmt::Debug for Context {
+impl fmt::Debug for Context<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Context")
             .field("target_size", &self.target_size())
@@ -174,26 +247,40 @@ impl fmt::Debug for Context {
     }
 }
 
-unsafe impl WrappedBlCore for Context {
+unsafe impl WrappedBlCore for Context<'_> {
     type Core = ffi::BLContextCore;
     const IMPL_TYPE_INDEX: usize = crate::variant::ImplType::Context as usize;
 
     #[inline]
     fn from_core(core: Self::Core) -> Self {
-        Context { core }
+        Context {
+            core,
+            saved_state_limit: None,
+            _pd: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn core(&self) -> &Self::Core {
+        &self.core
+    }
+
+    #[inline]
+    fn core_mut(&mut self) -> &mut Self::Core {
+        &mut self.core
     }
 }
 
-impl Context {
+impl<'a> Context<'a> {
     /// Creates a new context that renders to the given [`Image`].
     #[inline]
-    pub fn new(target: &mut Image) -> Result<Context> {
+    pub fn new(target: &'a mut Image) -> Result<Context<'a>> {
         Self::new_with_options(target, None)
     }
 
     /// Creates a new context with optional creation info that renders to the
     /// given [`Image`].
-    pub fn new_with_options<C>(target: &mut Image, info: C) -> Result<Context>
+    pub fn new_with_options<C>(target: &'a mut Image, info: C) -> Result<Context<'a>>
     where
         C: Into<Option<ContextCreateInfo>>,
     {
@@ -209,8 +296,8 @@ impl Context {
                 this.core_mut(),
                 target.core_mut(),
                 info.as_ref().map_or(ptr::null(), |ptr| ptr as *const _),
-            ))
-            .map(|_| this)
+            ))?;
+            Ok(this)
         }
     }
 
@@ -249,10 +336,22 @@ impl Context {
         unsafe { errcode_to_result(ffi::blContextEnd(self.core_mut())) }
     }
 
-    /*#[inline]
-    pub fn flush(&mut self, flags: ContextFlushFlags) {
-        unsafe { ffi::blContextFlush(self.core_mut(), flags.bits()) };
-    }*/
+    /// Flushes the queued render commands of this context.
+    ///
+    /// With [`ContextFlushFlags::FLUSH_SYNC`] this waits until all commands
+    /// have been executed.
+    #[inline]
+    pub fn flush(&mut self, flags: ContextFlushFlags) -> Result<()> {
+        unsafe { errcode_to_result(ffi::blContextFlush(self.core_mut(), flags.bits())) }
+    }
+
+    /// Waits until all queued render commands have been executed.
+    ///
+    /// This is a no-op for synchronous contexts.
+    #[inline]
+    pub fn sync(&mut self) -> Result<()> {
+        self.flush(ContextFlushFlags::FLUSH_SYNC)
+    }
 
     /// Returns the number of saved states in the context (0 means no saved
     /// states).
@@ -261,7 +360,23 @@ impl Context {
         self.state().savedStateCount
     }
 
+    /// The maximum number of states that can be saved at once with
+    /// [`try_save`] and [`try_save_cookie`], as given by
+    /// [`ContextBuilder::saved_state_limit`].
+    ///
+    /// [`try_save`]: #method.try_save
+    /// [`try_save_cookie`]: #method.try_save_cookie
+    /// [`ContextBuilder::saved_state_limit`]: struct.ContextBuilder.html#method.saved_state_limit
+    #[inline]
+    pub fn saved_state_limit(&self) -> Option<usize> {
+        self.saved_state_limit
+    }
+
     /// Saves the current rendering context state.
+    ///
+    /// This ignores the saved state limit, see [`try_save`].
+    ///
+    /// [`try_save`]: #method.try_save
     #[inline]
     pub fn save(&mut self) {
         unsafe { expect_mem_err(ffi::blContextSave(self.core_mut(), ptr::null_mut())) };
@@ -269,6 +384,10 @@ impl Context {
 
     /// Saves the current rendering context state and creates a restoration
     /// [`ContextCookie`].
+    ///
+    /// This ignores the saved state limit, see [`try_save_cookie`].
+    ///
+    /// [`try_save_cookie`]: #method.try_save_cookie
     #[inline]
     pub fn save_cookie(&mut self) -> ContextCookie {
         unsafe {
@@ -281,6 +400,39 @@ impl Context {
         }
     }
 
+    /// Saves the current rendering context state.
+    ///
+    /// Returns [`Error::TooManyStates`] if the saved state limit of the
+    /// context is reached.
+    ///
+    /// [`Error::TooManyStates`]: ../error/enum.Error.html#variant.TooManyStates
+    #[inline]
+    pub fn try_save(&mut self) -> Result<()> {
+        self.check_saved_state_limit()?;
+        self.save();
+        Ok(())
+    }
+
+    /// Saves the current rendering context state and creates a restoration
+    /// [`ContextCookie`].
+    ///
+    /// Returns [`Error::TooManyStates`] if the saved state limit of the
+    /// context is reached.
+    ///
+    /// [`Error::TooManyStates`]: ../error/enum.Error.html#variant.TooManyStates
+    pub fn try_save_cookie(&mut self) -> Result<ContextCookie> {
+        self.check_saved_state_limit()?;
+        Ok(self.save_cookie())
+    }
+
+    #[inline]
+    fn check_saved_state_limit(&self) -> Result<()> {
+        match self.saved_state_limit {
+            Some(limit) if self.saved_state_count() >= limit => Err(Error::TooManyStates { limit }),
+            _ => Ok(()),
+        }
+    }
+
     /// Restores the top-most saved context-state.
     #[inline]
     pub fn restore(&mut self) -> Result<()> {
@@ -305,11 +457,60 @@ impl Context {
     where
         F: FnOnce(&mut Self) -> Result<()>,
     {
-        let cookie = self.save_cookie();
+        let cookie = self.try_save_cookie()?;
         f(self)?;
         self.restore_cookie(cookie)
     }
 
+    /// Runs the given closure while preserving the current context-state and
+    /// returns its result.
+    ///
+    /// Unlike with [`with_pushed_context`] the closure may return any value
+    /// and the state is restored regardless of it, so a closure returning a
+    /// [`Result`] has its error returned inside the `Ok`.
+    ///
+    /// [`with_pushed_context`]: #method.with_pushed_context
+    pub fn with_state<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let cookie = self.try_save_cookie()?;
+        let value = f(self);
+        self.restore_cookie(cookie).map(|_| value)
+    }
+
+    /// Runs the given closure with the composition operator set to `comp_op`
+    /// and restores the previous operator afterwards.
+    ///
+    /// This is cheaper than saving and restoring the whole state, changes to
+    /// other parts of the state made by the closure are kept.
+    pub fn with_comp_op<F, T>(&mut self, comp_op: CompOp, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let previous = self.comp_op();
+        self.set_comp_op(comp_op);
+        let value = f(self);
+        self.set_comp_op(previous);
+        value
+    }
+
+    /// Runs the given closure with the global alpha set to `alpha` and
+    /// restores the previous global alpha afterwards.
+    ///
+    /// This is cheaper than saving and restoring the whole state, changes to
+    /// other parts of the state made by the closure are kept.
+    pub fn with_alpha<F, T>(&mut self, alpha: f64, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let previous = self.global_alpha();
+        self.set_global_alpha(alpha);
+        let value = f(self);
+        self.set_global_alpha(previous);
+        value
+    }
+
     #[inline]
     pub fn meta_matrix(&self) -> &Matrix2D {
         unsafe { cast_ref(&self.state().metaMatrix) }
@@ -400,8 +601,7 @@ impl Context {
     }
 }
 
-// FIXME? make functions generic over a Stroke/FillStyle trait?
-impl Context {
+impl Context<'_> {
     #[inline]
     pub fn fill_rule(&self) -> FillRule {
         u32::from(self.state().fillRule).into()
@@ -449,13 +649,24 @@ impl Context {
     }
 
     #[inline]
-    pub fn set_fill_style_rgba32(&mut self, color: u32) {
-        unsafe { ffi::blContextSetFillStyleRgba32(self.core_mut(), color) };
+    pub fn set_fill_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetFillStyleRgba32(self.core_mut(), color.into().value) };
+    }
+
+    #[inline]
+    pub fn set_fill_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetFillStyleRgba64(self.core_mut(), color.into().value) };
     }
 
+    /// Sets the fill style to a solid color whose alpha component is the
+    /// given `value`.
+    ///
+    /// This is meant for [`ImageFormat::A8`] targets, see
+    /// [A8 Targets](struct.Context.html#a8-targets). On targets with color
+    /// components this fills with white at the given opacity.
     #[inline]
-    pub fn set_fill_style_rgba64(&mut self, color: u64) {
-        unsafe { ffi::blContextSetFillStyleRgba64(self.core_mut(), color) };
+    pub fn set_fill_alpha_value(&mut self, value: u8) {
+        self.set_fill_style_rgba32(alpha_value_rgba32(value));
     }
 
     #[inline]
@@ -473,9 +684,33 @@ impl Context {
             errcode_to_result(ffi::blContextGetFillStyleRgba64(self.core(), &mut out)).map(|_| out)
         }
     }
+
+    /// Returns the current fill style, or `None` if no fill style is set.
+    pub fn fill_style(&self) -> Option<Style> {
+        self.style(ContextOpType::Fill)
+    }
+
+    /// Sets the fill style to the given color, gradient, pattern or
+    /// [`Style`].
+    ///
+    /// [`Style`]: ../style/enum.Style.html
+    #[inline]
+    pub fn set_fill_style<S: FillStrokeStyle + ?Sized>(&mut self, style: &S) {
+        style.apply_style(self, ContextOpType::Fill);
+    }
+
+    /// Sets the fill style from a CSS color or `linear-gradient()`, see
+    /// [`Style::from_css`]. Gradients are laid out for the whole target image.
+    ///
+    /// [`Style::from_css`]: ../style/enum.Style.html#method.from_css
+    pub fn set_fill_css(&mut self, value: &str) -> Result<()> {
+        let style = self.css_style(value)?;
+        self.set_fill_style(&style);
+        Ok(())
+    }
 }
 
-impl Context {
+impl Context<'_> {
     #[inline]
     pub fn stroke_alpha(&self) -> f64 {
         self.state().styleAlpha[ContextOpType::Stroke as usize]
@@ -512,13 +747,24 @@ impl Context {
     }
 
     #[inline]
-    pub fn set_stroke_style_rgba32(&mut self, color: u32) {
-        unsafe { ffi::blContextSetStrokeStyleRgba32(self.core_mut(), color) };
+    pub fn set_stroke_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetStrokeStyleRgba32(self.core_mut(), color.into().value) };
     }
 
     #[inline]
-    pub fn set_stroke_style_rgba64(&mut self, color: u64) {
-        unsafe { ffi::blContextSetStrokeStyleRgba64(self.core_mut(), color) };
+    pub fn set_stroke_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetStrokeStyleRgba64(self.core_mut(), color.into().value) };
+    }
+
+    /// Sets the stroke style to a solid color whose alpha component is the
+    /// given `value`.
+    ///
+    /// This is meant for [`ImageFormat::A8`] targets, see
+    /// [A8 Targets](struct.Context.html#a8-targets). On targets with color
+    /// components this strokes with white at the given opacity.
+    #[inline]
+    pub fn set_stroke_alpha_value(&mut self, value: u8) {
+        self.set_stroke_style_rgba32(alpha_value_rgba32(value));
     }
 
     #[inline]
@@ -539,6 +785,65 @@ impl Context {
         }
     }
 
+    /// Returns the current stroke style, or `None` if no stroke style is set.
+    pub fn stroke_style(&self) -> Option<Style> {
+        self.style(ContextOpType::Stroke)
+    }
+
+    /// Sets the stroke style to the given color, gradient, pattern or
+    /// [`Style`].
+    ///
+    /// [`Style`]: ../style/enum.Style.html
+    #[inline]
+    pub fn set_stroke_style<S: FillStrokeStyle + ?Sized>(&mut self, style: &S) {
+        style.apply_style(self, ContextOpType::Stroke);
+    }
+
+    /// Sets the stroke style from a CSS color or `linear-gradient()`, see
+    /// [`Style::from_css`]. Gradients are laid out for the whole target image.
+    ///
+    /// [`Style::from_css`]: ../style/enum.Style.html#method.from_css
+    pub fn set_stroke_css(&mut self, value: &str) -> Result<()> {
+        let style = self.css_style(value)?;
+        self.set_stroke_style(&style);
+        Ok(())
+    }
+
+    fn css_style(&self, value: &str) -> Result<Style> {
+        let size = self.target_size();
+        Style::from_css(value, &RectD::new(0.0, 0.0, size.w, size.h))
+    }
+
+    /// Returns a reference counted copy of the style of the given operation.
+    fn style(&self, op: ContextOpType) -> Option<Style> {
+        let style_type: StyleType = u32::from(self.state().styleType[op as usize]).into();
+        let get_style = |out: *mut c_void| unsafe {
+            match op {
+                ContextOpType::Fill => ffi::blContextGetFillStyle(self.core(), out as *mut _),
+                ContextOpType::Stroke => ffi::blContextGetStrokeStyle(self.core(), out as *mut _),
+            }
+        };
+        match style_type {
+            StyleType::None => None,
+            StyleType::Solid => match op {
+                ContextOpType::Fill => self.get_fill_style_rgba64(),
+                ContextOpType::Stroke => self.get_stroke_style_rgba64(),
+            }
+            .ok()
+            .map(Style::Solid),
+            StyleType::Gradient => {
+                let mut core = *LinearGradient::none();
+                get_style(&mut core as *mut _ as *mut c_void);
+                Some(Style::Gradient(DynamicGradient::from_core(core)))
+            },
+            StyleType::Pattern => {
+                let mut pattern = Pattern::from_core(*Pattern::none());
+                get_style(pattern.core_mut() as *mut _ as *mut c_void);
+                Some(Style::Pattern(pattern))
+            },
+        }
+    }
+
     #[inline]
     pub fn stroke_options(&self) -> &StrokeOptions {
         unsafe { cast_ref(&self.state().strokeOptions) }
@@ -638,10 +943,76 @@ impl Context {
     pub fn set_stroke_options(&mut self, opts: &StrokeOptions) {
         unsafe { ffi::blContextSetStrokeOptions(self.core_mut(), &opts.core) };
     }
+
+    /// Returns all stroke parameters of this context at once.
+    pub fn stroke_state(&self) -> StrokeState {
+        let opts = self.stroke_options();
+        StrokeState {
+            width: opts.width(),
+            miter_limit: opts.miter_limit(),
+            join: opts.join(),
+            start_cap: opts.start_cap(),
+            end_cap: opts.end_cap(),
+            dash_offset: opts.dash_offset(),
+            dash_array: opts.dash_array().clone(),
+            transform_order: opts.transform_order(),
+        }
+    }
+
+    /// Applies all stroke parameters of the given [`StrokeState`].
+    pub fn set_stroke_state(&mut self, state: &StrokeState) {
+        self.set_stroke_width(state.width);
+        self.set_stroke_miter_limit(state.miter_limit);
+        self.set_stroke_join(state.join);
+        self.set_stroke_start_cap(state.start_cap);
+        self.set_stroke_end_cap(state.end_cap);
+        self.set_stroke_dash_offset(state.dash_offset);
+        self.set_stroke_dash_array(&state.dash_array);
+        self.set_stroke_transform_order(state.transform_order);
+    }
+
+    /// Multiplies the stroke width by the given factor.
+    ///
+    /// The dash offset and dash array are scaled as well so that dashes keep
+    /// their proportions relative to the stroke width.
+    pub fn scale_stroke_width(&mut self, factor: f64) {
+        let state = self.stroke_state();
+        self.set_stroke_width(state.width * factor);
+        if !state.dash_array.is_empty() {
+            let dashes: Vec<f64> = state.dash_array.iter().map(|dash| dash * factor).collect();
+            self.set_stroke_dash_offset(state.dash_offset * factor);
+            self.set_stroke_dash_array(&Array::from(dashes));
+        }
+    }
+
+    /// Sets the stroke width so that strokes are `width` device pixels wide
+    /// regardless of the current transformation.
+    ///
+    /// With [`StrokeTransformOrder::Before`] the stroke width is already
+    /// specified in device space and used as is. With
+    /// [`StrokeTransformOrder::After`] the stroke is transformed by the user
+    /// and meta matrices, so the width is divided by their average scale
+    /// factor. Non-uniform scaling or skewing still distorts the stroke in
+    /// that case.
+    pub fn set_device_stroke_width(&mut self, width: f64) {
+        let width = match self.stroke_transform_order() {
+            StrokeTransformOrder::Before => width,
+            StrokeTransformOrder::After => {
+                let det = self.user_matrix().determinant() * self.meta_matrix().determinant();
+                let scale = det.abs().sqrt();
+                if scale > 0.0 && scale.is_finite() {
+                    width / scale
+                } else {
+                    width
+                }
+            },
+        };
+        self.set_stroke_width(width);
+    }
 }
 
 /// Clip Operations
-impl Context {
+impl Context<'_> {
     #[inline]
     pub fn restore_clipping(&mut self) {
         unsafe { ffi::blContextRestoreClipping(self.core_mut()) };
@@ -656,10 +1027,118 @@ impl Context {
     pub fn clip_to(&mut self, x: f64, y: f64, w: f64, h: f64) {
         self.clip_to_rect(&RectD { x, y, w, h });
     }
+
+    /// Renders what `draw` draws clipped to the given path filled with
+    /// `fill_rule`.
+    ///
+    /// Blend2D only supports rectangular clipping, so path clipping is
+    /// emulated: `draw` renders into a transparent layer of the target's size
+    /// using a context that has this context's transformation, fill and stroke
+    /// styles and stroke parameters. The layer is masked by an
+    /// [`ImageFormat::A8`] coverage mask of the path and then blitted onto
+    /// this context, respecting its composition operator, global alpha and
+    /// rectangular clip.
+    pub fn clip_to_path<F>(&mut self, path: &Path, fill_rule: FillRule, draw: F) -> Result<()>
+    where
+        F: FnOnce(&mut Context<'_>) -> Result<()>,
+    {
+        let size = self.target_size();
+        let (w, h) = (size.w.ceil() as i32, size.h.ceil() as i32);
+        let mut matrix = *self.user_matrix();
+        matrix.post_transform(self.meta_matrix());
+
+        let mut mask = Image::new(w, h, ImageFormat::A8)?;
+        {
+            let mut ctx = Context::new(&mut mask)?;
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.clear_all()?;
+            ctx.set_comp_op(CompOp::SrcOver);
+            ctx.set_matrix(&matrix);
+            ctx.set_fill_rule(fill_rule);
+            ctx.set_fill_alpha_value(0xFF);
+            ctx.fill_path(path)?;
+            ctx.end()?;
+        }
+
+        let mut layer = self.render_layer(draw)?;
+        layer.apply_mask(&mask, PointI::ZERO)?;
+        self.blit_layer(&layer)
+    }
+
+    /// Renders what `draw` draws into a separate layer that is then
+    /// composited onto this context as a whole with the given composition
+    /// operator and alpha.
+    ///
+    /// This emulates group opacity and layer blend modes: overlapping shapes
+    /// drawn into the layer are blended with each other first, so that for
+    /// example two overlapping half transparent shapes don't show their
+    /// overlap. The layer has the target's size and `draw` renders into it
+    /// like [`clip_to_path`] describes, the alpha is combined with this
+    /// context's global alpha.
+    ///
+    /// Returns [`Error::InvalidValue`] without calling `draw` if `alpha` is
+    /// NaN.
+    ///
+    /// [`clip_to_path`]: #method.clip_to_path
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn with_layer<F>(&mut self, comp_op: CompOp, alpha: f64, draw: F) -> Result<()>
+    where
+        F: FnOnce(&mut Context<'_>) -> Result<()>,
+    {
+        if alpha.is_nan() {
+            return Err(Error::InvalidValue);
+        }
+        let layer = self.render_layer(draw)?;
+        let cookie = self.save_cookie();
+        self.set_comp_op(comp_op);
+        self.set_global_alpha(self.global_alpha() * alpha.max(0.0).min(1.0));
+        let result = self.blit_layer(&layer);
+        self.restore_cookie(cookie).and(result)
+    }
+
+    /// Renders `draw` into a transparent layer of the target's size with this
+    /// context's transformation and style state.
+    fn render_layer<F>(&self, draw: F) -> Result<Image>
+    where
+        F: FnOnce(&mut Context<'_>) -> Result<()>,
+    {
+        let size = self.target_size();
+        let mut matrix = *self.user_matrix();
+        matrix.post_transform(self.meta_matrix());
+        let mut template = self.style_template();
+        template.comp_op = CompOp::SrcOver;
+        template.global_alpha = 1.0;
+
+        let mut layer = Image::new(
+            size.w.ceil() as i32,
+            size.h.ceil() as i32,
+            ImageFormat::PRgb32,
+        )?;
+        {
+            let mut ctx = Context::new(&mut layer)?;
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.clear_all()?;
+            ctx.apply_style_template(&template);
+            ctx.set_matrix(&matrix);
+            draw(&mut ctx)?;
+            ctx.end()?;
+        }
+        Ok(layer)
+    }
+
+    /// Blits a layer that is in device space already onto this context.
+    fn blit_layer(&mut self, layer: &Image) -> Result<()> {
+        // undo the meta matrix as well
+        let device = self.meta_matrix().inverted().ok_or(Error::InvalidValue)?;
+        let cookie = self.save_cookie();
+        self.set_matrix(&device);
+        let result = self.blit_image(&PointI::ZERO, layer, None);
+        self.restore_cookie(cookie).and(result)
+    }
 }
 
 /// Clear Operations
-impl Context {
+impl Context<'_> {
     #[inline]
     pub fn clear_all(&mut self) -> Result<()> {
         unsafe { errcode_to_result(ffi::blContextClearAll(self.core_mut())) }
@@ -697,6 +1176,62 @@ impl Context {
         }
     }
 
+    /// Blits the image at `dst` with the given `opacity`, optionally
+    /// multiplying its pixels with the non-premultiplied `tint` color in the
+    /// `0xAARRGGBB` format.
+    ///
+    /// The opacity is combined with the current global alpha, the context
+    /// state is left untouched afterwards. Tinting is applied to a temporary
+    /// copy of the source area, the source image itself is not modified.
+    ///
+    /// Returns [`Error::InvalidValue`] if `opacity` is NaN.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn blit_image_with<'r, P, RI, T>(
+        &mut self,
+        dst: &P,
+        src: &Image,
+        src_area: RI,
+        opacity: f64,
+        tint: T,
+    ) -> Result<()>
+    where
+        P: Point,
+        RI: Into<Option<&'r RectI>>,
+        T: Into<Option<u32>>,
+    {
+        if opacity.is_nan() {
+            return Err(Error::InvalidValue);
+        }
+        let tinted;
+        let (src, src_area) = match tint.into() {
+            Some(tint) => {
+                tinted = tinted_image(src, src_area.into(), tint)?;
+                (&tinted, None)
+            },
+            None => (src, src_area.into()),
+        };
+        let cookie = self.save_cookie();
+        self.set_global_alpha(self.global_alpha() * opacity.max(0.0).min(1.0));
+        let result = self.blit_image(dst, src, src_area);
+        self.restore_cookie(cookie).and(result)
+    }
+
+    /// Blits the image at `dst` clipped to the given [`ImageFormat::A8`]
+    /// mask, which is placed at `dst` as well.
+    ///
+    /// Blend2D only supports rectangular clipping, so this is how content can
+    /// be clipped softly: render it into a separate image and blit that image
+    /// through the mask. The masking is applied to a temporary copy of the
+    /// source, see [`Image::apply_mask`].
+    ///
+    /// [`Image::apply_mask`]: ../image/struct.Image.html#method.apply_mask
+    pub fn blit_masked_image(&mut self, dst: &PointI, src: &Image, mask: &Image) -> Result<()> {
+        let mut layer = prgb32_copy(src, None)?;
+        layer.apply_mask(mask, PointI::ZERO)?;
+        self.blit_image(dst, &layer, None)
+    }
+
     pub fn blit_scaled_image<'r, R, RI>(&mut self, dst: &R, src: &Image, src_area: RI) -> Result<()>
     where
         R: Rect,
@@ -716,7 +1251,7 @@ impl Context {
 }
 
 /// Fill Operations
-impl Context {
+impl Context<'_> {
     pub fn fill_geometry<T: Geometry + ?Sized>(&mut self, geo: &T) -> Result<()> {
         unsafe {
             errcode_to_result(ffi::blContextFillGeometry(
@@ -727,6 +1262,24 @@ impl Context {
         }
     }
 
+    /// Fills the given [`ImageFormat::A8`] mask at `dst` with the current
+    /// fill style, using each mask value as coverage.
+    ///
+    /// Only solid fill styles are supported, the error of
+    /// [`get_fill_style_rgba32`] is returned otherwise. [`Error::InvalidValue`]
+    /// is returned if the mask is not an [`ImageFormat::A8`] image.
+    ///
+    /// [`get_fill_style_rgba32`]: #method.get_fill_style_rgba32
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn fill_mask<'r, RI>(&mut self, dst: &PointI, mask: &Image, mask_area: RI) -> Result<()>
+    where
+        RI: Into<Option<&'r RectI>>,
+    {
+        let color = self.get_fill_style_rgba32()?;
+        let layer = masked_color_image(mask, mask_area.into(), color)?;
+        self.blit_image_with(dst, &layer, None, self.fill_alpha(), None)
+    }
+
     #[inline]
     pub fn fill_all(&mut self) -> Result<()> {
         unsafe { errcode_to_result(ffi::blContextFillAll(self.core_mut())) }
@@ -782,6 +1335,12 @@ impl Context {
         self.fill_geometry(&Triangle { x0, y0, x1, y1, x2, y2 })
     }
 
+    /// Fills the given path.
+    ///
+    /// Open figures are implicitly closed when filled, see
+    /// [`Path::close_all_figures`] to close them explicitly.
+    ///
+    /// [`Path::close_all_figures`]: ../path/struct.Path.html#method.close_all_figures
     #[inline]
     pub fn fill_path(&mut self, p: &Path) -> Result<()> {
         self.fill_geometry(p)
@@ -810,17 +1369,78 @@ impl Context {
     #[inline]
     pub fn fill_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) -> Result<()> {
         unsafe {
-            errcode_to_result(P::FILL_TEXT(
-                self.core_mut(),
-                &dst as *const _ as *const _,
-                font.core(),
+            self.fill_text_raw(
+                dst,
+                font,
                 text.as_bytes().as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
-            ))
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8,
+            )
         }
     }
 
+    /// Shapes the text and fills it with the given additional letter and
+    /// word spacing.
+    pub fn fill_utf8_text_with_spacing<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        text: &str,
+        spacing: &TextSpacing,
+    ) -> Result<()> {
+        let mut buf = GlyphBuffer::from_utf8_text(text);
+        font.shape(&mut buf)?;
+        buf.apply_spacing(text, font, spacing)?;
+        self.fill_glyph_buffer(dst, font, &buf)
+    }
+
+    /// Fills UTF-16 encoded text.
+    #[inline]
+    pub fn fill_utf16_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u16]) -> Result<()> {
+        unsafe {
+            self.fill_text_raw(
+                dst,
+                font,
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16,
+            )
+        }
+    }
+
+    /// Fills UTF-32 encoded text.
+    #[inline]
+    pub fn fill_utf32_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u32]) -> Result<()> {
+        unsafe {
+            self.fill_text_raw(
+                dst,
+                font,
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32,
+            )
+        }
+    }
+
+    #[inline]
+    unsafe fn fill_text_raw<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        text: *const c_void,
+        len: usize,
+        encoding: ffi::BLTextEncoding::Type,
+    ) -> Result<()> {
+        errcode_to_result(P::FILL_TEXT(
+            self.core_mut(),
+            &dst as *const _ as *const _,
+            font.core(),
+            text,
+            len,
+            encoding as u32,
+        ))
+    }
+
     #[inline]
     pub fn fill_glyph_run<P: Point>(
         &mut self,
@@ -837,10 +1457,117 @@ impl Context {
             ))
         }
     }
+
+    /// Fills the glyphs of the given [`GlyphBuffer`], usually after it has been
+    /// shaped with [`Font::shape`].
+    ///
+    /// [`Font::shape`]: ../font/struct.Font.html#method.shape
+    #[inline]
+    pub fn fill_glyph_buffer<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        glyph_buffer: &GlyphBuffer,
+    ) -> Result<()> {
+        self.fill_glyph_run(dst, font, glyph_buffer.glyph_run())
+    }
+
+    /// Fills all runs of the given [`TextLayout`], which must have been laid
+    /// out with the given font.
+    ///
+    /// [`TextLayout`]: ../font/struct.TextLayout.html
+    pub fn fill_text_layout(&mut self, layout: &TextLayout, font: &Font) -> Result<()> {
+        for run in layout.lines().iter().flat_map(|line| line.runs()) {
+            self.fill_glyph_buffer(run.origin(), font, run.glyphs())?;
+        }
+        Ok(())
+    }
+
+    /// Fills the glyphs of a shaped glyph buffer like [`fill_glyph_buffer`],
+    /// rendering `COLR` color glyphs with the colors of the given palette.
+    ///
+    /// Color layers without a color, and glyphs that aren't color glyphs,
+    /// are filled with the current fill style. Embedded bitmaps and SVG
+    /// glyphs are not supported, see [`FontFace::color_glyph_formats`].
+    ///
+    /// [`fill_glyph_buffer`]: #method.fill_glyph_buffer
+    /// [`FontFace::color_glyph_formats`]: ../font/struct.FontFace.html#method.color_glyph_formats
+    pub fn fill_color_glyph_buffer(
+        &mut self,
+        dst: PointD,
+        font: &Font,
+        glyph_buffer: &GlyphBuffer,
+        palette: usize,
+    ) -> Result<()> {
+        let face = font.face();
+        if !face.color_glyph_formats().contains(ColorGlyphFormats::COLR) {
+            return self.fill_glyph_buffer(dst, font, glyph_buffer);
+        }
+        let foreground = self.fill_style();
+        let [m00, m01, m10, m11] = font.font_matrix().0;
+        let to_user = |p: PointI| {
+            let (x, y) = (f64::from(p.x), f64::from(p.y));
+            PointD::new(x * m00 + y * m10, x * m01 + y * m11)
+        };
+        let mut pen = dst;
+        let mut path = Path::new();
+        self.with_state(|ctx| -> Result<()> {
+            for item in glyph_buffer.glyph_run().iter() {
+                let placement = item.placement.unwrap_or_default();
+                let offset = to_user(placement.placement);
+                let origin = Matrix2D::translation(pen.x + offset.x, pen.y + offset.y);
+                let layers = face
+                    .color_layers(item.glyph_id, palette)
+                    .unwrap_or_else(|| {
+                        vec![ColorLayer {
+                            glyph_id: item.glyph_id,
+                            color: None,
+                        }]
+                    });
+                for layer in layers {
+                    path.clear();
+                    font.get_glyph_outlines(layer.glyph_id, &origin, &mut path)?;
+                    match (layer.color, &foreground) {
+                        (Some(color), _) => ctx.set_fill_style_rgba32(color),
+                        (None, Some(style)) => ctx.set_fill_style(style),
+                        (None, None) => continue,
+                    }
+                    ctx.fill_path(&path)?;
+                }
+                let advance = to_user(placement.advance);
+                pen.x += advance.x;
+                pen.y += advance.y;
+            }
+            Ok(())
+        })?
+    }
+
+    /// Fills many short texts with the same font, each with its baseline
+    /// starting at the given point.
+    ///
+    /// This reuses a single [`GlyphBuffer`] for shaping and doesn't touch the
+    /// state of the context, which makes it cheaper than calling
+    /// [`fill_utf8_text`] for every text, for example for the tick labels of
+    /// a chart. Stops at the first text that fails to render.
+    ///
+    /// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+    /// [`fill_utf8_text`]: #method.fill_utf8_text
+    pub fn fill_texts(&mut self, font: &Font, items: &[(PointD, &str)]) -> Result<()> {
+        let mut buf = GlyphBuffer::new();
+        for &(dst, text) in items {
+            if text.is_empty() {
+                continue;
+            }
+            buf.set_utf8_text(text);
+            font.shape(&mut buf)?;
+            self.fill_glyph_buffer(dst, font, &buf)?;
+        }
+        Ok(())
+    }
 }
 
 /// Stroke Operations
-impl Context {
+impl Context<'_> {
     pub fn stroke_geometry<T: Geometry + ?Sized>(&mut self, geo: &T) -> Result<()> {
         unsafe {
             errcode_to_result(ffi::blContextStrokeGeometry(
@@ -947,20 +1674,107 @@ impl Context {
         self.stroke_geometry(slice.as_ref())
     }
 
+    /// Strokes the geometry with the given dash pattern, starting `phase`
+    /// units into the pattern, and restores the previous dash array and
+    /// offset afterwards.
+    pub fn stroke_geometry_dashed<T: Geometry + ?Sized>(
+        &mut self,
+        geo: &T,
+        dash: &[f64],
+        phase: f64,
+    ) -> Result<()> {
+        let (dash_array, dash_offset) =
+            (self.stroke_dash_array().clone(), self.stroke_dash_offset());
+        self.set_stroke_dash_array(&Array::from(dash));
+        self.set_stroke_dash_offset(phase);
+        let result = self.stroke_geometry(geo);
+        self.set_stroke_dash_array(&dash_array);
+        self.set_stroke_dash_offset(dash_offset);
+        result
+    }
+
+    /// Strokes the outline of the rectangle dashed, see
+    /// [`stroke_geometry_dashed`](#method.stroke_geometry_dashed).
+    #[inline]
+    pub fn stroke_rect_dashed<R>(&mut self, rect: &R, dash: &[f64], phase: f64) -> Result<()>
+    where
+        R: Rect + Geometry,
+    {
+        self.stroke_geometry_dashed(rect, dash, phase)
+    }
+
+    /// Strokes the outline of the ellipse dashed, see
+    /// [`stroke_geometry_dashed`](#method.stroke_geometry_dashed).
+    #[inline]
+    pub fn stroke_ellipse_dashed(
+        &mut self,
+        ellipse: &Ellipse,
+        dash: &[f64],
+        phase: f64,
+    ) -> Result<()> {
+        self.stroke_geometry_dashed(ellipse, dash, phase)
+    }
+
     #[inline]
     pub fn stroke_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) -> Result<()> {
         unsafe {
-            errcode_to_result(P::STROKE_TEXT(
-                self.core_mut(),
-                &dst as *const _ as *const _,
-                font.core(),
+            self.stroke_text_raw(
+                dst,
+                font,
                 text.as_bytes().as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
-            ))
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8,
+            )
         }
     }
 
+    /// Strokes UTF-16 encoded text.
+    #[inline]
+    pub fn stroke_utf16_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u16]) -> Result<()> {
+        unsafe {
+            self.stroke_text_raw(
+                dst,
+                font,
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16,
+            )
+        }
+    }
+
+    /// Strokes UTF-32 encoded text.
+    #[inline]
+    pub fn stroke_utf32_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u32]) -> Result<()> {
+        unsafe {
+            self.stroke_text_raw(
+                dst,
+                font,
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32,
+            )
+        }
+    }
+
+    #[inline]
+    unsafe fn stroke_text_raw<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        text: *const c_void,
+        len: usize,
+        encoding: ffi::BLTextEncoding::Type,
+    ) -> Result<()> {
+        errcode_to_result(P::STROKE_TEXT(
+            self.core_mut(),
+            &dst as *const _ as *const _,
+            font.core(),
+            text,
+            len,
+            encoding as u32,
+        ))
+    }
+
     #[inline]
     pub fn stroke_glyph_run<P: Point>(
         &mut self,
@@ -977,9 +1791,95 @@ impl Context {
             ))
         }
     }
+
+    /// Strokes the glyphs of the given [`GlyphBuffer`], usually after it has been
+    /// shaped with [`Font::shape`].
+    ///
+    /// [`Font::shape`]: ../font/struct.Font.html#method.shape
+    #[inline]
+    pub fn stroke_glyph_buffer<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        glyph_buffer: &GlyphBuffer,
+    ) -> Result<()> {
+        self.stroke_glyph_run(dst, font, glyph_buffer.glyph_run())
+    }
+}
+
+/// A white color with the given alpha component.
+#[inline]
+fn alpha_value_rgba32(value: u8) -> u32 {
+    u32::from(value) << 24 | 0x00_FF_FF_FF
 }
 
-impl MatrixTransform for Context {
+/// Copies the given area of `src` into a new [`ImageFormat::PRgb32`] image.
+fn prgb32_copy(src: &Image, area: Option<&RectI>) -> Result<Image> {
+    let area = area.copied().unwrap_or(RectI {
+        x: 0,
+        y: 0,
+        w: src.width(),
+        h: src.height(),
+    });
+    let mut image = Image::new(area.w, area.h, ImageFormat::PRgb32)?;
+    {
+        let mut ctx = Context::new(&mut image)?;
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.clear_all()?;
+        ctx.blit_image(&PointI { x: 0, y: 0 }, src, &area)?;
+        ctx.end()?;
+    }
+    Ok(image)
+}
+
+/// Copies the given area of `src` into a new [`ImageFormat::PRgb32`] image
+/// and multiplies its premultiplied pixels with the `tint` color.
+fn tinted_image(src: &Image, area: Option<&RectI>, tint: u32) -> Result<Image> {
+    let mut image = prgb32_copy(src, area)?;
+    let [ta, tr, tg, tb] = tint.to_be_bytes();
+    let (ta, tr, tg, tb) = (u32::from(ta), u32::from(tr), u32::from(tg), u32::from(tb));
+    // the tint is not premultiplied, so each color channel is scaled by both
+    // the tint's color and alpha, keeping the result premultiplied
+    let scale = |c: u32, t: u32| c * t * ta / (255 * 255);
+    for px in image.chunks_exact_mut(4) {
+        let p = u32::from_ne_bytes([px[0], px[1], px[2], px[3]]);
+        let a = (p >> 24) * ta / 255;
+        let r = scale((p >> 16) & 0xFF, tr);
+        let g = scale((p >> 8) & 0xFF, tg);
+        let b = scale(p & 0xFF, tb);
+        px.copy_from_slice(&(a << 24 | r << 16 | g << 8 | b).to_ne_bytes());
+    }
+    Ok(image)
+}
+
+/// Creates an image of the given non-premultiplied color with the alpha
+/// values of the mask applied.
+fn masked_color_image(mask: &Image, area: Option<&RectI>, color: u32) -> Result<Image> {
+    if mask.format() != ImageFormat::A8 {
+        return Err(Error::InvalidValue);
+    }
+    let area = area.copied().unwrap_or(RectI {
+        x: 0,
+        y: 0,
+        w: mask.width(),
+        h: mask.height(),
+    });
+    let mut image = Image::new(area.w, area.h, ImageFormat::PRgb32)?;
+    let color = Rgba32::new(color).premultiplied().value;
+    if let PixelsMut::Rgb32(mut rows) = image.pixels_mut() {
+        for (y, row) in rows.rows_mut().enumerate() {
+            for (x, px) in row.iter_mut().enumerate() {
+                let alpha = mask
+                    .pixel(area.x as usize + x, area.y as usize + y)
+                    .map_or(0, |a| a >> 24);
+                *px = scale_prgb32(color, alpha);
+            }
+        }
+    }
+    Ok(image)
+}
+
+impl MatrixTransform for Context<'_> {
     #[inline]
     #[doc(hidden)]
     fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
@@ -993,15 +1893,400 @@ impl MatrixTransform for Context {
     }
 }
 
-impl PartialEq for Context {
+impl PartialEq for Context<'_> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.impl_equals(other)
     }
 }
 
-impl Drop for Context {
+impl Drop for Context<'_> {
     fn drop(&mut self) {
         unsafe { ffi::blContextReset(&mut self.core) };
     }
 }
+
+#[cfg(test)]
+mod test_context {
+    use crate::array::Array;
+    use crate::context::{CompOp, Context, ContextCreateFlags, ContextCreateInfo};
+    use crate::error::Error;
+    use crate::font::FontFace;
+    use crate::geometry::{Circle, FillRule, PointD, PointI, RectD};
+    use crate::glyph_buffer::GlyphBuffer;
+    use crate::gradient::{LinearGradient, LinearGradientValues};
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::MatrixTransform;
+    use crate::path::{Path, StrokeCap, StrokeTransformOrder};
+    use crate::style::Style;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_fill_color_glyph_buffer_without_color_glyphs() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("ab");
+        font.shape(&mut buf).unwrap();
+        let render = |color: bool| {
+            let mut image = Image::new(32, 20, ImageFormat::PRgb32).unwrap();
+            {
+                let mut ctx = Context::new(&mut image).unwrap();
+                ctx.clear_all().unwrap();
+                ctx.set_fill_style_rgba32(0xFF_FF_FF_FF);
+                let dst = PointD::new(2.0, 16.0);
+                if color {
+                    ctx.fill_color_glyph_buffer(dst, &font, &buf, 0).unwrap();
+                } else {
+                    ctx.fill_glyph_buffer(dst, &font, &buf).unwrap();
+                }
+                ctx.end().unwrap();
+            }
+            image
+        };
+        assert_eq!(render(true), render(false));
+    }
+
+    #[test]
+    fn test_fill_color_glyph_buffer_with_color_layers() {
+        // 'A' is an 800x800 unit square made of two COLR layers, a red left
+        // half from the CPAL palette and a right half in the foreground color.
+        let face = FontFace::from_path("assets/ColrTest.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(20.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("A");
+        font.shape(&mut buf).unwrap();
+        let mut image = Image::new(20, 20, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_fill_style_rgba32(0xFF_FF_FF_FF);
+            ctx.fill_color_glyph_buffer(PointD::new(0.0, 18.0), &font, &buf, 0)
+                .unwrap();
+            ctx.end().unwrap();
+        }
+        assert_eq!(image.pixel(5, 10), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(14, 10), Some(0xFF_FF_FF_FF));
+        assert_eq!(image.pixel(0, 10), Some(0));
+    }
+
+    #[test]
+    fn test_fill_texts() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut image = Image::new(64, 20, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_fill_style_rgba32(0xFF_FF_FF_FF);
+            let items = [
+                (PointD::new(2.0, 16.0), "10"),
+                (PointD::new(34.0, 16.0), "20"),
+            ];
+            ctx.fill_texts(&font, &items).unwrap();
+            ctx.end().unwrap();
+        }
+        let covered = |xs: std::ops::Range<usize>| {
+            xs.flat_map(|x| (0..20).map(move |y| (x, y)))
+                .any(|(x, y)| image.pixel(x, y).map_or(false, |px| px != 0))
+        };
+        assert!(covered(0..32) && covered(32..64));
+    }
+
+    #[test]
+    fn test_set_fill_css() {
+        let mut image = Image::new(10, 10, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            assert!(ctx.set_fill_css("rgb(1, 2").is_err());
+            ctx.set_fill_css("linear-gradient(to right, black, white)")
+                .unwrap();
+            ctx.fill_all().unwrap();
+            ctx.end().unwrap();
+        }
+        let (left, right) = (image.pixel(0, 5).unwrap(), image.pixel(9, 5).unwrap());
+        assert!(left & 0xFF < 0x20 && right & 0xFF > 0xE0);
+    }
+
+    #[test]
+    fn test_stroke_rect_dashed() {
+        let mut image = Image::new(20, 20, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_stroke_style_rgba32(0xFF_FF_FF_FF);
+            ctx.set_stroke_width(2.0);
+            ctx.set_stroke_dash_offset(1.0);
+            let rect = RectD::new(2.0, 2.0, 16.0, 16.0);
+            ctx.stroke_rect_dashed(&rect, &[4.0, 4.0], 0.0).unwrap();
+            assert!(ctx.stroke_dash_array().is_empty());
+            assert_eq!(ctx.stroke_dash_offset(), 1.0);
+            ctx.end().unwrap();
+        }
+        // the top edge starts with a dash at x = 2 followed by a gap
+        assert_eq!(image.pixel(4, 2), Some(0xFF_FF_FF_FF));
+        assert_eq!(image.pixel(8, 2), Some(0));
+    }
+
+    #[test]
+    fn test_clip_to_path() {
+        let mut image = Image::new(20, 20, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.clear_all().unwrap();
+            ctx.set_comp_op(CompOp::SrcOver);
+            ctx.set_fill_style_rgba32(0xFF_FF_00_00);
+            let circle = Circle {
+                cx: 10.0,
+                cy: 10.0,
+                r: 8.0,
+            };
+            let mut path = Path::new();
+            path.add_geometry(&circle, None, Default::default());
+            ctx.clip_to_path(&path, FillRule::NonZero, |ctx| ctx.fill_all())
+                .unwrap();
+            ctx.end().unwrap();
+        }
+        assert_eq!(image.pixel(10, 10), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(0, 0), Some(0));
+        assert_eq!(image.pixel(19, 19), Some(0));
+    }
+
+    fn fill_a8(f: impl FnOnce(&mut Context<'_>)) -> Image {
+        let mut image = Image::new(4, 4, ImageFormat::A8).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        f(&mut ctx);
+        ctx.fill_all().unwrap();
+        ctx.end().unwrap();
+        image
+    }
+
+    fn assert_mask(image: &Image, value: u8) {
+        let stride = image.len() / image.height() as usize;
+        for row in image.chunks(stride) {
+            assert_eq!(&row[..image.width() as usize], &[value; 4][..]);
+        }
+    }
+
+    #[test]
+    fn test_a8_fill_alpha_value() {
+        let image = fill_a8(|ctx| ctx.set_fill_alpha_value(0x80));
+        assert_mask(&image, 0x80);
+    }
+
+    #[test]
+    fn test_a8_fill_rgba_ignores_color() {
+        let red = fill_a8(|ctx| ctx.set_fill_style_rgba32(0x40_FF_00_00));
+        let blue = fill_a8(|ctx| ctx.set_fill_style_rgba32(0x40_00_00_FF));
+        assert_mask(&red, 0x40);
+        assert_mask(&blue, 0x40);
+    }
+
+    #[test]
+    fn test_threaded_sync() {
+        let mut image = Image::new(16, 16, ImageFormat::PRgb32).unwrap();
+        let info = ContextCreateInfo {
+            flags: ContextCreateFlags::FORCE_THREADS | ContextCreateFlags::FALLBACK_TO_SYNC,
+            thread_count: 2,
+            cpu_features: 0,
+        };
+        let mut ctx = Context::new_with_options(&mut image, info).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_style_rgba32(0xFF_FF_00_00);
+        ctx.fill_all().unwrap();
+        ctx.sync().unwrap();
+        ctx.end().unwrap();
+        assert!(image.chunks(4).all(|px| px == 0xFF_FF_00_00u32.to_ne_bytes()));
+    }
+
+    #[test]
+    fn test_stroke_state() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_stroke_width(2.0);
+        ctx.set_stroke_end_cap(StrokeCap::Round);
+        ctx.set_stroke_dash_array(&Array::from(vec![4.0, 2.0]));
+        let state = ctx.stroke_state();
+        assert_eq!(state.width, 2.0);
+        assert_eq!(state.end_cap, StrokeCap::Round);
+
+        ctx.scale_stroke_width(0.5);
+        assert_eq!(ctx.stroke_width(), 1.0);
+        assert_eq!(&ctx.stroke_dash_array()[..], &[2.0, 1.0]);
+
+        ctx.set_stroke_state(&state);
+        assert_eq!(ctx.stroke_width(), 2.0);
+        assert_eq!(&ctx.stroke_dash_array()[..], &[4.0, 2.0]);
+    }
+
+    #[test]
+    fn test_device_stroke_width() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.scale(4.0, 4.0);
+        ctx.set_stroke_transform_order(StrokeTransformOrder::After);
+        ctx.set_device_stroke_width(1.0);
+        assert_eq!(ctx.stroke_width(), 0.25);
+        ctx.set_stroke_transform_order(StrokeTransformOrder::Before);
+        ctx.set_device_stroke_width(1.0);
+        assert_eq!(ctx.stroke_width(), 1.0);
+    }
+
+    #[test]
+    fn test_blit_image_with() {
+        let mut src = Image::new(1, 1, ImageFormat::PRgb32).unwrap();
+        src.set_pixel(0, 0, 0xFF_FF_FF_FF);
+        let mut image = Image::new(2, 1, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.clear_all().unwrap();
+        ctx.set_comp_op(CompOp::SrcOver);
+        ctx.blit_image_with(&PointI::ZERO, &src, None, 1.0, 0xFF_FF_00_00)
+            .unwrap();
+        assert!(matches!(
+            ctx.blit_image_with(&PointI::new(1, 0), &src, None, f64::NAN, None),
+            Err(Error::InvalidValue)
+        ));
+        assert_eq!(ctx.global_alpha(), 1.0);
+        ctx.end().unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(1, 0), Some(0));
+    }
+
+    #[test]
+    fn test_fill_mask() {
+        let mut mask = Image::new(2, 1, ImageFormat::A8).unwrap();
+        mask.set_pixel(0, 0, 0xFF << 24);
+        mask.set_pixel(1, 0, 0x80 << 24);
+        let mut image = Image::new(2, 1, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.clear_all().unwrap();
+        ctx.set_comp_op(CompOp::SrcOver);
+        ctx.set_fill_style_rgba32(0xFF_00_00_FF);
+        ctx.fill_mask(&PointI::ZERO, &mask, None).unwrap();
+        ctx.end().unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0xFF_00_00_FF));
+        assert_eq!(image.pixel(1, 0), Some(0x80_00_00_80));
+    }
+
+    #[test]
+    fn test_style_readback() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_fill_style_rgba32(0xFF_00_80_FF);
+        assert_eq!(ctx.fill_style(), Some(Style::from_rgba32(0xFF_00_80_FF)));
+
+        let values = LinearGradientValues {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 4.0,
+            y1: 4.0,
+        };
+        let gradient = LinearGradient::new(&values, Default::default(), &[], None);
+        ctx.set_stroke_style_gradient(&gradient);
+        let style = Style::from(gradient);
+        assert_eq!(ctx.stroke_style(), Some(style.clone()));
+        ctx.set_fill_style(&style);
+        assert_eq!(ctx.fill_style(), Some(style));
+    }
+
+    #[test]
+    fn test_with_comp_op_and_alpha() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        let inner = ctx.with_comp_op(CompOp::SrcCopy, |ctx| {
+            ctx.with_alpha(0.25, |ctx| (ctx.comp_op(), ctx.global_alpha()))
+        });
+        assert_eq!(inner, (CompOp::SrcCopy, 0.25));
+        assert_eq!(ctx.comp_op(), CompOp::SrcOver);
+        assert_eq!(ctx.global_alpha(), 1.0);
+        assert_eq!(ctx.saved_state_count(), 0);
+    }
+
+    #[test]
+    fn test_deep_saved_states() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        let mut cookies = Vec::new();
+        for i in 0..4096 {
+            ctx.set_global_alpha(f64::from(i) / 4096.0);
+            cookies.push(ctx.save_cookie());
+        }
+        assert_eq!(ctx.saved_state_count(), 4096);
+        ctx.restore_cookie(cookies[2048]).unwrap();
+        assert_eq!(ctx.saved_state_count(), 2048);
+        assert_eq!(ctx.global_alpha(), 0.5);
+        // the cookies of the discarded states don't match anymore
+        assert!(matches!(
+            ctx.restore_cookie(cookies[3000]),
+            Err(Error::NoMatchingCookie)
+        ));
+        while ctx.saved_state_count() > 0 {
+            ctx.restore().unwrap();
+        }
+        assert_eq!(ctx.global_alpha(), 0.0);
+        assert!(matches!(ctx.restore(), Err(Error::NoStatesToRestore)));
+        ctx.end().unwrap();
+    }
+
+    #[test]
+    fn test_saved_state_limit() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::builder()
+            .saved_state_limit(8)
+            .build(&mut image)
+            .unwrap();
+        assert_eq!(ctx.saved_state_limit(), Some(8));
+        for _ in 0..8 {
+            ctx.try_save().unwrap();
+        }
+        assert!(matches!(
+            ctx.try_save_cookie(),
+            Err(Error::TooManyStates { limit: 8 })
+        ));
+        assert!(matches!(
+            ctx.with_state(|_| ()),
+            Err(Error::TooManyStates { limit: 8 })
+        ));
+        assert_eq!(ctx.saved_state_count(), 8);
+        // saving without checking the limit still works
+        ctx.save();
+        assert_eq!(ctx.saved_state_count(), 9);
+        ctx.restore().unwrap();
+        ctx.restore().unwrap();
+        assert!(ctx.with_state(|_| ()).is_ok());
+        ctx.end().unwrap();
+
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let ctx = Context::new(&mut image).unwrap();
+        assert_eq!(ctx.saved_state_limit(), None);
+    }
+
+    #[test]
+    fn test_with_layer() {
+        let mut image = Image::new(8, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_fill_style_rgba32(0xFF_FF_00_00);
+        ctx.with_layer(CompOp::SrcOver, 0.5, |layer| {
+            layer.fill_rect(0.0, 0.0, 6.0, 4.0)?;
+            layer.fill_rect(2.0, 0.0, 6.0, 4.0)
+        })
+        .unwrap();
+        assert_eq!(ctx.global_alpha(), 1.0);
+        assert_eq!(ctx.saved_state_count(), 0);
+        ctx.end().unwrap();
+        // the overlap isn't any more opaque than the rest
+        assert_eq!(image.pixel(1, 1), image.pixel(4, 1));
+        let alpha = image.pixel(4, 1).unwrap() >> 24;
+        assert!(alpha == 0x7F || alpha == 0x80, "{:#x}", alpha);
+
+        let mut ctx = Context::new(&mut image).unwrap();
+        assert!(matches!(
+            ctx.with_layer(CompOp::SrcOver, f64::NAN, |_| Ok(())),
+            Err(Error::InvalidValue)
+        ));
+    }
+}
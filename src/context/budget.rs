@@ -0,0 +1,181 @@
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+
+use super::Context;
+use crate::error::{Error, Result};
+use crate::font::Font;
+use crate::geometry::{Geometry, PointD};
+
+/// A [`Context`] that is only allowed to render until a deadline, created by
+/// [`Context::with_budget`] or [`Context::with_deadline`].
+///
+/// The budget is cooperative, it is only checked by [`check_budget`] and by
+/// the batched drawing operations of this type between chunks of items,
+/// which return [`Error::RenderTimeout`] once the deadline passed. For a
+/// synchronous context the measured time includes the rasterization, an
+/// asynchronous context only queues the commands until it is flushed.
+///
+/// [`Context`]: struct.Context.html
+/// [`Context::with_budget`]: struct.Context.html#method.with_budget
+/// [`Context::with_deadline`]: struct.Context.html#method.with_deadline
+/// [`check_budget`]: #method.check_budget
+/// [`Error::RenderTimeout`]: ../error/enum.Error.html#variant.RenderTimeout
+#[derive(Debug)]
+pub struct BudgetedContext<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    deadline: Instant,
+}
+
+impl<'a> Context<'a> {
+    /// Limits the rendering of the returned context to the given duration
+    /// from now.
+    #[inline]
+    pub fn with_budget(&mut self, budget: Duration) -> BudgetedContext<'_, 'a> {
+        self.with_deadline(Instant::now() + budget)
+    }
+
+    /// Limits the rendering of the returned context to the given deadline.
+    #[inline]
+    pub fn with_deadline(&mut self, deadline: Instant) -> BudgetedContext<'_, 'a> {
+        BudgetedContext {
+            ctx: self,
+            deadline,
+        }
+    }
+}
+
+impl<'a> BudgetedContext<'_, 'a> {
+    #[inline]
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// The time left until the deadline, zero if it passed.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns [`Error::RenderTimeout`] with nothing completed if the
+    /// deadline passed.
+    ///
+    /// [`Error::RenderTimeout`]: ../error/enum.Error.html#variant.RenderTimeout
+    #[inline]
+    pub fn check_budget(&self) -> Result<()> {
+        self.check(0)
+    }
+
+    fn check(&self, completed: usize) -> Result<()> {
+        if Instant::now() >= self.deadline {
+            Err(Error::RenderTimeout { completed })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs `draw` for every chunk of `chunk_size` items, checking the budget
+    /// before each one.
+    fn chunked<T, F>(&mut self, items: &[T], chunk_size: usize, mut draw: F) -> Result<()>
+    where
+        F: FnMut(&mut Context<'a>, &[T]) -> Result<()>,
+    {
+        let chunk_size = chunk_size.max(1);
+        for (i, chunk) in items.chunks(chunk_size).enumerate() {
+            self.check(i * chunk_size)?;
+            draw(self.ctx, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Fills the geometries in chunks of `chunk_size`.
+    ///
+    /// Returns [`Error::RenderTimeout`] with the number of filled geometries
+    /// if the deadline passes before all chunks are filled, so that rendering
+    /// can be resumed from there later on.
+    ///
+    /// [`Error::RenderTimeout`]: ../error/enum.Error.html#variant.RenderTimeout
+    pub fn fill_geometries<G: Geometry>(
+        &mut self,
+        geometries: &[G],
+        chunk_size: usize,
+    ) -> Result<()> {
+        self.chunked(geometries, chunk_size, |ctx, chunk| {
+            chunk.iter().try_for_each(|geo| ctx.fill_geometry(geo))
+        })
+    }
+
+    /// Strokes the geometries in chunks of `chunk_size`, see
+    /// [`fill_geometries`].
+    ///
+    /// [`fill_geometries`]: #method.fill_geometries
+    pub fn stroke_geometries<G: Geometry>(
+        &mut self,
+        geometries: &[G],
+        chunk_size: usize,
+    ) -> Result<()> {
+        self.chunked(geometries, chunk_size, |ctx, chunk| {
+            chunk.iter().try_for_each(|geo| ctx.stroke_geometry(geo))
+        })
+    }
+
+    /// Fills the texts in chunks of `chunk_size` with
+    /// [`Context::fill_texts`], see [`fill_geometries`].
+    ///
+    /// [`Context::fill_texts`]: struct.Context.html#method.fill_texts
+    /// [`fill_geometries`]: #method.fill_geometries
+    pub fn fill_texts_chunked(
+        &mut self,
+        font: &Font,
+        items: &[(PointD, &str)],
+        chunk_size: usize,
+    ) -> Result<()> {
+        self.chunked(items, chunk_size, |ctx, chunk| ctx.fill_texts(font, chunk))
+    }
+}
+
+impl<'a> Deref for BudgetedContext<'_, 'a> {
+    type Target = Context<'a>;
+
+    #[inline]
+    fn deref(&self) -> &Context<'a> {
+        self.ctx
+    }
+}
+
+impl<'a> DerefMut for BudgetedContext<'_, 'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Context<'a> {
+        self.ctx
+    }
+}
+
+#[cfg(test)]
+mod test_budget {
+    use std::time::{Duration, Instant};
+
+    use crate::context::Context;
+    use crate::error::Error;
+    use crate::geometry::RectD;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_render_budget() {
+        let mut image = Image::new(16, 16, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        let rects = (0..16)
+            .map(|i| RectD::new(f64::from(i), 0.0, 1.0, 16.0))
+            .collect::<Vec<_>>();
+
+        let mut budgeted = ctx.with_budget(Duration::from_secs(3600));
+        assert!(budgeted.check_budget().is_ok());
+        budgeted.fill_geometries(&rects, 4).unwrap();
+
+        let mut expired = ctx.with_deadline(Instant::now());
+        match expired.fill_geometries(&rects, 4) {
+            Err(Error::RenderTimeout { completed: 0 }) => {},
+            result => panic!("expected a timeout, got {:?}", result),
+        }
+        assert_eq!(expired.remaining(), Duration::from_secs(0));
+        ctx.end().unwrap();
+    }
+}
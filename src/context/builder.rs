@@ -0,0 +1,138 @@
+use super::{Context, ContextCreateFlags, ContextCreateInfo};
+use crate::error::Result;
+use crate::image::Image;
+
+/// A builder for a [`Context`] with custom creation options, created by
+/// [`Context::builder`].
+///
+/// [`Context`]: struct.Context.html
+/// [`Context::builder`]: struct.Context.html#method.builder
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ContextBuilder {
+    info: ContextCreateInfo,
+    saved_state_limit: Option<usize>,
+}
+
+impl ContextBuilder {
+    /// Creates a builder with the default options, that is a synchronous
+    /// context.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of worker threads, `0` renders synchronously on the
+    /// calling thread.
+    #[inline]
+    pub fn threads(mut self, count: u32) -> Self {
+        self.info.thread_count = count;
+        self
+    }
+
+    /// Forces the use of worker threads even for a thread count of `0`.
+    #[inline]
+    pub fn force_threads(self) -> Self {
+        self.flag(ContextCreateFlags::FORCE_THREADS)
+    }
+
+    /// Falls back to synchronous rendering if the worker threads can't be
+    /// created.
+    #[inline]
+    pub fn fallback_to_sync(self) -> Self {
+        self.flag(ContextCreateFlags::FALLBACK_TO_SYNC)
+    }
+
+    /// Uses worker threads that are not shared with other contexts.
+    #[inline]
+    pub fn isolated_threads(self) -> Self {
+        self.flag(ContextCreateFlags::ISOLATED_THREADS)
+    }
+
+    /// Uses a JIT runtime that is not shared with other contexts.
+    #[inline]
+    pub fn isolated_jit(self) -> Self {
+        self.flag(ContextCreateFlags::ISOLATED_JIT)
+    }
+
+    /// Overrides the detected CPU features with the given ones.
+    #[inline]
+    pub fn cpu_features(mut self, features: u32) -> Self {
+        self.info.cpu_features = features;
+        self.flag(ContextCreateFlags::OVERRIDE_CPU_FEATURES)
+    }
+
+    /// Limits the number of states that can be saved at once, saving more
+    /// with [`Context::try_save`], [`Context::try_save_cookie`] or the
+    /// functions built on them fails with [`Error::TooManyStates`].
+    ///
+    /// [`Context::try_save`]: struct.Context.html#method.try_save
+    /// [`Context::try_save_cookie`]: struct.Context.html#method.try_save_cookie
+    /// [`Error::TooManyStates`]: ../error/enum.Error.html#variant.TooManyStates
+    #[inline]
+    pub fn saved_state_limit(mut self, limit: usize) -> Self {
+        self.saved_state_limit = Some(limit);
+        self
+    }
+
+    /// The creation info built so far.
+    #[inline]
+    pub fn create_info(&self) -> ContextCreateInfo {
+        self.info
+    }
+
+    /// Creates a context that renders to the given [`Image`].
+    ///
+    /// [`Image`]: ../image/struct.Image.html
+    #[inline]
+    pub fn build(self, target: &mut Image) -> Result<Context<'_>> {
+        let mut ctx = Context::new_with_options(target, self.info)?;
+        ctx.saved_state_limit = self.saved_state_limit;
+        Ok(ctx)
+    }
+
+    #[inline]
+    fn flag(mut self, flag: ContextCreateFlags) -> Self {
+        self.info.flags |= flag;
+        self
+    }
+}
+
+impl Context<'_> {
+    /// Creates a [`ContextBuilder`] for configuring the creation options of a
+    /// context.
+    ///
+    /// [`ContextBuilder`]: struct.ContextBuilder.html
+    #[inline]
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test_builder {
+    use crate::context::{Context, ContextCreateFlags};
+    use crate::image::Image;
+
+    #[test]
+    fn test_context_builder() {
+        let builder = Context::builder().threads(2).isolated_jit().cpu_features(0);
+        let info = builder.create_info();
+        assert_eq!(info.thread_count, 2);
+        assert_eq!(
+            info.flags,
+            ContextCreateFlags::ISOLATED_JIT | ContextCreateFlags::OVERRIDE_CPU_FEATURES
+        );
+
+        let mut image = Image::new(16, 16, Default::default()).unwrap();
+        let mut ctx = Context::builder().build(&mut image).unwrap();
+        let value = ctx
+            .with_state(|ctx| {
+                ctx.set_global_alpha(0.5);
+                ctx.global_alpha()
+            })
+            .unwrap();
+        assert_eq!(value, 0.5);
+        assert_eq!(ctx.global_alpha(), 1.0);
+        ctx.end().unwrap();
+    }
+}
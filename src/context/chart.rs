@@ -0,0 +1,206 @@
+use super::Context;
+use crate::error::{Error, Result};
+use crate::geometry::RectD;
+use crate::path::Path;
+use crate::style::FillStrokeStyle;
+
+/// The orientation of a chart axis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AxisOrientation {
+    /// An axis along the x direction, its ticks are vertical lines.
+    Horizontal,
+    /// An axis along the y direction, its ticks are horizontal lines.
+    Vertical,
+}
+
+/// A tick of a chart axis as computed by [`axis_ticks`].
+///
+/// [`axis_ticks`]: fn.axis_ticks.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AxisTick {
+    /// The data value of the tick, meant to be used as its label.
+    pub value: f64,
+    /// The position of the tick along the axis in user space.
+    pub position: f64,
+}
+
+/// Snaps a coordinate of an axis aligned line with the given width so that
+/// the line covers whole pixels and is rendered without blurry edges.
+///
+/// Lines of odd widths are centered on pixel centers, lines of even widths on
+/// pixel edges. This assumes that the user space is only translated by whole
+/// pixels relative to the device space.
+pub fn snap_to_pixel(value: f64, line_width: f64) -> f64 {
+    if line_width.round() as i64 % 2 == 1 {
+        value.floor() + 0.5
+    } else {
+        value.round()
+    }
+}
+
+/// Computes the ticks of an axis that maps the value range `min..=max` onto
+/// the positions `start..=end`.
+///
+/// The tick values are evenly spaced by a step of 1, 2 or 5 times a power of
+/// ten, chosen so that at most `max_ticks` ticks are produced. The positions
+/// are not snapped, so they can be used to place labels as well. An empty
+/// vector is returned if the range is empty or not finite.
+pub fn axis_ticks(min: f64, max: f64, start: f64, end: f64, max_ticks: usize) -> Vec<AxisTick> {
+    if max <= min || !max.is_finite() || !min.is_finite() || max_ticks == 0 {
+        return Vec::new();
+    }
+    let raw_step = (max - min) / (max_ticks.max(2) - 1) as f64;
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|&factor| factor * magnitude)
+        .find(|&step| step >= raw_step)
+        .unwrap_or(10.0 * magnitude);
+    let scale = (end - start) / (max - min);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    (first..=last)
+        .map(|i| i as f64 * step)
+        .map(|value| AxisTick {
+            value,
+            position: start + (value - min) * scale,
+        })
+        .collect()
+}
+
+/// Chart Operations
+impl Context<'_> {
+    /// Strokes the lines of a grid spanning the given rectangle with the given
+    /// style, spaced `x_step` apart horizontally and `y_step` apart
+    /// vertically, starting at the rectangle's origin.
+    ///
+    /// The lines are snapped to whole pixels according to the current stroke
+    /// width, see [`snap_to_pixel`]. The context state is left untouched
+    /// afterwards. Returns [`Error::InvalidValue`] if a step is not a positive
+    /// finite number.
+    ///
+    /// [`snap_to_pixel`]: fn.snap_to_pixel.html
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn draw_grid<S>(&mut self, rect: &RectD, x_step: f64, y_step: f64, style: &S) -> Result<()>
+    where
+        S: FillStrokeStyle + ?Sized,
+    {
+        if !(x_step.is_finite() && x_step > 0.0 && y_step.is_finite() && y_step > 0.0) {
+            return Err(Error::InvalidValue);
+        }
+        let width = self.stroke_width();
+        let mut path = Path::new();
+        let columns = (rect.w / x_step + 1e-9).floor() as usize;
+        for i in 0..=columns {
+            let x = snap_to_pixel(rect.x + i as f64 * x_step, width);
+            path.move_to(x, rect.y);
+            path.line_to(x, rect.y + rect.h);
+        }
+        let rows = (rect.h / y_step + 1e-9).floor() as usize;
+        for i in 0..=rows {
+            let y = snap_to_pixel(rect.y + i as f64 * y_step, width);
+            path.move_to(rect.x, y);
+            path.line_to(rect.x + rect.w, y);
+        }
+        self.stroke_with_style(&path, style)
+    }
+
+    /// Strokes a tick line of the given `length` for each of the `ticks`,
+    /// starting at `baseline` on the cross axis, with the given style.
+    ///
+    /// Ticks of a [`AxisOrientation::Horizontal`] axis extend downwards and
+    /// ticks of a [`AxisOrientation::Vertical`] axis extend to the right, a
+    /// negative length flips the direction. Tick positions are snapped like
+    /// the lines of [`draw_grid`], the context state is left untouched
+    /// afterwards.
+    ///
+    /// [`AxisOrientation::Horizontal`]: enum.AxisOrientation.html#variant.Horizontal
+    /// [`AxisOrientation::Vertical`]: enum.AxisOrientation.html#variant.Vertical
+    /// [`draw_grid`]: #method.draw_grid
+    pub fn draw_axis_ticks<S>(
+        &mut self,
+        orientation: AxisOrientation,
+        ticks: &[AxisTick],
+        baseline: f64,
+        length: f64,
+        style: &S,
+    ) -> Result<()>
+    where
+        S: FillStrokeStyle + ?Sized,
+    {
+        let width = self.stroke_width();
+        let mut path = Path::new();
+        for tick in ticks {
+            let position = snap_to_pixel(tick.position, width);
+            match orientation {
+                AxisOrientation::Horizontal => {
+                    path.move_to(position, baseline);
+                    path.line_to(position, baseline + length);
+                },
+                AxisOrientation::Vertical => {
+                    path.move_to(baseline, position);
+                    path.line_to(baseline + length, position);
+                },
+            }
+        }
+        self.stroke_with_style(&path, style)
+    }
+
+    fn stroke_with_style<S>(&mut self, path: &Path, style: &S) -> Result<()>
+    where
+        S: FillStrokeStyle + ?Sized,
+    {
+        let cookie = self.save_cookie();
+        self.set_stroke_style(style);
+        let result = self.stroke_path(path);
+        self.restore_cookie(cookie).and(result)
+    }
+}
+
+#[cfg(test)]
+mod test_chart {
+    use crate::context::{axis_ticks, snap_to_pixel, CompOp, Context};
+    use crate::geometry::RectD;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_axis_ticks() {
+        let values: Vec<_> = axis_ticks(0.0, 100.0, 0.0, 200.0, 6)
+            .iter()
+            .map(|tick| tick.value)
+            .collect();
+        assert_eq!(values, [0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        let ticks = axis_ticks(0.3, 1.7, 10.0, 150.0, 4);
+        assert_eq!(ticks[0].value, 0.5);
+        assert!((ticks[0].position - 30.0).abs() < 1e-9);
+        assert!(axis_ticks(1.0, 1.0, 0.0, 100.0, 5).is_empty());
+    }
+
+    #[test]
+    fn test_snap_to_pixel() {
+        assert_eq!(snap_to_pixel(10.2, 1.0), 10.5);
+        assert_eq!(snap_to_pixel(10.7, 2.0), 11.0);
+    }
+
+    #[test]
+    fn test_draw_grid_is_crisp() {
+        let mut image = Image::new(20, 20, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.clear_all().unwrap();
+            ctx.set_comp_op(CompOp::SrcOver);
+            let rect = RectD {
+                x: 0.0,
+                y: 0.0,
+                w: 20.0,
+                h: 20.0,
+            };
+            ctx.draw_grid(&rect, 10.0, 10.0, &0xFF_FF_FF_FFu32).unwrap();
+            ctx.end().unwrap();
+        }
+        assert_eq!(image.pixel(10, 5), Some(0xFF_FF_FF_FF));
+        assert_eq!(image.pixel(11, 5), Some(0));
+        assert_eq!(image.pixel(9, 5), Some(0));
+    }
+}
@@ -0,0 +1,151 @@
+use super::{CompOp, Context};
+use crate::error::Result;
+use crate::geometry::PointD;
+use crate::matrix::{Matrix2D, MatrixTransform};
+use crate::path::Path;
+use crate::rgba::Rgba32;
+
+/// Options of [`Context::debug_overlay`].
+///
+/// [`Context::debug_overlay`]: struct.Context.html#method.debug_overlay
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DebugOverlayOptions {
+    /// The color the area let through by the current clip is tinted with,
+    /// should be translucent.
+    pub clip_color: Rgba32,
+    /// The color of the axes of the meta matrix.
+    pub meta_axes_color: Rgba32,
+    /// The color of the axes of the combined user and meta matrix.
+    pub user_axes_color: Rgba32,
+    /// The color of the saved state indicator.
+    pub state_color: Rgba32,
+    /// The length of the drawn axes in device pixels.
+    pub axis_length: f64,
+    /// The width of the drawn lines in device pixels.
+    pub line_width: f64,
+}
+
+impl Default for DebugOverlayOptions {
+    fn default() -> Self {
+        DebugOverlayOptions {
+            clip_color: Rgba32::new(0x20_00_80_FF),
+            meta_axes_color: Rgba32::new(0xFF_80_80_80),
+            user_axes_color: Rgba32::new(0xFF_FF_00_80),
+            state_color: Rgba32::new(0xFF_FF_A0_00),
+            axis_length: 40.0,
+            line_width: 2.0,
+        }
+    }
+}
+
+/// Debug Operations
+impl Context<'_> {
+    /// Draws a diagnostic overlay of the current state onto the target.
+    ///
+    /// Blend2D doesn't expose the current clip, so it is visualized by
+    /// tinting everything it lets through with [`clip_color`]. The axes of the
+    /// meta matrix and of the combined user and meta matrix are drawn from
+    /// their origins, the x-axis ends in a dot. The number of saved states is
+    /// shown as a row of squares in the top left corner. Like everything else
+    /// the overlay is subject to the current clip. The context state is left
+    /// untouched afterwards.
+    ///
+    /// [`clip_color`]: struct.DebugOverlayOptions.html#structfield.clip_color
+    pub fn debug_overlay(&mut self, options: &DebugOverlayOptions) -> Result<()> {
+        let saved_states = self.saved_state_count();
+        let meta = *self.meta_matrix();
+        let mut user = *self.user_matrix();
+        user.post_transform(&meta);
+
+        let cookie = self.save_cookie();
+        let result = self.draw_debug_overlay(options, saved_states, &meta, &user);
+        self.restore_cookie(cookie).and(result)
+    }
+
+    fn draw_debug_overlay(
+        &mut self,
+        options: &DebugOverlayOptions,
+        saved_states: usize,
+        meta: &Matrix2D,
+        user: &Matrix2D,
+    ) -> Result<()> {
+        // draw in device space
+        let device = meta.inverted().unwrap_or_default();
+        self.set_matrix(&device);
+        self.set_comp_op(CompOp::SrcOver);
+        self.set_global_alpha(1.0);
+        self.set_fill_alpha(1.0);
+        self.set_stroke_alpha(1.0);
+
+        self.set_fill_style_rgba32(options.clip_color);
+        self.fill_all()?;
+
+        self.set_stroke_width(options.line_width);
+        for &(matrix, color) in &[
+            (meta, options.meta_axes_color),
+            (user, options.user_axes_color),
+        ] {
+            self.set_stroke_style_rgba32(color);
+            self.set_fill_style_rgba32(color);
+            let origin = matrix.map_point(0.0, 0.0);
+            let mut axes = Path::new();
+            let mut x_end = None;
+            for &(x, y) in &[(1.0, 0.0), (0.0, 1.0)] {
+                let p = matrix.map_point(x, y);
+                let (dx, dy) = (p.x - origin.x, p.y - origin.y);
+                let len = dx.hypot(dy);
+                if len == 0.0 || !len.is_finite() {
+                    continue;
+                }
+                let end = PointD::new(
+                    origin.x + dx / len * options.axis_length,
+                    origin.y + dy / len * options.axis_length,
+                );
+                axes.move_to(origin.x, origin.y);
+                axes.line_to(end.x, end.y);
+                if x == 1.0 {
+                    x_end = Some(end);
+                }
+            }
+            self.stroke_path(&axes)?;
+            if let Some(end) = x_end {
+                self.fill_circle(end.x, end.y, options.line_width * 1.5)?;
+            }
+        }
+
+        self.set_fill_style_rgba32(options.state_color);
+        let size = (options.line_width * 3.0).max(4.0);
+        for i in 0..saved_states {
+            self.fill_rect(4.0 + i as f64 * (size + 2.0), 4.0, size, size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_debug {
+    use crate::context::{CompOp, Context, DebugOverlayOptions};
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::MatrixTransform;
+
+    #[test]
+    fn test_debug_overlay() {
+        let mut image = Image::new(64, 64, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.clear_all().unwrap();
+            ctx.save();
+            ctx.translate(32.0, 32.0);
+            ctx.clip_to(0.0, 0.0, 16.0, 16.0);
+            let options = DebugOverlayOptions::default();
+            ctx.debug_overlay(&options).unwrap();
+            assert_eq!(ctx.saved_state_count(), 1);
+            assert_eq!(ctx.comp_op(), CompOp::SrcCopy);
+            ctx.end().unwrap();
+        }
+        // only the clipped area is tinted
+        assert_eq!(image.pixel(1, 60), Some(0));
+        assert_ne!(image.pixel(46, 46), Some(0));
+    }
+}
@@ -0,0 +1,144 @@
+use super::{ContextCreateFlags, ContextCreateInfo};
+use crate::error::Result;
+use crate::runtime::{query_build_info, query_system_info, CpuArch, CpuFeatures};
+
+/// The kind of rendering pipelines a context uses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PipelineKind {
+    /// Pipelines are compiled at runtime for the selected CPU features.
+    Jit,
+    /// Precompiled reference pipelines are used, either because the CPU
+    /// architecture has no JIT backend or because the selected CPU features
+    /// are below the JIT's minimum of SSE2.
+    Reference,
+}
+
+/// The highest SIMD instruction set a context's pipelines may use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimdLevel {
+    /// No SIMD instructions, only scalar code.
+    None,
+    /// The SSE2 instruction set, the minimum of the JIT.
+    Sse2,
+    /// The SSE3 instruction set.
+    Sse3,
+    /// The Supplemental SSE3 instruction set.
+    Ssse3,
+    /// The SSE4.1 instruction set.
+    Sse4_1,
+    /// The SSE4.2 instruction set.
+    Sse4_2,
+    /// The 256-bit AVX instruction set.
+    Avx,
+    /// The AVX2 instruction set with 256-bit integer operations.
+    Avx2,
+}
+
+impl SimdLevel {
+    /// Returns the highest SIMD level contained in the given features.
+    pub fn from_cpu_features(features: CpuFeatures) -> Self {
+        [
+            (CpuFeatures::X86_AVX2, SimdLevel::Avx2),
+            (CpuFeatures::X86_AVX, SimdLevel::Avx),
+            (CpuFeatures::X86_SSE4_2, SimdLevel::Sse4_2),
+            (CpuFeatures::X86_SSE4_1, SimdLevel::Sse4_1),
+            (CpuFeatures::X86_SSSE3, SimdLevel::Ssse3),
+            (CpuFeatures::X86_SSE3, SimdLevel::Sse3),
+            (CpuFeatures::X86_SSE2, SimdLevel::Sse2),
+        ]
+        .iter()
+        .find(|(feature, _)| features.contains(*feature))
+        .map_or(SimdLevel::None, |&(_, level)| level)
+    }
+}
+
+/// Describes the rendering pipelines a context created with a given
+/// [`ContextCreateInfo`] uses, see [`ContextCreateInfo::pipeline_info`].
+///
+/// Blend2D doesn't report which pipelines it compiled, so this is derived
+/// from the runtime's build and system information combined with the create
+/// flags, which makes it useful to verify that settings like
+/// [`ContextCreateFlags::OVERRIDE_CPU_FEATURES`] took effect.
+///
+/// [`ContextCreateInfo`]: struct.ContextCreateInfo.html
+/// [`ContextCreateInfo::pipeline_info`]: struct.ContextCreateInfo.html#method.pipeline_info
+/// [`ContextCreateFlags::OVERRIDE_CPU_FEATURES`]: struct.ContextCreateFlags.html#associatedconstant.OVERRIDE_CPU_FEATURES
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PipelineInfo {
+    /// Whether the pipelines are JIT compiled or precompiled.
+    pub kind: PipelineKind,
+    /// The highest SIMD level the pipelines are generated for.
+    pub simd_level: SimdLevel,
+    /// The CPU features the pipelines are generated for, either detected or
+    /// overridden by the create info.
+    pub cpu_features: CpuFeatures,
+    /// Whether the CPU features were overridden by the create info.
+    pub cpu_features_overridden: bool,
+    /// Whether the context compiles its pipelines with its own isolated JIT
+    /// runtime instead of the global one.
+    pub isolated_jit: bool,
+}
+
+impl ContextCreateInfo {
+    /// Returns the rendering pipelines a context created with this info uses.
+    pub fn pipeline_info(&self) -> Result<PipelineInfo> {
+        let system = query_system_info()?;
+        let overridden = self
+            .flags
+            .contains(ContextCreateFlags::OVERRIDE_CPU_FEATURES);
+        let cpu_features = if overridden {
+            CpuFeatures::from_bits_truncate(self.cpu_features)
+        } else {
+            system.cpu_features
+        };
+        let jit = system.cpu_arch == CpuArch::X86 && cpu_features.contains(CpuFeatures::X86_SSE2);
+        let (kind, simd_features) = if jit {
+            (PipelineKind::Jit, cpu_features)
+        } else {
+            // reference pipelines only contain code paths that were compiled
+            // into blend2d
+            let build = query_build_info()?;
+            (
+                PipelineKind::Reference,
+                cpu_features & build.supported_cpu_features,
+            )
+        };
+        Ok(PipelineInfo {
+            kind,
+            simd_level: SimdLevel::from_cpu_features(simd_features),
+            cpu_features,
+            cpu_features_overridden: overridden,
+            isolated_jit: jit && self.flags.contains(ContextCreateFlags::ISOLATED_JIT),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_pipeline {
+    use crate::context::{ContextCreateFlags, ContextCreateInfo, SimdLevel};
+    use crate::runtime::CpuFeatures;
+
+    #[test]
+    fn test_simd_level_from_features() {
+        let features = CpuFeatures::X86_SSE2 | CpuFeatures::X86_SSE4_1;
+        assert_eq!(SimdLevel::from_cpu_features(features), SimdLevel::Sse4_1);
+        assert_eq!(
+            SimdLevel::from_cpu_features(CpuFeatures::empty()),
+            SimdLevel::None
+        );
+    }
+
+    #[test]
+    fn test_pipeline_info_override() {
+        let info = ContextCreateInfo {
+            flags: ContextCreateFlags::OVERRIDE_CPU_FEATURES,
+            thread_count: 0,
+            cpu_features: CpuFeatures::X86_SSE2.bits(),
+        };
+        let pipeline = info.pipeline_info().unwrap();
+        assert!(pipeline.cpu_features_overridden);
+        assert_eq!(pipeline.cpu_features, CpuFeatures::X86_SSE2);
+        assert!(pipeline.simd_level <= SimdLevel::Sse2);
+        assert!(!pipeline.isolated_jit);
+    }
+}
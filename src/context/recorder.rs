@@ -0,0 +1,359 @@
+use std::fmt;
+
+use super::{CompOp, Context};
+use crate::error::Result;
+use crate::geometry::{Circle, FillRule, Geometry, Line, Rect, RectD};
+use crate::matrix::{Matrix2DOp, MatrixTransform};
+use crate::path::{Path, PathCommand, StrokeCap, StrokeJoin};
+use crate::rgba::{Rgba32, Rgba64};
+
+/// A [`Context`] wrapper that records every call made through it as a line of
+/// Rust code.
+///
+/// The recorded calls reproduce the rendering when run against a context
+/// named `ctx` inside a function returning [`Result`], which makes them
+/// useful for minimal bug reproductions and for diffing the draw streams of
+/// two versions of an application. Geometries other than paths are recorded
+/// through their [`Debug`] representation, which is valid Rust for all
+/// geometry types of this crate.
+///
+/// Calls made directly on the wrapped context, e.g. through
+/// [`context_mut`], are not recorded.
+///
+/// [`Result`]: ../error/type.Result.html
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`context_mut`]: #method.context_mut
+#[derive(Debug)]
+pub struct RecordingContext<'a> {
+    ctx: Context<'a>,
+    calls: Vec<String>,
+}
+
+impl<'a> RecordingContext<'a> {
+    /// Wraps the given context.
+    pub fn new(ctx: Context<'a>) -> Self {
+        RecordingContext {
+            ctx,
+            calls: Vec::new(),
+        }
+    }
+
+    /// The calls recorded so far, one line of Rust code per call.
+    #[inline]
+    pub fn calls(&self) -> &[String] {
+        &self.calls
+    }
+
+    /// Takes the calls recorded so far, clearing the recording.
+    #[inline]
+    pub fn take_calls(&mut self) -> Vec<String> {
+        std::mem::replace(&mut self.calls, Vec::new())
+    }
+
+    /// Returns all recorded calls as a single Rust snippet.
+    pub fn to_rust(&self) -> String {
+        let mut out = String::new();
+        for call in &self.calls {
+            out.push_str(call);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The wrapped context.
+    #[inline]
+    pub fn context(&self) -> &Context<'a> {
+        &self.ctx
+    }
+
+    /// The wrapped context, calls made through it are not recorded.
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut Context<'a> {
+        &mut self.ctx
+    }
+
+    /// Unwraps the context, discarding the recording.
+    #[inline]
+    pub fn into_inner(self) -> Context<'a> {
+        self.ctx
+    }
+
+    /// Ends the wrapped context, returning the recorded calls.
+    pub fn end(mut self) -> Result<Vec<String>> {
+        self.record(format_args!("ctx.end()?;"));
+        let calls = self.take_calls();
+        self.ctx.end().map(|_| calls)
+    }
+
+    #[inline]
+    fn record(&mut self, call: fmt::Arguments<'_>) {
+        self.calls.push(call.to_string());
+    }
+}
+
+/// State Operations
+impl RecordingContext<'_> {
+    pub fn save(&mut self) {
+        self.record(format_args!("ctx.save();"));
+        self.ctx.save();
+    }
+
+    pub fn restore(&mut self) -> Result<()> {
+        self.record(format_args!("ctx.restore()?;"));
+        self.ctx.restore()
+    }
+
+    pub fn set_comp_op(&mut self, comp_op: CompOp) {
+        self.record(format_args!("ctx.set_comp_op(CompOp::{:?});", comp_op));
+        self.ctx.set_comp_op(comp_op);
+    }
+
+    pub fn set_global_alpha(&mut self, alpha: f64) {
+        self.record(format_args!("ctx.set_global_alpha({:?});", alpha));
+        self.ctx.set_global_alpha(alpha);
+    }
+
+    pub fn set_fill_rule(&mut self, rule: FillRule) {
+        self.record(format_args!("ctx.set_fill_rule(FillRule::{:?});", rule));
+        self.ctx.set_fill_rule(rule);
+    }
+
+    pub fn set_fill_alpha(&mut self, alpha: f64) {
+        self.record(format_args!("ctx.set_fill_alpha({:?});", alpha));
+        self.ctx.set_fill_alpha(alpha);
+    }
+
+    pub fn set_fill_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        let color = color.into().value;
+        self.record(format_args!("ctx.set_fill_style_rgba32(0x{:08X});", color));
+        self.ctx.set_fill_style_rgba32(color);
+    }
+
+    pub fn set_fill_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        let color = color.into().value;
+        self.record(format_args!("ctx.set_fill_style_rgba64(0x{:016X});", color));
+        self.ctx.set_fill_style_rgba64(color);
+    }
+
+    pub fn set_stroke_alpha(&mut self, alpha: f64) {
+        self.record(format_args!("ctx.set_stroke_alpha({:?});", alpha));
+        self.ctx.set_stroke_alpha(alpha);
+    }
+
+    pub fn set_stroke_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        let color = color.into().value;
+        self.record(format_args!("ctx.set_stroke_style_rgba32(0x{:08X});", color));
+        self.ctx.set_stroke_style_rgba32(color);
+    }
+
+    pub fn set_stroke_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        let color = color.into().value;
+        self.record(format_args!("ctx.set_stroke_style_rgba64(0x{:016X});", color));
+        self.ctx.set_stroke_style_rgba64(color);
+    }
+
+    pub fn set_stroke_width(&mut self, width: f64) {
+        self.record(format_args!("ctx.set_stroke_width({:?});", width));
+        self.ctx.set_stroke_width(width);
+    }
+
+    pub fn set_stroke_miter_limit(&mut self, limit: f64) {
+        self.record(format_args!("ctx.set_stroke_miter_limit({:?});", limit));
+        self.ctx.set_stroke_miter_limit(limit);
+    }
+
+    pub fn set_stroke_join(&mut self, join: StrokeJoin) {
+        self.record(format_args!("ctx.set_stroke_join(StrokeJoin::{:?});", join));
+        self.ctx.set_stroke_join(join);
+    }
+
+    pub fn set_stroke_caps(&mut self, cap: StrokeCap) {
+        self.record(format_args!("ctx.set_stroke_caps(StrokeCap::{:?});", cap));
+        self.ctx.set_stroke_caps(cap);
+    }
+}
+
+/// Clip and Clear Operations
+impl RecordingContext<'_> {
+    pub fn clip_to_rect<R: Rect + fmt::Debug>(&mut self, rect: &R) {
+        self.record(format_args!("ctx.clip_to_rect(&{:?});", rect));
+        self.ctx.clip_to_rect(rect);
+    }
+
+    pub fn restore_clipping(&mut self) {
+        self.record(format_args!("ctx.restore_clipping();"));
+        self.ctx.restore_clipping();
+    }
+
+    pub fn clear_all(&mut self) -> Result<()> {
+        self.record(format_args!("ctx.clear_all()?;"));
+        self.ctx.clear_all()
+    }
+
+    pub fn clear_rect<R: Rect + fmt::Debug>(&mut self, rect: &R) -> Result<()> {
+        self.record(format_args!("ctx.clear_rect(&{:?})?;", rect));
+        self.ctx.clear_rect(rect)
+    }
+}
+
+/// Fill and Stroke Operations
+impl RecordingContext<'_> {
+    pub fn fill_all(&mut self) -> Result<()> {
+        self.record(format_args!("ctx.fill_all()?;"));
+        self.ctx.fill_all()
+    }
+
+    pub fn fill_geometry<T: Geometry + fmt::Debug + ?Sized>(&mut self, geo: &T) -> Result<()> {
+        self.record(format_args!("ctx.fill_geometry(&{:?})?;", geo));
+        self.ctx.fill_geometry(geo)
+    }
+
+    pub fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64) -> Result<()> {
+        self.fill_geometry(&RectD { x, y, w, h })
+    }
+
+    pub fn fill_circle(&mut self, cx: f64, cy: f64, r: f64) -> Result<()> {
+        self.fill_geometry(&Circle { cx, cy, r })
+    }
+
+    pub fn fill_path(&mut self, path: &Path) -> Result<()> {
+        self.record(format_args!("ctx.fill_path(&{})?;", PathSnippet(path)));
+        self.ctx.fill_path(path)
+    }
+
+    pub fn stroke_geometry<T: Geometry + fmt::Debug + ?Sized>(&mut self, geo: &T) -> Result<()> {
+        self.record(format_args!("ctx.stroke_geometry(&{:?})?;", geo));
+        self.ctx.stroke_geometry(geo)
+    }
+
+    pub fn stroke_rect(&mut self, x: f64, y: f64, w: f64, h: f64) -> Result<()> {
+        self.stroke_geometry(&RectD { x, y, w, h })
+    }
+
+    pub fn stroke_line(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) -> Result<()> {
+        self.stroke_geometry(&Line { x0, y0, x1, y1 })
+    }
+
+    pub fn stroke_circle(&mut self, cx: f64, cy: f64, r: f64) -> Result<()> {
+        self.stroke_geometry(&Circle { cx, cy, r })
+    }
+
+    pub fn stroke_path(&mut self, path: &Path) -> Result<()> {
+        self.record(format_args!("ctx.stroke_path(&{})?;", PathSnippet(path)));
+        self.ctx.stroke_path(path)
+    }
+}
+
+impl MatrixTransform for RecordingContext<'_> {
+    #[inline]
+    #[doc(hidden)]
+    fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
+        let args = data
+            .iter()
+            .map(|v| format!("{:?}", v))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match op {
+            Matrix2DOp::Reset => self.record(format_args!("ctx.reset_matrix();")),
+            Matrix2DOp::Assign => {
+                self.record(format_args!("ctx.set_matrix(&Matrix2D::new({}));", args))
+            },
+            Matrix2DOp::Transform => {
+                self.record(format_args!("ctx.transform(&Matrix2D::new({}));", args))
+            },
+            Matrix2DOp::PostTransform => {
+                self.record(format_args!("ctx.post_transform(&Matrix2D::new({}));", args))
+            },
+            _ => {
+                let name = match op {
+                    Matrix2DOp::Translate => "translate",
+                    Matrix2DOp::Scale => "scale",
+                    Matrix2DOp::Skew => "skew",
+                    Matrix2DOp::Rotate => "rotate",
+                    Matrix2DOp::RotatePoint => "rotate_around",
+                    Matrix2DOp::PostTranslate => "post_translate",
+                    Matrix2DOp::PostScale => "post_scale",
+                    Matrix2DOp::PostSkew => "post_skew",
+                    Matrix2DOp::PostRotate => "post_rotate",
+                    _ => "post_rotate_around",
+                };
+                self.record(format_args!("ctx.{}({});", name, args))
+            },
+        }
+        self.ctx.apply_matrix_op(op, data);
+    }
+}
+
+/// Formats a path as a Rust block expression rebuilding it.
+struct PathSnippet<'p>(&'p Path);
+
+impl fmt::Display for PathSnippet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cmds = self.0.command_data();
+        let vtx = self.0.vertex_data();
+        f.write_str("{ let mut path = Path::new();")?;
+        let mut i = 0;
+        while i < cmds.len() {
+            let p = vtx[i];
+            match PathCommand::from(u32::from(cmds[i])) {
+                PathCommand::Move => write!(f, " path.move_to({:?}, {:?});", p.x, p.y)?,
+                PathCommand::On => write!(f, " path.line_to({:?}, {:?});", p.x, p.y)?,
+                PathCommand::Quad if i + 1 < cmds.len() => {
+                    let p1 = vtx[i + 1];
+                    write!(f, " path.quad_to({:?}, {:?}, {:?}, {:?});", p.x, p.y, p1.x, p1.y)?;
+                    i += 1;
+                },
+                PathCommand::Cubic if i + 2 < cmds.len() => {
+                    let (p1, p2) = (vtx[i + 1], vtx[i + 2]);
+                    write!(
+                        f,
+                        " path.cubic_to({:?}, {:?}, {:?}, {:?}, {:?}, {:?});",
+                        p.x, p.y, p1.x, p1.y, p2.x, p2.y
+                    )?;
+                    i += 2;
+                },
+                PathCommand::Close => f.write_str(" path.close();")?,
+                // a truncated curve, the remaining vertices can't be reproduced
+                PathCommand::Quad | PathCommand::Cubic => break,
+            }
+            i += 1;
+        }
+        f.write_str(" path }")
+    }
+}
+
+#[cfg(test)]
+mod test_recorder {
+    use crate::context::{CompOp, Context, RecordingContext};
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::MatrixTransform;
+    use crate::path::Path;
+
+    #[test]
+    fn test_recording() {
+        let mut image = Image::new(16, 16, ImageFormat::PRgb32).unwrap();
+        let mut ctx = RecordingContext::new(Context::new(&mut image).unwrap());
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_style_rgba32(0xFF00_00FF);
+        ctx.translate(1.0, 2.5);
+        ctx.fill_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 1.0, 2.0, 0.0);
+        path.close();
+        ctx.stroke_path(&path).unwrap();
+        let calls = ctx.end().unwrap();
+        assert_eq!(
+            calls,
+            [
+                "ctx.set_comp_op(CompOp::SrcCopy);",
+                "ctx.set_fill_style_rgba32(0xFF0000FF);",
+                "ctx.translate(1.0, 2.5);",
+                "ctx.fill_geometry(&RectD { x: 0.0, y: 0.0, w: 4.0, h: 4.0 })?;",
+                "ctx.stroke_path(&{ let mut path = Path::new(); path.move_to(0.0, 0.0); \
+                 path.quad_to(1.0, 1.0, 2.0, 0.0); path.close(); path })?;",
+                "ctx.end()?;",
+            ]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::{Deref, DerefMut};
+
+use super::{Context, ContextCookie};
+use crate::error::Result;
+
+/// A saved state of a [`Context`] that is restored when dropped, created by
+/// [`Context::save_guard`].
+///
+/// Unlike [`Context::with_pushed_context`] this allows early returns and the
+/// `?` operator while the state is saved.
+///
+/// [`Context`]: struct.Context.html
+/// [`Context::save_guard`]: struct.Context.html#method.save_guard
+/// [`Context::with_pushed_context`]: struct.Context.html#method.with_pushed_context
+#[derive(Debug)]
+pub struct StateGuard<'c, 'a> {
+    ctx: &'c mut Context<'a>,
+    cookie: Option<ContextCookie>,
+}
+
+impl<'a> Context<'a> {
+    /// Saves the current state, which is restored once the returned guard is
+    /// dropped.
+    ///
+    /// This ignores the saved state limit, see [`try_save_guard`].
+    ///
+    /// [`try_save_guard`]: #method.try_save_guard
+    #[inline]
+    pub fn save_guard(&mut self) -> StateGuard<'_, 'a> {
+        let cookie = self.save_cookie();
+        StateGuard {
+            ctx: self,
+            cookie: Some(cookie),
+        }
+    }
+
+    /// Saves the current state, which is restored once the returned guard is
+    /// dropped.
+    ///
+    /// Returns [`Error::TooManyStates`] if the saved state limit of the
+    /// context is reached.
+    ///
+    /// [`Error::TooManyStates`]: ../error/enum.Error.html#variant.TooManyStates
+    #[inline]
+    pub fn try_save_guard(&mut self) -> Result<StateGuard<'_, 'a>> {
+        let cookie = self.try_save_cookie()?;
+        Ok(StateGuard {
+            ctx: self,
+            cookie: Some(cookie),
+        })
+    }
+}
+
+impl StateGuard<'_, '_> {
+    /// Restores the saved state right away, returning the error that
+    /// dropping the guard would ignore.
+    #[inline]
+    pub fn restore(mut self) -> Result<()> {
+        match self.cookie.take() {
+            Some(cookie) => self.ctx.restore_cookie(cookie),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Deref for StateGuard<'_, 'a> {
+    type Target = Context<'a>;
+
+    #[inline]
+    fn deref(&self) -> &Context<'a> {
+        self.ctx
+    }
+}
+
+impl<'a> DerefMut for StateGuard<'_, 'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Context<'a> {
+        self.ctx
+    }
+}
+
+impl Drop for StateGuard<'_, '_> {
+    fn drop(&mut self) {
+        // states saved while the guard was alive are restored along with it
+        if let Some(cookie) = self.cookie.take() {
+            let _ = self.ctx.restore_cookie(cookie);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_state_guard {
+    use crate::context::Context;
+    use crate::error::{Error, Result};
+    use crate::image::{Image, ImageFormat};
+
+    fn draw_with_early_return(ctx: &mut Context, fail: bool) -> Result<()> {
+        let mut ctx = ctx.save_guard();
+        ctx.set_global_alpha(0.5);
+        ctx.save();
+        if fail {
+            return Err(Error::InvalidValue);
+        }
+        ctx.fill_all()
+    }
+
+    #[test]
+    fn test_save_guard() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        for &fail in &[false, true] {
+            assert_eq!(draw_with_early_return(&mut ctx, fail).is_err(), fail);
+            assert_eq!(ctx.saved_state_count(), 0);
+            assert_eq!(ctx.global_alpha(), 1.0);
+        }
+
+        let mut guard = ctx.save_guard();
+        guard.set_global_alpha(0.25);
+        guard.restore().unwrap();
+        assert_eq!(ctx.global_alpha(), 1.0);
+        ctx.end().unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+use super::{CompOp, Context, ContextHint, ContextHints, StrokeState};
+use crate::geometry::FillRule;
+use crate::path::FlattenMode;
+use crate::style::Style;
+
+/// A snapshot of the style related state of a [`Context`] that can be
+/// applied to other contexts in one call, so that contexts rendering
+/// different tiles or targets share the same configuration.
+///
+/// Created with [`Context::style_template`] or [`from_context`] and applied
+/// with [`Context::apply_style_template`]. The transformation and the clip
+/// are not part of a template, as they usually differ between targets.
+///
+/// [`Context`]: struct.Context.html
+/// [`Context::style_template`]: struct.Context.html#method.style_template
+/// [`Context::apply_style_template`]: struct.Context.html#method.apply_style_template
+/// [`from_context`]: #method.from_context
+#[derive(Clone, Debug)]
+pub struct StyleTemplate {
+    pub comp_op: CompOp,
+    pub global_alpha: f64,
+    pub hints: ContextHints,
+    pub flatten_mode: FlattenMode,
+    pub flatten_tolerance: f64,
+    pub fill_rule: FillRule,
+    /// The fill style, `None` keeps the fill style of the context the
+    /// template is applied to.
+    pub fill_style: Option<Style>,
+    pub fill_alpha: f64,
+    /// The stroke style, `None` keeps the stroke style of the context the
+    /// template is applied to.
+    pub stroke_style: Option<Style>,
+    pub stroke_alpha: f64,
+    pub stroke: StrokeState,
+}
+
+impl StyleTemplate {
+    /// Captures the style related state of the given context.
+    pub fn from_context(ctx: &Context<'_>) -> Self {
+        StyleTemplate {
+            comp_op: ctx.comp_op(),
+            global_alpha: ctx.global_alpha(),
+            hints: *ctx.hints(),
+            flatten_mode: ctx.flatten_mode(),
+            flatten_tolerance: ctx.flatten_tolerance(),
+            fill_rule: ctx.fill_rule(),
+            fill_style: ctx.fill_style(),
+            fill_alpha: ctx.fill_alpha(),
+            stroke_style: ctx.stroke_style(),
+            stroke_alpha: ctx.stroke_alpha(),
+            stroke: ctx.stroke_state(),
+        }
+    }
+}
+
+impl Context<'_> {
+    /// Captures the style related state of this context, see
+    /// [`StyleTemplate`].
+    ///
+    /// [`StyleTemplate`]: struct.StyleTemplate.html
+    #[inline]
+    pub fn style_template(&self) -> StyleTemplate {
+        StyleTemplate::from_context(self)
+    }
+
+    /// Applies all settings of the given [`StyleTemplate`] to this context.
+    ///
+    /// [`StyleTemplate`]: struct.StyleTemplate.html
+    pub fn apply_style_template(&mut self, template: &StyleTemplate) {
+        self.set_comp_op(template.comp_op);
+        self.set_global_alpha(template.global_alpha);
+        let hints = &template.hints;
+        self.set_hint(
+            ContextHint::RenderingQuality,
+            u32::from(hints.rendering_quality),
+        );
+        self.set_hint(
+            ContextHint::GradientQuality,
+            u32::from(hints.gradient_quality),
+        );
+        self.set_hint(
+            ContextHint::PatternQuality,
+            u32::from(hints.pattern_quality),
+        );
+        self.set_flatten_mode(template.flatten_mode);
+        self.set_flatten_tolerance(template.flatten_tolerance);
+        self.set_fill_rule(template.fill_rule);
+        if let Some(style) = &template.fill_style {
+            self.set_fill_style(style);
+        }
+        self.set_fill_alpha(template.fill_alpha);
+        if let Some(style) = &template.stroke_style {
+            self.set_stroke_style(style);
+        }
+        self.set_stroke_alpha(template.stroke_alpha);
+        self.set_stroke_state(&template.stroke);
+    }
+}
+
+#[cfg(test)]
+mod test_template {
+    use crate::context::{CompOp, Context};
+    use crate::geometry::FillRule;
+    use crate::image::{Image, ImageFormat};
+    use crate::path::StrokeCap;
+    use crate::style::Style;
+
+    #[test]
+    fn test_style_template() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_global_alpha(0.5);
+        ctx.set_flatten_tolerance(0.5);
+        ctx.set_fill_rule(FillRule::EvenOdd);
+        ctx.set_fill_style_rgba32(0xFF_00_FF_00);
+        ctx.set_stroke_alpha(0.25);
+        ctx.set_stroke_width(3.0);
+        ctx.set_stroke_end_cap(StrokeCap::Round);
+        let template = ctx.style_template();
+        ctx.end().unwrap();
+
+        let mut tiles = (0..2)
+            .map(|_| Image::new(4, 4, ImageFormat::PRgb32).unwrap())
+            .collect::<Vec<_>>();
+        for tile in &mut tiles {
+            let mut ctx = Context::new(tile).unwrap();
+            ctx.apply_style_template(&template);
+            assert_eq!(ctx.comp_op(), CompOp::SrcCopy);
+            assert_eq!(ctx.global_alpha(), 0.5);
+            assert_eq!(ctx.flatten_tolerance(), 0.5);
+            assert_eq!(ctx.fill_rule(), FillRule::EvenOdd);
+            assert_eq!(ctx.fill_style(), Some(Style::from_rgba32(0xFF_00_FF_00)));
+            assert_eq!(ctx.stroke_alpha(), 0.25);
+            assert_eq!(ctx.stroke_state().width, 3.0);
+            assert_eq!(ctx.stroke_state().end_cap, StrokeCap::Round);
+            ctx.end().unwrap();
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::{error, fmt};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+use std::{error, fmt, mem, process, ptr};
 
 use ffi::BLResultCode;
 
@@ -9,8 +12,8 @@ pub(in crate) fn errcode_to_result(code: u32) -> Result<()> {
     match code as _ {
         0 => Ok(()),
         #[cold]
-        BLResultCode::BL_ERROR_OUT_OF_MEMORY => panic!("memory allocation failed"),
-        _ => Err(error_from_errcode(code)),
+        BLResultCode::BL_ERROR_OUT_OF_MEMORY => Err(out_of_memory_error()),
+        _ => Err(reported(error_from_errcode(code))),
     }
 }
 
@@ -18,11 +21,169 @@ pub(in crate) fn errcode_to_result(code: u32) -> Result<()> {
 pub(in crate) fn expect_mem_err(code: u32) {
     match code as _ {
         #[cold]
-        BLResultCode::BL_ERROR_OUT_OF_MEMORY => panic!("memory allocation failed"),
+        BLResultCode::BL_ERROR_OUT_OF_MEMORY => out_of_memory(),
         _ => (),
     }
 }
 
+/// How out of memory errors reported by blend2d are handled, see
+/// [`set_oom_policy`].
+///
+/// [`set_oom_policy`]: fn.set_oom_policy.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OomPolicy {
+    /// Panic with the message `memory allocation failed`, the default.
+    Panic,
+    /// Abort the process, for when unwinding is not acceptable, for example
+    /// across foreign function boundaries.
+    Abort,
+    /// Functions returning a [`Result`] return [`Error::OutOfMemory`].
+    ///
+    /// This does not turn the other functions into fallible ones: functions
+    /// without a [`Result`], like [`Path::line_to`], still return normally.
+    /// They record the failure for the current thread instead, which has to
+    /// be checked with [`take_oom_error`], and leave the object in whatever
+    /// state blend2d left behind, usually without the failed change. Where
+    /// the failure has to be handled right away use the `try_*` variants,
+    /// like [`Path::try_line_to`], which always return it.
+    ///
+    /// [`Result`]: type.Result.html
+    /// [`Error::OutOfMemory`]: enum.Error.html#variant.OutOfMemory
+    /// [`Path::line_to`]: ../path/struct.Path.html#method.line_to
+    /// [`take_oom_error`]: fn.take_oom_error.html
+    /// [`Path::try_line_to`]: ../path/struct.Path.html#method.try_line_to
+    Report,
+}
+
+impl Default for OomPolicy {
+    fn default() -> Self {
+        OomPolicy::Panic
+    }
+}
+
+static OOM_POLICY: AtomicU8 = AtomicU8::new(OomPolicy::Panic as u8);
+
+thread_local! {
+    static OOM_PENDING: Cell<bool> = Cell::new(false);
+}
+
+/// Sets how out of memory errors are handled for the whole process.
+///
+/// The allocating `try_*` functions always return their error, regardless of
+/// the policy.
+pub fn set_oom_policy(policy: OomPolicy) {
+    OOM_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// Returns the current [`OomPolicy`].
+///
+/// [`OomPolicy`]: enum.OomPolicy.html
+pub fn oom_policy() -> OomPolicy {
+    match OOM_POLICY.load(Ordering::Relaxed) {
+        x if x == OomPolicy::Abort as u8 => OomPolicy::Abort,
+        x if x == OomPolicy::Report as u8 => OomPolicy::Report,
+        _ => OomPolicy::Panic,
+    }
+}
+
+/// Returns and resets whether an allocation failed on this thread in a
+/// function that couldn't report it since the last call, which only happens
+/// with [`OomPolicy::Report`].
+///
+/// [`OomPolicy::Report`]: enum.OomPolicy.html#variant.Report
+pub fn take_oom_error() -> std::result::Result<(), OutOfMemory> {
+    if OOM_PENDING.with(|pending| pending.replace(false)) {
+        Err(OutOfMemory)
+    } else {
+        Ok(())
+    }
+}
+
+/// A function that is called with every error reported by blend2d, see
+/// [`set_error_hook`].
+///
+/// [`set_error_hook`]: fn.set_error_hook.html
+pub type ErrorHook = fn(&Error);
+
+/// The installed [`ErrorHook`], null if there is none.
+static ERROR_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs a hook that is called with every error reported by blend2d
+/// before it is returned, or removes it with `None`, and returns the
+/// previous hook.
+///
+/// The hook is process wide and meant for translating errors into logs or
+/// metrics. Out of memory errors are passed to it before the [`OomPolicy`]
+/// is applied, including the ones of functions that can't report errors.
+/// A panic in the hook is caught and ignored, so the hook never changes how
+/// an error is handled.
+///
+/// [`OomPolicy`]: enum.OomPolicy.html
+pub fn set_error_hook(hook: Option<ErrorHook>) -> Option<ErrorHook> {
+    let hook = hook.map_or(ptr::null_mut(), |hook| hook as *mut ());
+    error_hook_from_ptr(ERROR_HOOK.swap(hook, Ordering::AcqRel))
+}
+
+/// Returns the installed [`ErrorHook`].
+///
+/// [`ErrorHook`]: type.ErrorHook.html
+pub fn error_hook() -> Option<ErrorHook> {
+    error_hook_from_ptr(ERROR_HOOK.load(Ordering::Acquire))
+}
+
+fn error_hook_from_ptr(hook: *mut ()) -> Option<ErrorHook> {
+    if hook.is_null() {
+        None
+    } else {
+        // only ever stores pointers of `ErrorHook`s
+        Some(unsafe { mem::transmute::<*mut (), ErrorHook>(hook) })
+    }
+}
+
+/// Passes the error to the installed [`ErrorHook`] and returns it.
+///
+/// [`ErrorHook`]: type.ErrorHook.html
+#[cold]
+fn reported(error: Error) -> Error {
+    if let Some(hook) = error_hook() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(&error)));
+    }
+    error
+}
+
+/// Handles a failed allocation according to the current [`OomPolicy`].
+///
+/// [`OomPolicy`]: enum.OomPolicy.html
+#[cold]
+pub(in crate) fn out_of_memory() {
+    handle_out_of_memory(oom_policy())
+}
+
+#[cold]
+fn handle_out_of_memory(policy: OomPolicy) {
+    reported(Error::OutOfMemory);
+    match policy {
+        OomPolicy::Panic => panic!("memory allocation failed"),
+        OomPolicy::Abort => process::abort(),
+        OomPolicy::Report => OOM_PENDING.with(|pending| pending.set(true)),
+    }
+}
+
+#[cold]
+fn out_of_memory_error() -> Error {
+    out_of_memory_error_with(oom_policy())
+}
+
+#[cold]
+fn out_of_memory_error_with(policy: OomPolicy) -> Error {
+    if policy == OomPolicy::Report {
+        reported(Error::OutOfMemory)
+    } else {
+        handle_out_of_memory(policy);
+        Error::OutOfMemory
+    }
+}
+
 /// An error returned by a function if it was unable to succeed due to not being
 /// able to allocate enough memory.
 #[derive(Debug)]
@@ -80,6 +241,10 @@ pub enum FontError {
 /// An error returned by blend2d.
 #[derive(Debug)]
 pub enum Error {
+    /// Only returned with [`OomPolicy::Report`].
+    ///
+    /// [`OomPolicy::Report`]: enum.OomPolicy.html#variant.Report
+    OutOfMemory,
     InvalidValue,
     InvalidState,
     InvalidHandle,
@@ -140,11 +305,26 @@ pub enum Error {
     Jpeg(JpegError),
     Font(FontError),
     InvalidGlyph,
+    /// The deadline of a [`BudgetedContext`] passed after `completed` items
+    /// of a batched drawing operation. Never returned by blend2d itself.
+    ///
+    /// [`BudgetedContext`]: ../context/struct.BudgetedContext.html
+    RenderTimeout {
+        completed: usize,
+    },
+    /// Saving the state of a [`Context`] would exceed the saved state
+    /// `limit` it was created with. Never returned by blend2d itself.
+    ///
+    /// [`Context`]: ../context/struct.Context.html
+    TooManyStates {
+        limit: usize,
+    },
 }
 
 pub(super) fn error_from_errcode(errcode: u32) -> Error {
     use BLResultCode::*;
     match errcode as ffi::BLResultCode::Type {
+        BL_ERROR_OUT_OF_MEMORY => Error::OutOfMemory,
         BL_ERROR_INVALID_VALUE => Error::InvalidValue,
         BL_ERROR_INVALID_STATE => Error::InvalidState,
         BL_ERROR_INVALID_HANDLE => Error::InvalidHandle,
@@ -226,9 +406,144 @@ pub(super) fn error_from_errcode(errcode: u32) -> Error {
     }
 }
 
+/// Maps an error back to its blend2d result code, used to report errors from
+/// Rust implemented callbacks to blend2d.
+pub(in crate) fn error_to_errcode(error: &Error) -> u32 {
+    use BLResultCode::*;
+    (match error {
+        Error::OutOfMemory => BL_ERROR_OUT_OF_MEMORY,
+        Error::InvalidValue => BL_ERROR_INVALID_VALUE,
+        Error::InvalidState => BL_ERROR_INVALID_STATE,
+        Error::InvalidHandle => BL_ERROR_INVALID_HANDLE,
+        Error::ValueTooLarge => BL_ERROR_VALUE_TOO_LARGE,
+        Error::NotInitialized => BL_ERROR_NOT_INITIALIZED,
+        Error::NotImplemented => BL_ERROR_NOT_IMPLEMENTED,
+        Error::NotPermitted => BL_ERROR_NOT_PERMITTED,
+        Error::Io => BL_ERROR_IO,
+        Error::Busy => BL_ERROR_BUSY,
+        Error::Interrupted => BL_ERROR_INTERRUPTED,
+        Error::TryAgain => BL_ERROR_TRY_AGAIN,
+        Error::TimedOut => BL_ERROR_TIMED_OUT,
+        Error::BrokenPipe => BL_ERROR_BROKEN_PIPE,
+        Error::InvalidSeek => BL_ERROR_INVALID_SEEK,
+        Error::SymlinkLoop => BL_ERROR_SYMLINK_LOOP,
+        Error::FileTooLarge => BL_ERROR_FILE_TOO_LARGE,
+        Error::AlreadyExists => BL_ERROR_ALREADY_EXISTS,
+        Error::AccessDenied => BL_ERROR_ACCESS_DENIED,
+        Error::MediaChanged => BL_ERROR_MEDIA_CHANGED,
+        Error::ReadOnlyFs => BL_ERROR_READ_ONLY_FS,
+        Error::NoDevice => BL_ERROR_NO_DEVICE,
+        Error::NoEntry => BL_ERROR_NO_ENTRY,
+        Error::NoMedia => BL_ERROR_NO_MEDIA,
+        Error::NoMoreData => BL_ERROR_NO_MORE_DATA,
+        Error::NoMoreFiles => BL_ERROR_NO_MORE_FILES,
+        Error::NoSpaceLeft => BL_ERROR_NO_SPACE_LEFT,
+        Error::NotEmpty => BL_ERROR_NOT_EMPTY,
+        Error::NotFile => BL_ERROR_NOT_FILE,
+        Error::NotDirectory => BL_ERROR_NOT_DIRECTORY,
+        Error::NotSameDevice => BL_ERROR_NOT_SAME_DEVICE,
+        Error::NotBlockDevice => BL_ERROR_NOT_BLOCK_DEVICE,
+        Error::InvalidFileName => BL_ERROR_INVALID_FILE_NAME,
+        Error::FileNameTooLong => BL_ERROR_FILE_NAME_TOO_LONG,
+        Error::TooManyOpenFiles => BL_ERROR_TOO_MANY_OPEN_FILES,
+        Error::TooManyOpenFilesByOs => BL_ERROR_TOO_MANY_OPEN_FILES_BY_OS,
+        Error::TooManyLinks => BL_ERROR_TOO_MANY_LINKS,
+        Error::TooManyThreads => BL_ERROR_TOO_MANY_THREADS,
+        Error::FileEmpty => BL_ERROR_FILE_EMPTY,
+        Error::OpenFailed => BL_ERROR_OPEN_FAILED,
+        Error::NotRootDevice => BL_ERROR_NOT_ROOT_DEVICE,
+        Error::UnknownSystemError => BL_ERROR_UNKNOWN_SYSTEM_ERROR,
+        Error::InvalidSignature => BL_ERROR_INVALID_SIGNATURE,
+        Error::InvalidData => BL_ERROR_INVALID_DATA,
+        Error::InvalidString => BL_ERROR_INVALID_STRING,
+        Error::DataTruncated => BL_ERROR_DATA_TRUNCATED,
+        Error::DataTooLarge => BL_ERROR_DATA_TOO_LARGE,
+        Error::DecompressionFailed => BL_ERROR_DECOMPRESSION_FAILED,
+        Error::InvalidGeometry => BL_ERROR_INVALID_GEOMETRY,
+        Error::NoMatchingVertex => BL_ERROR_NO_MATCHING_VERTEX,
+        Error::NoMatchingCookie => BL_ERROR_NO_MATCHING_COOKIE,
+        Error::NoStatesToRestore => BL_ERROR_NO_STATES_TO_RESTORE,
+        Error::ImageTooLarge => BL_ERROR_IMAGE_TOO_LARGE,
+        Error::ImageNoMatchingCodec => BL_ERROR_IMAGE_NO_MATCHING_CODEC,
+        Error::ImageUnknownFileFormat => BL_ERROR_IMAGE_UNKNOWN_FILE_FORMAT,
+        Error::ImageDecoderNotProvided => BL_ERROR_IMAGE_DECODER_NOT_PROVIDED,
+        Error::ImageEncoderNotProvided => BL_ERROR_IMAGE_ENCODER_NOT_PROVIDED,
+        Error::Png(PngError::MultipleIHDR) => BL_ERROR_PNG_MULTIPLE_IHDR,
+        Error::Png(PngError::InvalidIDAT) => BL_ERROR_PNG_INVALID_IDAT,
+        Error::Png(PngError::InvalidIEND) => BL_ERROR_PNG_INVALID_IEND,
+        Error::Png(PngError::InvalidPLTE) => BL_ERROR_PNG_INVALID_PLTE,
+        Error::Png(PngError::InvalidTRNS) => BL_ERROR_PNG_INVALID_TRNS,
+        Error::Png(PngError::InvalidFilter) => BL_ERROR_PNG_INVALID_FILTER,
+        Error::Jpeg(JpegError::UnsupportedFeature) => BL_ERROR_JPEG_UNSUPPORTED_FEATURE,
+        Error::Jpeg(JpegError::InvalidSOS) => BL_ERROR_JPEG_INVALID_SOS,
+        Error::Jpeg(JpegError::InvalidSOF) => BL_ERROR_JPEG_INVALID_SOF,
+        Error::Jpeg(JpegError::MultipleSOF) => BL_ERROR_JPEG_MULTIPLE_SOF,
+        Error::Jpeg(JpegError::UnsupportedSOF) => BL_ERROR_JPEG_UNSUPPORTED_SOF,
+        Error::Font(FontError::NoCharacterMapping) => BL_ERROR_FONT_NO_CHARACTER_MAPPING,
+        Error::Font(FontError::MissingImportantTable) => BL_ERROR_FONT_MISSING_IMPORTANT_TABLE,
+        Error::Font(FontError::FeatureNotAvailable) => BL_ERROR_FONT_FEATURE_NOT_AVAILABLE,
+        Error::Font(FontError::CffInvalidData) => BL_ERROR_FONT_CFF_INVALID_DATA,
+        Error::Font(FontError::ProgramTerminated) => BL_ERROR_FONT_PROGRAM_TERMINATED,
+        Error::InvalidGlyph => BL_ERROR_INVALID_GLYPH,
+        Error::RenderTimeout { .. } => BL_ERROR_TIMED_OUT,
+        Error::TooManyStates { .. } => BL_ERROR_INVALID_STATE,
+    }) as u32
+}
+
 impl error::Error for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
+
+#[cfg(test)]
+mod test_error {
+    use std::cell::RefCell;
+
+    use crate::error::{
+        errcode_to_result, error_hook, handle_out_of_memory, out_of_memory_error_with,
+        set_error_hook, take_oom_error, Error, OomPolicy,
+    };
+
+    // the policy is process wide, so the policies are tested without
+    // installing them to not affect tests running in parallel
+    #[test]
+    fn test_oom_policy_report() {
+        handle_out_of_memory(OomPolicy::Report);
+        assert!(take_oom_error().is_err());
+        assert!(take_oom_error().is_ok());
+        let error = out_of_memory_error_with(OomPolicy::Report);
+        assert!(matches!(error, Error::OutOfMemory));
+        assert!(take_oom_error().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "memory allocation failed")]
+    fn test_oom_policy_panic() {
+        out_of_memory_error_with(OomPolicy::Panic);
+    }
+
+    thread_local! {
+        static REPORTED: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    }
+
+    fn record(error: &Error) {
+        REPORTED.with(|reported| reported.borrow_mut().push(format!("{:?}", error)));
+    }
+
+    #[test]
+    fn test_error_hook() {
+        // the hook is process wide, errors of other test threads are recorded
+        // in their own thread local
+        assert!(set_error_hook(Some(record)).is_none());
+        assert!(error_hook().is_some());
+        let _ = errcode_to_result(ffi::BLResultCode::BL_ERROR_INVALID_VALUE as u32);
+        assert!(errcode_to_result(0).is_ok());
+        assert!(set_error_hook(Some(|_| ())).is_some());
+        assert!(errcode_to_result(ffi::BLResultCode::BL_ERROR_INVALID_STATE as u32).is_err());
+        set_error_hook(None);
+        let _ = errcode_to_result(ffi::BLResultCode::BL_ERROR_INVALID_VALUE as u32);
+        REPORTED.with(|reported| assert_eq!(*reported.borrow(), ["InvalidValue"]));
+    }
+}
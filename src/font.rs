@@ -1,18 +1,52 @@
+mod cmap;
+
+mod color;
+pub use self::color::{ColorGlyphFormats, ColorLayer};
+
 mod face;
 pub use self::face::FontFace;
 
+mod fallback;
+pub use self::fallback::FallbackFont;
+
+mod kerning;
+
+mod layout;
+pub use self::layout::{TextAlign, TextLayout, TextLayoutOptions, TextLine, TextRun, TextWrap};
+
 mod manager;
 pub use self::manager::FontManager;
 
+mod shaped;
+pub use self::shaped::ShapedRun;
+
 mod data;
 pub use self::data::FontData;
 
-use std::fmt;
+mod summary;
+pub use self::summary::{FaceInfoSummary, Faces};
+
+mod system;
+pub use self::system::{system_font_dirs, system_fonts, SystemFonts};
+
+mod table;
+
+mod usage;
+pub use self::usage::GlyphUsageTracker;
+
+mod variations;
+pub use self::variations::FontVariationAxis;
+
+use std::ffi::c_void;
+use std::{fmt, mem};
 
 use crate::array::Array;
-use crate::error::{errcode_to_result, Result};
+use crate::error::{errcode_to_result, Error, Result};
 use crate::font_defs::*;
-use crate::glyph_buffer::GlyphBuffer;
+use crate::geometry::{BoxI, PointI};
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+use crate::matrix::Matrix2D;
+use crate::path::{Path, PathSink};
 use crate::util::cast_ref;
 use crate::variant::WrappedBlCore;
 
@@ -152,7 +186,22 @@ impl Font {
         }
     }
 
-    // TODO positionGlyphs
+    /// Computes the placements of the glyphs of a buffer that has been mapped
+    /// with [`map_text_to_glyphs`], which can then be adjusted with
+    /// [`GlyphBuffer::placements_mut`].
+    ///
+    /// [`map_text_to_glyphs`]: #method.map_text_to_glyphs
+    /// [`GlyphBuffer::placements_mut`]: ../glyph_buffer/struct.GlyphBuffer.html#method.placements_mut
+    #[inline]
+    pub fn position_glyphs(&self, buf: &mut GlyphBuffer) -> Result<()> {
+        unsafe {
+            errcode_to_result(ffi::blFontPositionGlyphs(
+                self.core(),
+                &mut buf.core,
+                0xFFFF_FFFF,
+            ))
+        }
+    }
 
     #[inline]
     pub fn apply_kerning(&self, buf: &mut GlyphBuffer) -> Result<()> {
@@ -183,6 +232,15 @@ impl Font {
         }
     }
 
+    /// Computes the metrics of a shaped glyph buffer from its current
+    /// placements.
+    ///
+    /// The buffer is not shaped again, so it can be measured and then passed
+    /// on to [`get_glyph_run_outlines`] or rendered as is, see also
+    /// [`ShapedRun`].
+    ///
+    /// [`get_glyph_run_outlines`]: #method.get_glyph_run_outlines
+    /// [`ShapedRun`]: struct.ShapedRun.html
     #[inline]
     pub fn get_text_metrics(&self, buf: &mut GlyphBuffer) -> Result<TextMetrics> {
         let mut metrics = TextMetrics::default();
@@ -196,13 +254,217 @@ impl Font {
         }
     }
 
-    //TODO getGlyphBounds
+    /// Computes the bounding boxes of the given glyphs relative to their
+    /// origins, in font design units.
+    pub fn glyph_bounds(&self, glyphs: &[GlyphId]) -> Result<Array<BoxI>> {
+        let mut bounds = vec![BoxI::default(); glyphs.len()];
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphBounds(
+                self.core(),
+                glyphs.as_ptr() as *const _,
+                mem::size_of::<GlyphId>() as isize,
+                bounds.as_mut_ptr() as *mut _,
+                glyphs.len(),
+            ))?;
+        }
+        Ok(bounds.into())
+    }
+
+    /// Computes the advances of the given glyphs without applying kerning or
+    /// any other positioning, in font design units.
+    pub fn glyph_advances(&self, glyphs: &[GlyphId]) -> Result<Array<PointI>> {
+        let mut placements = vec![GlyphPlacement::default(); glyphs.len()];
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphAdvances(
+                self.core(),
+                glyphs.as_ptr() as *const _,
+                mem::size_of::<GlyphId>() as isize,
+                placements.as_mut_ptr() as *mut _,
+                glyphs.len(),
+            ))?;
+        }
+        Ok(placements.iter().map(|placement| placement.advance).collect())
+    }
+
+    /// Appends the outlines of the glyph to the given path, transformed by
+    /// the optional matrix.
+    pub fn get_glyph_outlines<'m, M>(
+        &self,
+        glyph_id: GlyphId,
+        matrix: M,
+        out: &mut Path,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+    {
+        self.get_glyph_outlines_with(glyph_id, matrix, out, |_, _| Ok(()))
+    }
+
+    /// Appends the outlines of the glyph to the given path, transformed by
+    /// the optional matrix.
+    ///
+    /// The sink is invoked after the outlines of the glyph have been appended
+    /// to the path. Returning an error from the sink aborts the operation and
+    /// returns the error.
+    pub fn get_glyph_outlines_with<'m, M, F>(
+        &self,
+        glyph_id: GlyphId,
+        matrix: M,
+        out: &mut Path,
+        sink: F,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+        F: FnMut(&mut Path, &GlyphOutlineSinkInfo) -> Result<()>,
+    {
+        let matrix = matrix.into().unwrap_or(&Matrix2D::IDENTITY);
+        let mut sink = OutlineSink::new(sink);
+        let res = unsafe {
+            ffi::blFontGetGlyphOutlines(
+                self.core(),
+                u32::from(glyph_id),
+                matrix as *const _ as *const _,
+                out.core_mut(),
+                Some(outline_sink_callback::<F>),
+                &mut sink as *mut _ as *mut _,
+            )
+        };
+        sink.finish(res)
+    }
+
+    /// Appends the outlines of all glyphs of the glyph run to the given path,
+    /// transformed by the optional matrix.
+    pub fn get_glyph_run_outlines<'m, M>(
+        &self,
+        glyph_run: GlyphRun<'_>,
+        matrix: M,
+        out: &mut Path,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+    {
+        self.get_glyph_run_outlines_with(glyph_run, matrix, out, |_, _| Ok(()))
+    }
+
+    /// Appends the outlines of all glyphs of the glyph run to the given path,
+    /// transformed by the optional matrix.
+    ///
+    /// The sink is invoked after the outlines of each glyph have been
+    /// appended to the path, with [`GlyphOutlineSinkInfo::glyph_index`]
+    /// being the index of the glyph in the run. Returning an error from the
+    /// sink aborts the operation and returns the error.
+    ///
+    /// [`GlyphOutlineSinkInfo::glyph_index`]: ../font_defs/struct.GlyphOutlineSinkInfo.html#structfield.glyph_index
+    pub fn get_glyph_run_outlines_with<'m, M, F>(
+        &self,
+        glyph_run: GlyphRun<'_>,
+        matrix: M,
+        out: &mut Path,
+        sink: F,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+        F: FnMut(&mut Path, &GlyphOutlineSinkInfo) -> Result<()>,
+    {
+        let matrix = matrix.into().unwrap_or(&Matrix2D::IDENTITY);
+        let mut sink = OutlineSink::new(sink);
+        let res = unsafe {
+            ffi::blFontGetGlyphRunOutlines(
+                self.core(),
+                glyph_run.raw,
+                matrix as *const _ as *const _,
+                out.core_mut(),
+                Some(outline_sink_callback::<F>),
+                &mut sink as *mut _ as *mut _,
+            )
+        };
+        sink.finish(res)
+    }
+
+    /// Pushes the outlines of the glyph, transformed by the optional matrix,
+    /// into the given [`PathSink`].
+    ///
+    /// [`PathSink`]: ../path/trait.PathSink.html
+    pub fn get_glyph_outlines_into<'m, M, S>(
+        &self,
+        glyph_id: GlyphId,
+        matrix: M,
+        sink: &mut S,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+        S: PathSink + ?Sized,
+    {
+        let mut path = Path::new();
+        self.get_glyph_outlines(glyph_id, matrix, &mut path)?;
+        path.replay(sink);
+        Ok(())
+    }
+
+    /// Pushes the outlines of all glyphs of the glyph run, transformed by the
+    /// optional matrix, into the given [`PathSink`].
+    ///
+    /// [`PathSink`]: ../path/trait.PathSink.html
+    pub fn get_glyph_run_outlines_into<'m, M, S>(
+        &self,
+        glyph_run: GlyphRun<'_>,
+        matrix: M,
+        sink: &mut S,
+    ) -> Result<()>
+    where
+        M: Into<Option<&'m Matrix2D>>,
+        S: PathSink + ?Sized,
+    {
+        let mut path = Path::new();
+        self.get_glyph_run_outlines(glyph_run, matrix, &mut path)?;
+        path.replay(sink);
+        Ok(())
+    }
+}
+
+/// The state of a user provided outline sink, keeping track of the error the
+/// sink returned.
+struct OutlineSink<F> {
+    func: F,
+    err: Option<Error>,
+}
 
-    //TODO getGlyphAdvances
+impl<F> OutlineSink<F> {
+    #[inline]
+    fn new(func: F) -> Self {
+        OutlineSink { func, err: None }
+    }
 
-    //TODO getGlyphOutlines
+    /// Turns the result code of the sink consuming call into a result,
+    /// preferring the error returned by the sink.
+    #[inline]
+    fn finish(self, res: ffi::BLResult) -> Result<()> {
+        match self.err {
+            Some(err) => Err(err),
+            None => errcode_to_result(res),
+        }
+    }
+}
 
-    //TODO getGlyphRunOutlines
+unsafe extern "C" fn outline_sink_callback<F>(
+    path: *mut ffi::BLPathCore,
+    info: *const c_void,
+    closure: *mut c_void,
+) -> ffi::BLResult
+where
+    F: FnMut(&mut Path, &GlyphOutlineSinkInfo) -> Result<()>,
+{
+    let sink = &mut *(closure as *mut OutlineSink<F>);
+    let path = &mut *(path as *mut Path);
+    let info = &*(info as *const GlyphOutlineSinkInfo);
+    match (sink.func)(path, info) {
+        Ok(()) => 0,
+        Err(err) => {
+            sink.err = Some(err);
+            // any error aborts the operation, the actual one is kept in the sink
+            ffi::BLResultCode::BL_ERROR_INVALID_STATE as ffi::BLResult
+        },
+    }
 }
 
 impl PartialEq for Font {
@@ -229,3 +491,68 @@ impl fmt::Debug for Font {
         f.debug_struct("Font").finish()
     }
 }
+
+#[cfg(test)]
+mod test_font {
+    use crate::font::FontFace;
+    use crate::glyph_buffer::GlyphBuffer;
+    use crate::path::{Path, PathSegment};
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_glyph_bounds_and_advances() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("a .");
+        font.map_text_to_glyphs(&mut buf).unwrap();
+        let glyphs = buf.glyph_run().glyph_ids().collect::<Vec<_>>();
+
+        let bounds = font.glyph_bounds(&glyphs).unwrap();
+        assert_eq!(bounds.len(), 3);
+        assert!(bounds[0].x1 > bounds[0].x0 && bounds[0].y1 > bounds[0].y0);
+
+        let advances = font.glyph_advances(&glyphs).unwrap();
+        assert_eq!(advances.len(), 3);
+        assert!(advances.iter().all(|advance| advance.x > 0 && advance.y == 0));
+    }
+
+    #[test]
+    fn test_font_matrix() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let scale = 16.0 / f64::from(font.units_per_em());
+        let [m00, m01, m10, m11] = font.font_matrix().0;
+        assert!((m00 - scale).abs() < 1e-9 && (m11 + scale).abs() < 1e-9);
+        assert_eq!((m01, m10), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_glyph_outlines_into() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("ab");
+        font.map_text_to_glyphs(&mut buf).unwrap();
+
+        let mut expected = Path::new();
+        font.get_glyph_run_outlines(buf.glyph_run(), None, &mut expected)
+            .unwrap();
+        let mut segments = Vec::new();
+        font.get_glyph_run_outlines_into(buf.glyph_run(), None, &mut |segment: PathSegment| {
+            segments.push(segment)
+        })
+        .unwrap();
+        assert_eq!(segments.len(), expected.iter().count());
+
+        let glyph_id = buf.glyph_run().glyph_ids().next().unwrap();
+        let mut expected = Path::new();
+        font.get_glyph_outlines(glyph_id, None, &mut expected)
+            .unwrap();
+        let mut path = Path::new();
+        font.get_glyph_outlines_into(glyph_id, None, &mut path)
+            .unwrap();
+        assert_eq!(path, expected);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::Result;
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+
+use super::FontFace;
+
+impl FontFace {
+    /// Returns the glyph this font-face maps the character to, or `None` if
+    /// it has no glyph for it.
+    pub fn glyph_id(&self, c: char) -> Result<Option<GlyphId>> {
+        let mut buf = [0; 4];
+        let glyphs = self.map_chars(c.encode_utf8(&mut buf))?;
+        Ok(glyphs.first().copied().filter(|id| !id.is_notdef()))
+    }
+
+    /// Maps every character of the text to a glyph with the character map of
+    /// this font-face, characters without a glyph are mapped to
+    /// [`GlyphId::NOTDEF`].
+    ///
+    /// Unlike shaping this maps characters one to one, which makes it cheap
+    /// to check the coverage of a text, for example to pick fallback fonts.
+    ///
+    /// [`GlyphId::NOTDEF`]: ../glyph_buffer/struct.GlyphId.html#associatedconstant.NOTDEF
+    pub fn map_chars(&self, text: &str) -> Result<Vec<GlyphId>> {
+        // the mapping doesn't depend on the size, any font of the face will do
+        let font = self.create_font(self.units_per_em() as f32)?;
+        let mut buf = GlyphBuffer::from_utf8_text(text);
+        font.map_text_to_glyphs(&mut buf)?;
+        Ok(buf.glyph_run().glyph_ids().collect())
+    }
+}
+
+#[cfg(test)]
+mod test_cmap {
+    use crate::font::FontFace;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_char_to_glyph() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let a = face.glyph_id('a').unwrap();
+        assert!(a.is_some());
+        assert_eq!(face.glyph_id('\u{E000}').unwrap(), None);
+
+        let glyphs = face.map_chars("aä\u{E000}").unwrap();
+        assert_eq!(glyphs.len(), 3);
+        assert_eq!(Some(glyphs[0]), a);
+        assert!(!glyphs[1].is_notdef() && glyphs[2].is_notdef());
+    }
+}
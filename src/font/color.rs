@@ -0,0 +1,195 @@
+use bitflags::bitflags;
+
+use crate::glyph_buffer::GlyphId;
+use crate::rgba::Rgba32;
+use crate::Tag;
+
+use super::table::{read_u16, read_u32};
+use super::FontFace;
+
+bitflags! {
+    /// The color glyph tables of a font-face, see
+    /// [`FontFace::color_glyph_formats`].
+    ///
+    /// [`FontFace::color_glyph_formats`]: struct.FontFace.html#method.color_glyph_formats
+    #[derive(Default)]
+    pub struct ColorGlyphFormats: u32 {
+        /// Glyphs made of layers of colored outlines, the `COLR` and `CPAL`
+        /// tables.
+        const COLR = 0x01;
+        /// Embedded bitmaps as used by Apple, the `sbix` table.
+        const SBIX = 0x02;
+        /// Embedded bitmaps as used by Google, the `CBDT` and `CBLC` tables.
+        const CBDT = 0x04;
+        /// Glyphs defined as SVG documents, the `SVG ` table.
+        const SVG  = 0x08;
+    }
+}
+
+/// A layer of a color glyph, as returned by [`FontFace::color_layers`].
+///
+/// [`FontFace::color_layers`]: struct.FontFace.html#method.color_layers
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ColorLayer {
+    /// The glyph whose outline makes up the layer.
+    pub glyph_id: GlyphId,
+    /// The color of the layer, `None` meaning the color the text is drawn
+    /// with.
+    pub color: Option<Rgba32>,
+}
+
+impl FontFace {
+    /// Returns the color glyph tables this font-face has.
+    ///
+    /// Only [`ColorGlyphFormats::COLR`] glyphs can be rendered, with
+    /// [`Context::fill_color_glyph_buffer`]. Blend2D itself renders the
+    /// outlines of all glyphs, which color fonts may leave empty.
+    ///
+    /// [`ColorGlyphFormats::COLR`]: struct.ColorGlyphFormats.html#associatedconstant.COLR
+    /// [`Context::fill_color_glyph_buffer`]: ../context/struct.Context.html#method.fill_color_glyph_buffer
+    pub fn color_glyph_formats(&self) -> ColorGlyphFormats {
+        let has_table = |tag: &[u8; 4]| !self.table(tag).is_empty();
+        let mut formats = ColorGlyphFormats::empty();
+        formats.set(
+            ColorGlyphFormats::COLR,
+            has_table(b"COLR") && has_table(b"CPAL"),
+        );
+        formats.set(ColorGlyphFormats::SBIX, has_table(b"sbix"));
+        formats.set(
+            ColorGlyphFormats::CBDT,
+            has_table(b"CBDT") && has_table(b"CBLC"),
+        );
+        formats.set(ColorGlyphFormats::SVG, has_table(b"SVG "));
+        formats
+    }
+
+    /// Checks whether this font-face has color glyphs of any format.
+    #[inline]
+    pub fn has_color_glyphs(&self) -> bool {
+        !self.color_glyph_formats().is_empty()
+    }
+
+    /// Returns the layers of the glyph from bottom to top, with the colors of
+    /// the given palette, or `None` if it isn't a `COLR` color glyph.
+    ///
+    /// Palette `0` is the default palette, colors of palettes the font-face
+    /// doesn't have are taken from the default palette.
+    pub fn color_layers(&self, glyph_id: GlyphId, palette: usize) -> Option<Vec<ColorLayer>> {
+        let colr = self.table(b"COLR");
+        let cpal = self.table(b"CPAL");
+        colr_layers(colr, glyph_id)?
+            .map(|(glyph_id, palette_index)| {
+                let color = match palette_index {
+                    0xFFFF => None,
+                    index => Some(
+                        cpal_color(cpal, palette, index).or_else(|| cpal_color(cpal, 0, index))?,
+                    ),
+                };
+                Some(ColorLayer { glyph_id, color })
+            })
+            .collect()
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> &[u8] {
+        let (table, _) = self
+            .font_data()
+            .query_table(self.face_index(), Tag::from_bytes(*tag));
+        table.data
+    }
+}
+
+/// Looks up the layers of a base glyph in a version 0 `COLR` table, as pairs
+/// of the layer glyph and its palette entry.
+fn colr_layers(
+    data: &[u8],
+    glyph_id: GlyphId,
+) -> Option<impl Iterator<Item = (GlyphId, u16)> + '_> {
+    let base_count = usize::from(read_u16(data, 2)?);
+    let base_offset = read_u32(data, 4)? as usize;
+    let layer_offset = read_u32(data, 8)? as usize;
+    // the base glyph records are sorted by their glyph id
+    let (mut lo, mut hi) = (0, base_count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let record = base_offset + mid * 6;
+        let id = read_u16(data, record)?;
+        if id < glyph_id.value() {
+            lo = mid + 1;
+        } else if id > glyph_id.value() {
+            hi = mid;
+        } else {
+            let first = usize::from(read_u16(data, record + 2)?);
+            let count = usize::from(read_u16(data, record + 4)?);
+            // validate all layers upfront so the iterator can't fail
+            data.get(layer_offset..layer_offset + (first + count) * 4)?;
+            return Some((first..first + count).map(move |layer| {
+                let record = layer_offset + layer * 4;
+                (
+                    GlyphId(read_u16(data, record).unwrap()),
+                    read_u16(data, record + 2).unwrap(),
+                )
+            }));
+        }
+    }
+    None
+}
+
+/// Reads an entry of a palette of a `CPAL` table.
+fn cpal_color(data: &[u8], palette: usize, index: u16) -> Option<Rgba32> {
+    let entry_count = read_u16(data, 2)?;
+    let palette_count = usize::from(read_u16(data, 4)?);
+    if index >= entry_count || palette >= palette_count {
+        return None;
+    }
+    let records = read_u32(data, 8)? as usize;
+    let first = usize::from(read_u16(data, 12 + palette * 2)?);
+    let record = records + (first + usize::from(index)) * 4;
+    match data.get(record..record + 4)? {
+        &[b, g, r, a] => Some(Rgba32::from_rgba(r, g, b, a)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_color {
+    use super::{colr_layers, cpal_color};
+    use crate::font::{ColorGlyphFormats, FontFace};
+    use crate::glyph_buffer::GlyphId;
+    use crate::rgba::Rgba32;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_colr_cpal() {
+        #[rustfmt::skip]
+        let colr: &[u8] = &[
+            0, 0, 0, 1, // version 0, 1 base glyph
+            0, 0, 0, 14, 0, 0, 0, 20, 0, 2, // base and layer offsets, 2 layers
+            0, 7, 0, 0, 0, 2, // glyph 7 => layers 0..2
+            0, 8, 0, 1, // glyph 8, palette entry 1
+            0, 9, 0xFF, 0xFF, // glyph 9, foreground
+        ];
+        let layers = colr_layers(colr, GlyphId(7)).unwrap().collect::<Vec<_>>();
+        assert_eq!(layers, vec![(GlyphId(8), 1), (GlyphId(9), 0xFFFF)]);
+        assert!(colr_layers(colr, GlyphId(8)).is_none());
+        assert!(colr_layers(&colr[..22], GlyphId(7)).is_none());
+
+        #[rustfmt::skip]
+        let cpal: &[u8] = &[
+            0, 0, 0, 2, 0, 1, 0, 2, 0, 0, 0, 14, // 2 entries, 1 palette
+            0, 0, // palette 0 starts at record 0
+            0x00, 0x00, 0xFF, 0xFF, // opaque red
+            0xFF, 0x00, 0x00, 0x80, // translucent blue
+        ];
+        assert_eq!(cpal_color(cpal, 0, 1), Some(Rgba32::new(0x80_00_00_FF)));
+        assert_eq!(cpal_color(cpal, 0, 2), None);
+        assert_eq!(cpal_color(cpal, 1, 0), None);
+    }
+
+    #[test]
+    fn test_outline_font_has_no_color_glyphs() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        assert_eq!(face.color_glyph_formats(), ColorGlyphFormats::empty());
+        assert!(face.color_layers(GlyphId(36), 0).is_none());
+    }
+}
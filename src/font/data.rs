@@ -84,28 +84,39 @@ impl FontData {
     }
 
     pub fn query_table(&self, face_index: u32, tag: Tag) -> (FontTable<'_>, usize) {
-        self.query_tables(face_index, &[tag])
+        let (mut tables, n) = self.query_tables(face_index, &[tag]);
+        (tables.remove(0), n)
     }
 
-    pub fn query_tables(&self, face_index: u32, tags: &[Tag]) -> (FontTable<'_>, usize) {
+    /// Queries the tables of the given tags, returning one table per tag and
+    /// the number of tables found. Missing tables are empty.
+    pub fn query_tables(&self, face_index: u32, tags: &[Tag]) -> (Vec<FontTable<'_>>, usize) {
+        let empty = ffi::BLFontTable {
+            data: std::ptr::null(),
+            size: 0,
+        };
+        // blend2d writes one table per tag
+        let mut dst = vec![empty; tags.len()];
         unsafe {
-            let mut dst = ffi::BLFontTable {
-                data: std::ptr::null(),
-                size: 0,
-            };
             let n = ffi::blFontDataQueryTables(
                 self.core(),
                 face_index,
-                &mut dst,
+                dst.as_mut_ptr(),
                 tags.as_ptr() as *const _ as *const _,
                 tags.len(),
             );
-            (
-                FontTable {
-                    data: slice::from_raw_parts(dst.data, dst.size),
-                },
-                n,
-            )
+            let tables = dst
+                .iter()
+                .map(|table| FontTable {
+                    // missing tables are reported as null data
+                    data: if table.data.is_null() {
+                        &[]
+                    } else {
+                        slice::from_raw_parts(table.data, table.size)
+                    },
+                })
+                .collect();
+            (tables, n)
         }
     }
 
@@ -165,3 +176,26 @@ impl fmt::Debug for FontData {
         f.debug_struct("FontData").finish()
     }
 }
+
+#[cfg(test)]
+mod test_data {
+    use crate::array::Array;
+    use crate::font::FontData;
+    use crate::{DataAccessFlags, Tag};
+
+    #[test]
+    fn test_query_tables() {
+        let data =
+            FontData::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let tags = Array::<Tag>::from(&[Tag::from_bytes(*b"head"), Tag::from_bytes(*b"zzzz")][..]);
+        let (tables, found) = data.query_tables(0, &tags);
+        assert_eq!(found, 1);
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].data.len(), 54);
+        assert!(tables[1].data.is_empty());
+
+        let (table, found) = data.query_table(0, Tag::from_bytes(*b"head"));
+        assert_eq!((table.data, found), (tables[0].data, 1));
+        assert!(data.list_tags(0).unwrap().contains(&tags[0]));
+    }
+}
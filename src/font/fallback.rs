@@ -0,0 +1,154 @@
+use std::ops::Range;
+
+use crate::context::Context;
+use crate::error::Result;
+use crate::geometry::PointD;
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+
+use super::Font;
+
+/// An ordered list of fonts where each character of a text is rendered with
+/// the first font that has a glyph for it.
+///
+/// This allows rendering text mixing scripts or containing emoji that no
+/// single font covers. Characters none of the fonts cover are rendered with
+/// the first font.
+#[derive(Debug, Default)]
+pub struct FallbackFont {
+    fonts: Vec<Font>,
+}
+
+impl FallbackFont {
+    /// Creates a fallback chain of the given fonts, in the order they are
+    /// tried.
+    pub fn new(fonts: Vec<Font>) -> Self {
+        FallbackFont { fonts }
+    }
+
+    /// Appends a font to the end of the chain.
+    pub fn push(&mut self, font: Font) {
+        self.fonts.push(font);
+    }
+
+    /// The fonts of the chain.
+    #[inline]
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
+    }
+
+    /// Splits the text into segments rendered with the same font, returning
+    /// their byte ranges and the index of their font.
+    ///
+    /// A character stays in the current segment if its font covers it, so
+    /// characters most fonts cover, like spaces, don't split segments.
+    pub fn segments(&self, text: &str) -> Result<Vec<(Range<usize>, usize)>> {
+        if self.fonts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut coverage = Vec::with_capacity(self.fonts.len());
+        let mut buf = GlyphBuffer::new();
+        for font in &self.fonts {
+            buf.set_utf8_text(text);
+            font.map_text_to_glyphs(&mut buf)?;
+            coverage.push(buf.glyph_run().glyph_ids().collect::<Vec<GlyphId>>());
+        }
+        let covers = |font: usize, char_index: usize| {
+            coverage[font]
+                .get(char_index)
+                .map_or(false, |id| !id.is_notdef())
+        };
+        Ok(split_segments(text, self.fonts.len(), covers))
+    }
+
+    /// Fills the text with its baseline starting at the given point, each
+    /// segment with its font, see [`segments`].
+    ///
+    /// Segments are shaped separately, so there is no kerning or other
+    /// positioning across segment boundaries. Does nothing if the chain is
+    /// empty.
+    ///
+    /// [`segments`]: #method.segments
+    pub fn fill_text(&self, ctx: &mut Context<'_>, dst: PointD, text: &str) -> Result<()> {
+        let mut pen = dst;
+        let mut buf = GlyphBuffer::new();
+        for (range, font) in self.segments(text)? {
+            let font = &self.fonts[font];
+            buf.set_utf8_text(&text[range]);
+            font.shape(&mut buf)?;
+            ctx.fill_glyph_buffer(pen, font, &buf)?;
+            pen.x += font.get_text_metrics(&mut buf)?.advance.x;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the text into segments of the first of `font_count` fonts that
+/// covers each character, given whether a font covers the character at an
+/// index.
+fn split_segments<F>(text: &str, font_count: usize, covers: F) -> Vec<(Range<usize>, usize)>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let mut segments: Vec<(Range<usize>, usize)> = Vec::new();
+    for (char_index, (start, c)) in text.char_indices().enumerate() {
+        let end = start + c.len_utf8();
+        if let Some((range, font)) = segments.last_mut() {
+            if covers(*font, char_index) {
+                range.end = end;
+                continue;
+            }
+        }
+        let font = (0..font_count)
+            .find(|&font| covers(font, char_index))
+            .unwrap_or(0);
+        match segments.last_mut() {
+            Some((range, last)) if *last == font => range.end = end,
+            _ => segments.push((start..end, font)),
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod test_fallback {
+    use super::split_segments;
+    use crate::font::{FallbackFont, FontFace};
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_split_segments() {
+        // font 0 covers ASCII only, font 1 everything but 'x'
+        let text = "ab \u{3b1}\u{3b2} c x\u{E000}";
+        let chars = text.chars().collect::<Vec<_>>();
+        let covers = |font, i: usize| match font {
+            0 => chars[i].is_ascii(),
+            _ => chars[i] != 'x',
+        };
+        let segments = split_segments(text, 2, covers)
+            .into_iter()
+            .map(|(range, font)| (&text[range], font))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            segments,
+            vec![
+                ("ab ", 0),
+                ("\u{3b1}\u{3b2} c ", 1),
+                ("x", 0),
+                ("\u{E000}", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fallback_font_segments() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut fallback = FallbackFont::default();
+        assert!(fallback.segments("ab").unwrap().is_empty());
+        fallback.push(font.clone());
+        fallback.push(font);
+        let text = "ab \u{E000} c";
+        assert_eq!(fallback.segments(text).unwrap(), vec![(0..text.len(), 0)]);
+    }
+}
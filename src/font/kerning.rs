@@ -0,0 +1,117 @@
+use crate::glyph_buffer::GlyphId;
+use crate::Tag;
+
+use super::table::{read_u16, read_u32};
+use super::Font;
+
+impl Font {
+    /// Returns the horizontal kerning between the two glyphs in user units,
+    /// as defined by the legacy `kern` table of the font-face.
+    ///
+    /// This is meant for simple layouts that don't need full text shaping,
+    /// kerning defined through OpenType GPOS features is not taken into
+    /// account. Returns `None` if the font-face has no `kern` table with
+    /// horizontal kerning pairs, and `Some(0.0)` if the pair isn't kerned.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<f64> {
+        let face = self.face();
+        let (table, _) = face
+            .font_data()
+            .query_table(face.face_index(), Tag::from_bytes(*b"kern"));
+        let value = kern_table_lookup(table.data, left, right)?;
+        Some(f64::from(value) * f64::from(self.size()) / f64::from(self.units_per_em()))
+    }
+}
+
+/// Looks up the kerning value of the pair in the horizontal format 0
+/// subtables of a `kern` table, supporting both the OpenType and the Apple
+/// table versions.
+fn kern_table_lookup(data: &[u8], left: GlyphId, right: GlyphId) -> Option<i32> {
+    let apple = read_u16(data, 0)? == 1;
+    let (table_count, mut offset) = if apple {
+        (read_u32(data, 4)?, 8)
+    } else {
+        (u32::from(read_u16(data, 2)?), 4)
+    };
+    let mut value = None;
+    for _ in 0..table_count {
+        // normalize both header layouts to the length, format and whether
+        // the subtable contains plain horizontal kerning values
+        let (length, format, horizontal, header_size) = if apple {
+            let coverage = read_u16(data, offset + 4)?;
+            (
+                read_u32(data, offset)? as usize,
+                coverage & 0xFF,
+                coverage & 0xE000 == 0,
+                8,
+            )
+        } else {
+            let coverage = read_u16(data, offset + 4)?;
+            (
+                usize::from(read_u16(data, offset + 2)?),
+                coverage >> 8,
+                coverage & 0x07 == 0x01,
+                6,
+            )
+        };
+        if length < header_size {
+            return value;
+        }
+        if format == 0 && horizontal {
+            let pairs = offset + header_size;
+            let pair_count = usize::from(read_u16(data, pairs)?);
+            let key = u32::from(left) << 16 | u32::from(right);
+            // the pairs are sorted by their combined glyph ids
+            let (mut lo, mut hi) = (0, pair_count);
+            let mut found = 0;
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let entry = pairs + 8 + mid * 6;
+                let entry_key = read_u32(data, entry)?;
+                if entry_key < key {
+                    lo = mid + 1;
+                } else if entry_key > key {
+                    hi = mid;
+                } else {
+                    found = i32::from(read_u16(data, entry + 4)? as i16);
+                    break;
+                }
+            }
+            value = Some(value.unwrap_or(0) + found);
+        }
+        offset += length;
+    }
+    value
+}
+
+#[cfg(test)]
+mod test_kerning {
+    use super::kern_table_lookup;
+    use crate::font::{Font, FontFace};
+    use crate::glyph_buffer::GlyphId;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_kern_table_lookup() {
+        #[rustfmt::skip]
+        let table: &[u8] = &[
+            0, 0, 0, 1, // version 0, 1 subtable
+            0, 0, 0, 26, 0x00, 0x01, // format 0, horizontal
+            0, 2, 0, 12, 0, 1, 0, 0, // 2 pairs
+            0, 3, 0, 5, 0xFF, 0xF6, // (3, 5) => -10
+            0, 4, 0, 1, 0, 20, // (4, 1) => 20
+        ];
+        let (a, b, c, d) = (GlyphId(1), GlyphId(3), GlyphId(4), GlyphId(5));
+        assert_eq!(kern_table_lookup(table, b, d), Some(-10));
+        assert_eq!(kern_table_lookup(table, c, a), Some(20));
+        assert_eq!(kern_table_lookup(table, a, c), Some(0));
+        assert_eq!(kern_table_lookup(&[], b, d), None);
+    }
+
+    #[test]
+    fn test_font_kerning_without_kern_table() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = Font::from_face(&face, 16.0).unwrap();
+        assert_eq!(font.kerning(GlyphId(36), GlyphId(57)), None);
+    }
+}
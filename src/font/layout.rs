@@ -0,0 +1,349 @@
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::geometry::{PointD, RectD};
+use crate::glyph_buffer::GlyphBuffer;
+
+use super::Font;
+
+/// The horizontal alignment of the lines of a [`TextLayout`].
+///
+/// [`TextLayout`]: struct.TextLayout.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretches the space between the words of a line to fill the width of
+    /// the layout rectangle, except for the last line of a paragraph, which
+    /// is aligned left.
+    Justify,
+}
+
+/// How the text of a [`TextLayout`] is broken into lines.
+///
+/// [`TextLayout`]: struct.TextLayout.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextWrap {
+    /// Lines are only broken at line feeds.
+    None,
+    /// Lines are additionally broken at whitespace before they get wider than
+    /// the layout rectangle. Words wider than the rectangle overflow it.
+    Word,
+}
+
+/// Options of a [`TextLayout`].
+///
+/// [`TextLayout`]: struct.TextLayout.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextLayoutOptions {
+    pub align: TextAlign,
+    pub wrap: TextWrap,
+    /// The factor the line height of the font is multiplied with.
+    pub line_spacing: f64,
+}
+
+impl Default for TextLayoutOptions {
+    fn default() -> Self {
+        TextLayoutOptions {
+            align: TextAlign::Left,
+            wrap: TextWrap::Word,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+/// A shaped piece of text of a [`TextLine`] and its position.
+///
+/// [`TextLine`]: struct.TextLine.html
+#[derive(Debug)]
+pub struct TextRun {
+    origin: PointD,
+    range: Range<usize>,
+    glyphs: GlyphBuffer,
+}
+
+impl TextRun {
+    /// The start of the run on the baseline.
+    #[inline]
+    pub fn origin(&self) -> PointD {
+        self.origin
+    }
+
+    /// The byte range of the run in the laid out text.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The shaped glyphs of the run.
+    #[inline]
+    pub fn glyphs(&self) -> &GlyphBuffer {
+        &self.glyphs
+    }
+}
+
+/// A line of a [`TextLayout`].
+///
+/// [`TextLayout`]: struct.TextLayout.html
+#[derive(Debug)]
+pub struct TextLine {
+    baseline: f64,
+    width: f64,
+    range: Range<usize>,
+    runs: Vec<TextRun>,
+}
+
+impl TextLine {
+    /// The vertical position of the baseline of the line.
+    #[inline]
+    pub fn baseline(&self) -> f64 {
+        self.baseline
+    }
+
+    /// The advance width of the line.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// The byte range of the line in the laid out text, without the
+    /// whitespace it was broken at.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The runs of the line, a justified line has one run per word, every
+    /// other line one run for the whole line or none if it is empty.
+    #[inline]
+    pub fn runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+}
+
+/// Text broken into lines that are aligned within a rectangle.
+///
+/// The text is shaped with a [`GlyphBuffer`] line by line, the lines are
+/// stacked from the top of the rectangle. Whitespace at the start and end of
+/// lines is dropped. Lines that don't fit below the rectangle are laid out
+/// nevertheless, see [`height`].
+///
+/// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+/// [`height`]: #method.height
+#[derive(Debug)]
+pub struct TextLayout {
+    lines: Vec<TextLine>,
+    line_height: f64,
+}
+
+impl TextLayout {
+    /// Lays out the text with the given font inside of the rectangle.
+    pub fn new(
+        text: &str,
+        font: &Font,
+        rect: &RectD,
+        options: &TextLayoutOptions,
+    ) -> Result<TextLayout> {
+        let metrics = font.font_metrics();
+        let ascent = f64::from(metrics.horizontal_ascent);
+        let line_height =
+            f64::from(metrics.horizontal_ascent + metrics.horizontal_descent + metrics.line_gap)
+                * options.line_spacing;
+        let mut layouter = Layouter {
+            text,
+            font,
+            rect,
+            align: options.align,
+            scratch: GlyphBuffer::new(),
+            lines: Vec::new(),
+            baseline: rect.y + ascent,
+            line_height,
+        };
+
+        let mut offset = 0;
+        for paragraph in text.split('\n') {
+            let words = words(paragraph, offset);
+            offset += paragraph.len() + 1;
+            if words.is_empty() {
+                let end = offset - 1;
+                layouter.push_line(&[end..end], true)?;
+                continue;
+            }
+            let mut line_start = 0;
+            if options.wrap == TextWrap::Word {
+                for i in 1..words.len() {
+                    let candidate = words[line_start].start..words[i].end;
+                    if layouter.advance(candidate)? > rect.w {
+                        layouter.push_line(&words[line_start..i], false)?;
+                        line_start = i;
+                    }
+                }
+            }
+            layouter.push_line(&words[line_start..], true)?;
+        }
+
+        Ok(TextLayout {
+            lines: layouter.lines,
+            line_height,
+        })
+    }
+
+    /// The laid out lines.
+    #[inline]
+    pub fn lines(&self) -> &[TextLine] {
+        &self.lines
+    }
+
+    /// The distance between the baselines of two lines.
+    #[inline]
+    pub fn line_height(&self) -> f64 {
+        self.line_height
+    }
+
+    /// The height of all lines, which may exceed the height of the layout
+    /// rectangle.
+    #[inline]
+    pub fn height(&self) -> f64 {
+        self.lines.len() as f64 * self.line_height
+    }
+}
+
+struct Layouter<'a> {
+    text: &'a str,
+    font: &'a Font,
+    rect: &'a RectD,
+    align: TextAlign,
+    scratch: GlyphBuffer,
+    lines: Vec<TextLine>,
+    baseline: f64,
+    line_height: f64,
+}
+
+impl Layouter<'_> {
+    fn shape(&self, range: Range<usize>) -> Result<(GlyphBuffer, f64)> {
+        let mut glyphs = GlyphBuffer::from_utf8_text(&self.text[range]);
+        self.font.shape(&mut glyphs)?;
+        let advance = self.font.get_text_metrics(&mut glyphs)?.advance.x;
+        Ok((glyphs, advance))
+    }
+
+    fn advance(&mut self, range: Range<usize>) -> Result<f64> {
+        self.scratch.set_utf8_text(&self.text[range]);
+        self.font.shape(&mut self.scratch)?;
+        Ok(self.font.get_text_metrics(&mut self.scratch)?.advance.x)
+    }
+
+    /// Lays out the given words as the next line.
+    fn push_line(&mut self, words: &[Range<usize>], last_of_paragraph: bool) -> Result<()> {
+        let range = words[0].start..words[words.len() - 1].end;
+        let mut runs = Vec::new();
+        let width;
+        if self.align == TextAlign::Justify && !last_of_paragraph && words.len() > 1 {
+            let mut shaped = Vec::with_capacity(words.len());
+            let mut words_width = 0.0;
+            for word in words {
+                let (glyphs, advance) = self.shape(word.clone())?;
+                words_width += advance;
+                shaped.push((word.clone(), glyphs, advance));
+            }
+            let gap = (self.rect.w - words_width) / (words.len() - 1) as f64;
+            let mut x = self.rect.x;
+            for (range, glyphs, advance) in shaped {
+                runs.push(TextRun {
+                    origin: PointD::new(x, self.baseline),
+                    range,
+                    glyphs,
+                });
+                x += advance + gap;
+            }
+            width = self.rect.w.max(words_width);
+        } else {
+            let (glyphs, advance) = self.shape(range.clone())?;
+            let offset = match self.align {
+                TextAlign::Left | TextAlign::Justify => 0.0,
+                TextAlign::Center => (self.rect.w - advance) / 2.0,
+                TextAlign::Right => self.rect.w - advance,
+            };
+            if !range.is_empty() {
+                runs.push(TextRun {
+                    origin: PointD::new(self.rect.x + offset, self.baseline),
+                    range: range.clone(),
+                    glyphs,
+                });
+            }
+            width = advance;
+        }
+        self.lines.push(TextLine {
+            baseline: self.baseline,
+            width,
+            range,
+            runs,
+        });
+        self.baseline += self.line_height;
+        Ok(())
+    }
+}
+
+/// The byte ranges of the whitespace separated words of the paragraph,
+/// offset by the start of the paragraph in the text.
+fn words(paragraph: &str, offset: usize) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in paragraph.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push(offset + s..offset + i);
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => {},
+        }
+    }
+    if let Some(s) = start {
+        words.push(offset + s..offset + paragraph.len());
+    }
+    words
+}
+
+#[cfg(test)]
+mod test_layout {
+    use crate::font::{FontFace, TextAlign, TextLayout, TextLayoutOptions, TextWrap};
+    use crate::geometry::RectD;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_text_layout() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(20.0).unwrap();
+        let text = "one two three\n\nfour";
+        let rect = RectD::new(10.0, 0.0, 90.0, 200.0);
+
+        let mut options = TextLayoutOptions::default();
+        let layout = TextLayout::new(text, &font, &rect, &options).unwrap();
+        let ranges = layout
+            .lines()
+            .iter()
+            .map(|line| &text[line.range()])
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, ["one two", "three", "", "four"]);
+        assert!(layout.lines()[2].runs().is_empty());
+        assert_eq!(layout.height(), 4.0 * layout.line_height());
+
+        options.align = TextAlign::Right;
+        let layout = TextLayout::new(text, &font, &rect, &options).unwrap();
+        let line = &layout.lines()[1];
+        assert!((line.runs()[0].origin().x + line.width() - 100.0).abs() < 1e-9);
+
+        options.align = TextAlign::Justify;
+        let layout = TextLayout::new(text, &font, &rect, &options).unwrap();
+        let runs = layout.lines()[0].runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].origin().x, 10.0);
+
+        options.wrap = TextWrap::None;
+        let layout = TextLayout::new(text, &font, &rect, &options).unwrap();
+        assert_eq!(layout.lines().len(), 3);
+    }
+}
@@ -1,8 +1,17 @@
 use std::fmt;
 
+use crate::array::Array;
+use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::font_defs::FontQueryProperties;
 use crate::variant::WrappedBlCore;
 
+use super::FontFace;
+
 /// Font Manager
+///
+/// A registry of [`FontFace`]s that can be queried by family name and
+/// [`FontQueryProperties`]. Cloning a font manager creates a new reference to
+/// the same registry.
 #[repr(transparent)]
 pub struct FontManager {
     core: ffi::BLFontManagerCore,
@@ -17,6 +26,94 @@ unsafe impl WrappedBlCore for FontManager {
     }
 }
 
+impl FontManager {
+    /// Creates a new, empty font manager.
+    pub fn new() -> Self {
+        let mut this = Self::from_core(*Self::none());
+        unsafe { expect_mem_err(ffi::blFontManagerCreate(this.core_mut())) };
+        this
+    }
+
+    /// Returns the number of faces registered in this font manager.
+    #[inline]
+    pub fn face_count(&self) -> usize {
+        unsafe { ffi::blFontManagerGetFaceCount(self.core()) }
+    }
+
+    /// Returns the number of distinct font families registered in this font
+    /// manager.
+    #[inline]
+    pub fn family_count(&self) -> usize {
+        unsafe { ffi::blFontManagerGetFamilyCount(self.core()) }
+    }
+
+    /// Checks whether the given face has been registered.
+    #[inline]
+    pub fn has_face(&self, face: &FontFace) -> bool {
+        unsafe { ffi::blFontManagerHasFace(self.core(), face.core()) }
+    }
+
+    /// Registers a face in this font manager.
+    ///
+    /// Adding a face that is already registered does nothing.
+    #[inline]
+    pub fn add_face(&mut self, face: &FontFace) -> Result<()> {
+        unsafe { errcode_to_result(ffi::blFontManagerAddFace(self.core_mut(), face.core())) }
+    }
+
+    /// Returns the face of the given family that matches the given
+    /// properties best.
+    ///
+    /// The style, weight and stretch of the properties are matched in this
+    /// order, picking the closest value available in the family for each of
+    /// them. Family names are matched case-insensitively.
+    pub fn query_face(
+        &self,
+        family_name: &str,
+        properties: &FontQueryProperties,
+    ) -> Result<FontFace> {
+        let mut face = FontFace::from_core(*FontFace::none());
+        let properties = ffi::BLFontQueryProperties {
+            style: properties.style.into(),
+            weight: properties.weight.into(),
+            stretch: properties.stretch.into(),
+        };
+        unsafe {
+            errcode_to_result(ffi::blFontManagerQueryFace(
+                self.core(),
+                family_name.as_ptr() as *const _,
+                family_name.len(),
+                &properties,
+                face.core_mut(),
+            ))
+            .map(|_| face)
+        }
+    }
+
+    /// Returns all faces of the given family.
+    ///
+    /// Family names are matched case-insensitively.
+    pub fn query_faces_by_family_name(&self, family_name: &str) -> Result<Array<FontFace>> {
+        let mut faces = Array::<FontFace>::new();
+        unsafe {
+            errcode_to_result(ffi::blFontManagerQueryFacesByFamilyName(
+                self.core(),
+                family_name.as_ptr() as *const _,
+                family_name.len(),
+                faces.core_mut(),
+            ))
+            .map(|_| faces)
+        }
+    }
+}
+
+impl Default for FontManager {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PartialEq for FontManager {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -39,6 +136,9 @@ impl Drop for FontManager {
 
 impl fmt::Debug for FontManager {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("FontManager").finish()
+        f.debug_struct("FontManager")
+            .field("face_count", &self.face_count())
+            .field("family_count", &self.family_count())
+            .finish()
     }
 }
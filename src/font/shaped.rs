@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::error::Result;
+use crate::font_defs::{GlyphRun, TextMetrics};
+use crate::geometry::PointD;
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+use crate::path::Path;
+
+use super::Font;
+
+/// A piece of text shaped once with a [`Font`], which can then be measured
+/// and converted into outlines without shaping it again.
+///
+/// [`Font`]: struct.Font.html
+#[derive(Debug)]
+pub struct ShapedRun {
+    glyphs: GlyphBuffer,
+}
+
+impl ShapedRun {
+    /// Shapes the text with the given font.
+    pub fn new(font: &Font, text: &str) -> Result<ShapedRun> {
+        let mut glyphs = GlyphBuffer::from_utf8_text(text);
+        font.shape(&mut glyphs)?;
+        Ok(ShapedRun { glyphs })
+    }
+
+    /// Wraps a glyph buffer that has been shaped already, for example with
+    /// custom features or a manually adjusted positioning.
+    #[inline]
+    pub fn from_glyph_buffer(glyphs: GlyphBuffer) -> ShapedRun {
+        ShapedRun { glyphs }
+    }
+
+    /// The shaped glyphs.
+    #[inline]
+    pub fn glyphs(&self) -> &GlyphBuffer {
+        &self.glyphs
+    }
+
+    #[inline]
+    pub fn glyph_run(&self) -> GlyphRun<'_> {
+        self.glyphs.glyph_run()
+    }
+
+    #[inline]
+    pub fn into_glyph_buffer(self) -> GlyphBuffer {
+        self.glyphs
+    }
+
+    /// Computes the metrics of the run from its current placements, the font
+    /// should be the one the run was shaped with.
+    #[inline]
+    pub fn metrics(&mut self, font: &Font) -> Result<TextMetrics> {
+        font.get_text_metrics(&mut self.glyphs)
+    }
+
+    /// Converts the run into a path with its origin on the baseline at
+    /// `(0, 0)`, the font should be the one the run was shaped with.
+    ///
+    /// Unlike [`Font::get_glyph_run_outlines`] the outlines of each distinct
+    /// glyph are only decoded once and reused for all of its occurrences,
+    /// which is considerably faster for long runs.
+    ///
+    /// [`Font::get_glyph_run_outlines`]: struct.Font.html#method.get_glyph_run_outlines
+    pub fn to_path(&self, font: &Font) -> Result<Path> {
+        let [m00, m01, m10, m11] = font.font_matrix().0;
+        let mut outlines: HashMap<GlyphId, Path> = HashMap::new();
+        let mut path = Path::new();
+        // the pen position in font design units
+        let (mut pen_x, mut pen_y) = (0i64, 0i64);
+        for glyph in self.glyphs.glyph_run() {
+            let (offset, advance) = match glyph.placement {
+                Some(placement) => (placement.placement, placement.advance),
+                None => Default::default(),
+            };
+            if !outlines.contains_key(&glyph.glyph_id) {
+                let mut outline = Path::new();
+                font.get_glyph_outlines(glyph.glyph_id, None, &mut outline)?;
+                outlines.insert(glyph.glyph_id, outline);
+            }
+            let x = (pen_x + i64::from(offset.x)) as f64;
+            let y = (pen_y + i64::from(offset.y)) as f64;
+            let origin = PointD::new(x * m00 + y * m10, x * m01 + y * m11);
+            path.add_translated_path(&outlines[&glyph.glyph_id], &origin);
+            pen_x += i64::from(advance.x);
+            pen_y += i64::from(advance.y);
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test_shaped {
+    use crate::font::{FontFace, ShapedRun};
+    use crate::path::Path;
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_shaped_run_to_path() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut run = ShapedRun::new(&font, "abab cab").unwrap();
+        let metrics = run.metrics(&font).unwrap();
+        assert!(metrics.advance.x > 0.0);
+
+        let path = run.to_path(&font).unwrap();
+        let mut expected = Path::new();
+        font.get_glyph_run_outlines(run.glyph_run(), None, &mut expected)
+            .unwrap();
+        let (a, b) = (
+            path.bounding_box().unwrap(),
+            expected.bounding_box().unwrap(),
+        );
+        for &(x, y) in &[(a.x0, b.x0), (a.y0, b.y0), (a.x1, b.x1), (a.y1, b.y1)] {
+            assert!((x - y).abs() < 1e-6, "{} != {}", x, y);
+        }
+        assert_eq!(path.len(), expected.len());
+    }
+}
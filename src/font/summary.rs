@@ -0,0 +1,175 @@
+use std::ops::Range;
+
+use crate::font_defs::{FontStretch, FontStyle};
+use crate::Tag;
+
+use super::table::read_u16;
+use super::{FontData, FontFace};
+
+/// Basic information about a face of a [`FontData`], as yielded by
+/// [`FontData::faces`].
+///
+/// [`FontData::faces`]: struct.FontData.html#method.faces
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceInfoSummary {
+    /// The index of the face in the font data.
+    pub face_index: u32,
+    /// The typographic family name of the face if present, otherwise the
+    /// family name.
+    pub family_name: String,
+    /// The typographic subfamily name of the face if present, otherwise the
+    /// subfamily name.
+    pub subfamily_name: String,
+    pub style: FontStyle,
+    /// The numeric weight of the face, ranging from 1 to 1000 with 400 being
+    /// regular and 700 bold.
+    pub weight: u32,
+    pub stretch: FontStretch,
+}
+
+impl FaceInfoSummary {
+    fn read(data: &FontData, face_index: u32) -> Option<Self> {
+        let (name, found) = data.query_table(face_index, Tag::from_bytes(*b"name"));
+        if found == 0 {
+            return None;
+        }
+        let family_name = read_name(name.data, &[16, 1])?;
+        let subfamily_name = read_name(name.data, &[17, 2]).unwrap_or_default();
+        let (os2, found) = data.query_table(face_index, Tag::from_bytes(*b"OS/2"));
+        let os2 = if found == 0 { &[][..] } else { os2.data };
+        let style = match read_u16(os2, 62) {
+            Some(selection) if selection & 0x0001 != 0 => FontStyle::Italic,
+            Some(selection) if selection & 0x0200 != 0 => FontStyle::Oblique,
+            _ => FontStyle::Normal,
+        };
+        Some(FaceInfoSummary {
+            face_index,
+            family_name,
+            subfamily_name,
+            style,
+            weight: read_u16(os2, 4).map_or(400, u32::from),
+            stretch: read_u16(os2, 6).map_or(FontStretch::Normal, |width| u32::from(width).into()),
+        })
+    }
+
+    fn from_face(face: &FontFace) -> Self {
+        FaceInfoSummary {
+            face_index: face.face_index(),
+            family_name: face.family_name().to_owned(),
+            subfamily_name: face.subfamily_name().to_owned(),
+            style: face.style(),
+            weight: face.weight().into(),
+            stretch: face.stretch(),
+        }
+    }
+}
+
+impl FontData {
+    /// Returns an iterator over summaries of all faces of this font data.
+    ///
+    /// The summaries are read from the 'name' and 'OS/2' tables of each face
+    /// directly, without creating a [`FontFace`] for it. Only faces lacking a
+    /// usable 'name' table are created to retrieve their information, faces
+    /// that fail to load are skipped.
+    pub fn faces(&self) -> Faces<'_> {
+        Faces {
+            data: self,
+            indices: 0..self.face_count(),
+        }
+    }
+}
+
+/// An iterator over summaries of the faces of a [`FontData`].
+#[derive(Debug)]
+pub struct Faces<'a> {
+    data: &'a FontData,
+    indices: Range<u32>,
+}
+
+impl Iterator for Faces<'_> {
+    type Item = FaceInfoSummary;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.data;
+        self.indices.find_map(|index| {
+            FaceInfoSummary::read(data, index).or_else(|| {
+                FontFace::from_data(data, index)
+                    .ok()
+                    .map(|face| FaceInfoSummary::from_face(&face))
+            })
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
+}
+
+/// Reads the first of the given name ids from a 'name' table, preferring
+/// english unicode records.
+fn read_name(table: &[u8], name_ids: &[u16]) -> Option<String> {
+    let count = usize::from(read_u16(table, 2)?);
+    let storage = usize::from(read_u16(table, 4)?);
+    for &name_id in name_ids {
+        let mut best: Option<(u32, String)> = None;
+        for record in (0..count).map(|i| 6 + i * 12) {
+            let field = |n: usize| read_u16(table, record + n * 2);
+            if field(3)? != name_id {
+                continue;
+            }
+            let (platform, encoding, language) = (field(0)?, field(1)?, field(2)?);
+            let (len, offset) = (usize::from(field(4)?), usize::from(field(5)?));
+            let bytes = match table.get(storage + offset..storage + offset + len) {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            let (rank, name) = match (platform, encoding) {
+                (3, 1) | (3, 10) | (0, _) => {
+                    let units: Vec<u16> = bytes
+                        .chunks_exact(2)
+                        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                        .collect();
+                    let rank = if platform == 3 && language == 0x0409 { 0 } else { 1 };
+                    (rank, String::from_utf16_lossy(&units))
+                },
+                // Mac Roman, which matches ASCII for the common characters
+                (1, 0) => (2, bytes.iter().map(|&b| b as char).collect()),
+                _ => continue,
+            };
+            if best.as_ref().map_or(true, |(best, _)| rank < *best) {
+                best = Some((rank, name));
+            }
+        }
+        if let Some((_, name)) = best.filter(|(_, name)| !name.is_empty()) {
+            return Some(name);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test_summary {
+    use crate::font::{FontData, FontFace};
+    use crate::font_defs::{FontStretch, FontStyle};
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_font_data_faces() {
+        let data =
+            FontData::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let faces = data.faces().collect::<Vec<_>>();
+        assert_eq!(faces.len(), 1);
+        let summary = &faces[0];
+        assert_eq!(summary.face_index, 0);
+        assert_eq!(summary.family_name, "Noto Sans");
+        assert_eq!(summary.subfamily_name, "Regular");
+        assert_eq!(summary.style, FontStyle::Normal);
+        assert_eq!(summary.weight, 400);
+        assert_eq!(summary.stretch, FontStretch::Normal);
+
+        let face = FontFace::from_data(&data, 0).unwrap();
+        assert_eq!(summary.family_name, face.family_name());
+        assert_eq!(summary.weight, u32::from(face.weight()));
+    }
+}
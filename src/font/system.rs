@@ -0,0 +1,186 @@
+use std::collections::{BTreeSet, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::DataAccessFlags;
+
+use super::{FontData, FontFace, FontManager};
+
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+
+/// Returns the directories fonts are installed to on the current platform.
+///
+/// Only directories that exist are returned.
+pub fn system_font_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = Vec::new();
+    if cfg!(windows) {
+        let windir = env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        dirs.push("/System/Library/Fonts".into());
+        dirs.push("/Library/Fonts".into());
+        dirs.extend(home.map(|home| home.join("Library/Fonts")));
+    } else {
+        dirs.push("/usr/share/fonts".into());
+        dirs.push("/usr/local/share/fonts".into());
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        dirs.extend(data_home.map(|data| data.join("fonts")));
+        dirs.extend(home.map(|home| home.join(".fonts")));
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+/// Scans the [`system_font_dirs`] for fonts and registers them in a new
+/// [`SystemFonts`] collection.
+///
+/// Fonts that fail to load are skipped.
+///
+/// [`system_font_dirs`]: fn.system_font_dirs.html
+/// [`SystemFonts`]: struct.SystemFonts.html
+pub fn system_fonts() -> SystemFonts {
+    let mut fonts = SystemFonts::new();
+    for dir in system_font_dirs() {
+        fonts.load_dir(dir);
+    }
+    fonts
+}
+
+/// A [`FontManager`] populated from font files, keeping track of the
+/// registered font families.
+#[derive(Debug, Default)]
+pub struct SystemFonts {
+    manager: FontManager,
+    families: BTreeSet<String>,
+}
+
+impl SystemFonts {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers all faces of the given font file, returning the number of
+    /// faces registered.
+    ///
+    /// All faces are loaded before any of them is registered, so nothing is
+    /// registered if one of the faces of a collection fails to load. Only if
+    /// registering a loaded face fails the faces registered before it stay
+    /// registered.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize> {
+        let data = FontData::from_path(path, DataAccessFlags::READ)?;
+        let faces = (0..data.face_count())
+            .map(|index| FontFace::from_data(&data, index))
+            .collect::<Result<Vec<_>>>()?;
+        for face in &faces {
+            self.manager.add_face(face)?;
+            self.families.insert(face.family_name().to_owned());
+        }
+        Ok(faces.len())
+    }
+
+    /// Recursively registers all font files in the given directory, returning
+    /// the number of faces registered.
+    ///
+    /// Files that fail to load are skipped. Symbolic links are followed, but
+    /// every directory is only scanned once.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> usize {
+        self.load_dir_once(dir.as_ref(), &mut HashSet::new())
+    }
+
+    fn load_dir_once(&mut self, dir: &Path, visited: &mut HashSet<PathBuf>) -> usize {
+        // canonical paths resolve symbolic links, which may form cycles
+        match dir.canonicalize() {
+            Ok(dir) if visited.insert(dir) => {},
+            _ => return 0,
+        }
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
+        let mut count = 0;
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            if path.is_dir() {
+                count += self.load_dir_once(&path, visited);
+            } else if is_font_file(&path) {
+                count += self.load_file(&path).unwrap_or(0);
+            }
+        }
+        count
+    }
+
+    /// The [`FontManager`] all faces are registered in.
+    #[inline]
+    pub fn manager(&self) -> &FontManager {
+        &self.manager
+    }
+
+    /// Returns the [`FontManager`] all faces are registered in.
+    #[inline]
+    pub fn into_manager(self) -> FontManager {
+        self.manager
+    }
+
+    /// Returns the names of all registered font families in sorted order.
+    pub fn families(&self) -> impl Iterator<Item = &str> {
+        self.families.iter().map(String::as_str)
+    }
+
+    /// Checks whether a family with the given name has been registered.
+    ///
+    /// Family names are matched case-insensitively.
+    pub fn has_family(&self, family_name: &str) -> bool {
+        self.families
+            .iter()
+            .any(|family| family.eq_ignore_ascii_case(family_name))
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| FONT_EXTENSIONS.iter().any(|font| ext.eq_ignore_ascii_case(font)))
+}
+
+#[cfg(test)]
+mod test_system {
+    use std::{env, fs, process};
+
+    use crate::font::SystemFonts;
+
+    #[test]
+    fn test_load_dir() {
+        let mut fonts = SystemFonts::new();
+        assert!(fonts.load_dir("assets") > 0);
+        assert!(fonts.has_family("noto sans"));
+        assert!(fonts.families().any(|family| family == "Noto Sans"));
+        assert!(fonts.manager().face_count() > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_dir_symlink_cycle() {
+        let dir = env::temp_dir().join(format!("blend2d-fonts-{}", process::id()));
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::copy("assets/NotoSans-Regular.ttf", nested.join("NotoSans.ttf")).unwrap();
+        std::os::unix::fs::symlink(&dir, nested.join("loop")).unwrap();
+
+        let mut fonts = SystemFonts::new();
+        let count = fonts.load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(count, 1);
+        assert!(fonts.has_family("Noto Sans"));
+    }
+}
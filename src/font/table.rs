@@ -0,0 +1,17 @@
+//! Bounds checked readers for the big-endian values of OpenType tables.
+
+/// Reads the `u16` at the given offset, returning `None` if it is out of
+/// bounds.
+#[inline]
+pub(super) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads the `u32` at the given offset, returning `None` if it is out of
+/// bounds.
+#[inline]
+pub(super) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+
+use crate::error::{Error, Result};
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+
+use super::{Font, FontFace};
+
+/// Records the glyphs and characters of a font-face that were used, as
+/// needed to subset the font-face when embedding it into documents.
+///
+/// Text is recorded by shaping it with the font it is rendered with, so that
+/// substituted glyphs like ligatures are recorded as well. Characters are
+/// only recorded if the font-face has a glyph for them.
+#[derive(Debug, Clone)]
+pub struct GlyphUsageTracker {
+    face: FontFace,
+    glyph_ids: BTreeSet<GlyphId>,
+    codepoints: BTreeSet<char>,
+}
+
+impl GlyphUsageTracker {
+    /// Creates an empty tracker for the given font-face.
+    pub fn new(face: &FontFace) -> Self {
+        GlyphUsageTracker {
+            face: face.clone(),
+            glyph_ids: BTreeSet::new(),
+            codepoints: BTreeSet::new(),
+        }
+    }
+
+    /// The tracked font-face.
+    #[inline]
+    pub fn face(&self) -> &FontFace {
+        &self.face
+    }
+
+    /// Shapes the text with the given font and records the resulting glyphs
+    /// and the characters they were produced from.
+    ///
+    /// Returns [`Error::InvalidValue`] if the font doesn't belong to the
+    /// tracked font-face.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn record_text(&mut self, font: &Font, text: &str) -> Result<()> {
+        self.check_face(font)?;
+        let mut buf = GlyphBuffer::from_utf8_text(text);
+        font.shape(&mut buf)?;
+        let run = buf.glyph_run();
+        // the clusters are byte offsets into the text, each glyph covers the
+        // text up to the next larger cluster
+        let mut clusters: Vec<usize> = run.info.iter().map(|info| info.cluster as usize).collect();
+        clusters.sort_unstable();
+        clusters.dedup();
+        for (id, info) in run.glyph_ids().zip(run.info) {
+            if id.is_notdef() {
+                continue;
+            }
+            self.glyph_ids.insert(id);
+            let start = info.cluster as usize;
+            let end = clusters
+                .iter()
+                .copied()
+                .find(|&cluster| cluster > start)
+                .unwrap_or_else(|| text.len());
+            if let Some(chars) = text.get(start..end) {
+                self.codepoints.extend(chars.chars());
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the glyphs of a glyph buffer that has already been shaped or
+    /// mapped with a font of the tracked font-face.
+    ///
+    /// The characters the glyphs were produced from are unknown to the
+    /// buffer, so only the glyphs are recorded.
+    pub fn record_glyph_buffer(&mut self, buf: &GlyphBuffer) {
+        self.glyph_ids
+            .extend(buf.glyph_run().glyph_ids().filter(|id| !id.is_notdef()));
+    }
+
+    /// Records the given glyphs.
+    pub fn record_glyphs<I: IntoIterator<Item = GlyphId>>(&mut self, glyph_ids: I) {
+        self.glyph_ids.extend(glyph_ids);
+    }
+
+    /// The recorded glyph ids in ascending order.
+    #[inline]
+    pub fn glyph_ids(&self) -> &BTreeSet<GlyphId> {
+        &self.glyph_ids
+    }
+
+    /// The recorded characters in ascending order.
+    #[inline]
+    pub fn codepoints(&self) -> &BTreeSet<char> {
+        &self.codepoints
+    }
+
+    /// Removes all recorded glyphs and characters.
+    pub fn clear(&mut self) {
+        self.glyph_ids.clear();
+        self.codepoints.clear();
+    }
+
+    fn check_face(&self, font: &Font) -> Result<()> {
+        if font.face().face_unique_id() == self.face.face_unique_id() {
+            Ok(())
+        } else {
+            Err(Error::InvalidValue)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_usage {
+    use crate::font::{FontFace, GlyphUsageTracker};
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_glyph_usage_tracker() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut tracker = GlyphUsageTracker::new(&face);
+        tracker.record_text(&font, "abba").unwrap();
+        assert_eq!(tracker.codepoints().iter().collect::<String>(), "ab");
+        assert_eq!(tracker.glyph_ids().len(), 2);
+
+        let other = FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ)
+            .unwrap()
+            .create_font(16.0)
+            .unwrap();
+        assert!(tracker.record_text(&other, "c").is_err());
+
+        tracker.clear();
+        assert!(tracker.glyph_ids().is_empty());
+    }
+}
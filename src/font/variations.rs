@@ -0,0 +1,87 @@
+use crate::Tag;
+
+use super::table::{read_u16, read_u32};
+use super::FontFace;
+
+/// A variation axis of a variable font-face, as listed by
+/// [`FontFace::variation_axes`].
+///
+/// [`FontFace::variation_axes`]: struct.FontFace.html#method.variation_axes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontVariationAxis {
+    pub tag: Tag,
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+    /// Whether the font-face asks for the axis not to be shown in user
+    /// interfaces.
+    pub hidden: bool,
+}
+
+impl FontFace {
+    /// Lists the variation axes of this font-face as defined by its `fvar`
+    /// table, which is empty if the font-face isn't a variable font.
+    pub fn variation_axes(&self) -> Vec<FontVariationAxis> {
+        let (table, _) = self
+            .font_data()
+            .query_table(self.face_index(), Tag::from_bytes(*b"fvar"));
+        fvar_axes(table.data).unwrap_or_default()
+    }
+}
+
+/// Reads the axis records of an `fvar` table.
+fn fvar_axes(data: &[u8]) -> Option<Vec<FontVariationAxis>> {
+    if read_u16(data, 0)? != 1 {
+        return None;
+    }
+    let offset = usize::from(read_u16(data, 4)?);
+    let count = usize::from(read_u16(data, 8)?);
+    let size = usize::from(read_u16(data, 10)?);
+    // values are signed 16.16 fixed point numbers
+    let fixed = |offset| read_u32(data, offset).map(|v| v as i32 as f32 / 65536.0);
+    (0..count)
+        .map(|i| {
+            let record = offset + i * size;
+            Some(FontVariationAxis {
+                tag: Tag::from(read_u32(data, record)?),
+                min: fixed(record + 4)?,
+                default: fixed(record + 8)?,
+                max: fixed(record + 12)?,
+                hidden: read_u16(data, record + 16)? & 0x0001 != 0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_variations {
+    use super::fvar_axes;
+    use crate::font::FontFace;
+    use crate::{DataAccessFlags, Tag};
+
+    #[test]
+    fn test_fvar_axes() {
+        let mut fvar = vec![0, 1, 0, 0, 0, 16, 0, 2, 0, 1, 0, 20, 0, 0, 0, 0];
+        fvar.extend_from_slice(b"wght");
+        for value in &[100i32, 400, 900] {
+            fvar.extend_from_slice(&(value << 16).to_be_bytes());
+        }
+        fvar.extend_from_slice(&[0, 1, 1, 0]);
+        let axes = fvar_axes(&fvar).unwrap();
+        assert_eq!(axes.len(), 1);
+        assert_eq!(axes[0].tag, Tag::from_bytes(*b"wght"));
+        assert_eq!(
+            (axes[0].min, axes[0].default, axes[0].max),
+            (100.0, 400.0, 900.0)
+        );
+        assert!(axes[0].hidden);
+        assert!(fvar_axes(&fvar[..30]).is_none());
+    }
+
+    #[test]
+    fn test_static_font_has_no_axes() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        assert!(face.variation_axes().is_empty());
+    }
+}
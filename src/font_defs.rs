@@ -1,9 +1,11 @@
 use bitflags::bitflags;
 
-use std::fmt;
+use std::marker::PhantomData;
+use std::{fmt, slice};
 
 use crate::{
     geometry::{BoxD, BoxI, PointD, PointI},
+    glyph_buffer::GlyphId,
     Tag,
 };
 
@@ -203,7 +205,7 @@ bl_enum! {
 #[repr(C)]
 #[derive(Debug)]
 pub struct GlyphItem {
-    pub glyph_id: u16,
+    pub glyph_id: GlyphId,
     reserved: u16,
 }
 
@@ -212,21 +214,25 @@ pub struct GlyphItem {
 #[derive(Debug)]
 pub struct GlyphItem {
     reserved: u16,
-    pub glyph_id: u16,
+    pub glyph_id: GlyphId,
 }
 
-#[allow(dead_code)]
+/// Additional information about a glyph of a [`GlyphBuffer`].
+///
+/// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
 #[repr(C)]
-#[derive(Debug)]
-pub(in crate) struct GlyphInfo {
+#[derive(Copy, Clone, Debug)]
+pub struct GlyphInfo {
+    /// The index of the first text unit the glyph was produced from.
     pub cluster: u32,
     reserved: [u32; 2],
 }
 
-#[allow(dead_code)]
+/// The placement of a glyph relative to the pen position and the advance of
+/// the pen after the glyph, in font design units.
 #[repr(C)]
-#[derive(Debug)]
-pub(in crate) struct GlyphPlacement {
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GlyphPlacement {
     pub placement: PointI,
     pub advance: PointI,
 }
@@ -249,25 +255,233 @@ impl GlyphMappingState {
     }
 }
 
-#[allow(dead_code)]
+/// Information passed to the sink of [`Font::get_glyph_outlines_with`] and
+/// [`Font::get_glyph_run_outlines_with`].
+///
+/// [`Font::get_glyph_outlines_with`]: ../font/struct.Font.html#method.get_glyph_outlines_with
+/// [`Font::get_glyph_run_outlines_with`]: ../font/struct.Font.html#method.get_glyph_run_outlines_with
 #[repr(C)]
 #[derive(Debug)]
-pub(in crate) struct GlyphOutlineSinkInfo {
+pub struct GlyphOutlineSinkInfo {
+    /// The index of the glyph in the processed glyph run.
     pub glyph_index: usize,
+    /// The number of contours of the glyph.
     pub contour_count: usize,
 }
 
-// Fixme figure out what glyph run actually does and expose a proper api
+/// A borrowed view of a sequence of glyphs and their placements, usually
+/// obtained from a shaped [`GlyphBuffer`].
+///
+/// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+#[derive(Copy, Clone)]
 pub struct GlyphRun<'a> {
     pub(in crate) raw: &'a ffi::BLGlyphRun,
+    pub(in crate) info: &'a [GlyphInfo],
+}
+
+impl<'a> GlyphRun<'a> {
+    /// The number of glyphs in this run.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.size
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn flags(&self) -> GlyphRunFlags {
+        GlyphRunFlags::from_bits_truncate(self.raw.flags)
+    }
+
+    /// The type of the placement data of this run.
+    #[inline]
+    pub fn placement_type(&self) -> GlyphPlacementType {
+        u32::from(self.raw.placementType).into()
+    }
+
+    /// Returns an iterator over the glyph ids of this run.
+    #[inline]
+    pub fn glyph_ids(&self) -> GlyphIds<'a> {
+        GlyphIds {
+            data: self.raw.glyphIdData as *const u8,
+            size: self.raw.glyphIdSize,
+            advance: isize::from(self.raw.glyphIdAdvance),
+            remaining: if self.raw.glyphIdData.is_null() { 0 } else { self.len() },
+            _pd: PhantomData,
+        }
+    }
+
+    /// The placements of the glyphs of this run.
+    ///
+    /// This is empty unless the [`placement_type`] is
+    /// [`GlyphPlacementType::AdvanceOffset`].
+    ///
+    /// [`placement_type`]: #method.placement_type
+    pub fn placements(&self) -> &'a [GlyphPlacement] {
+        let contiguous =
+            self.raw.placementAdvance as usize == std::mem::size_of::<GlyphPlacement>();
+        if self.placement_type() == GlyphPlacementType::AdvanceOffset
+            && contiguous
+            && !self.raw.placementData.is_null()
+        {
+            unsafe {
+                slice::from_raw_parts(self.raw.placementData as *const GlyphPlacement, self.len())
+            }
+        } else {
+            &[]
+        }
+    }
+
+    /// The glyph infos of this run.
+    ///
+    /// This is empty unless the run was obtained from a [`GlyphBuffer`].
+    ///
+    /// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+    #[inline]
+    pub fn infos(&self) -> &'a [GlyphInfo] {
+        self.info
+    }
+
+    /// Returns an iterator over the clusters of the glyphs of this run.
+    ///
+    /// The iterator is empty unless the run was obtained from a
+    /// [`GlyphBuffer`].
+    ///
+    /// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+    #[inline]
+    pub fn clusters(&self) -> impl Iterator<Item = u32> + 'a {
+        self.info.iter().map(|info| info.cluster)
+    }
+
+    /// Returns an iterator over the glyphs of this run, combining the glyph
+    /// id with the cluster and placement of each glyph where available.
+    #[inline]
+    pub fn iter(&self) -> GlyphRunIter<'a> {
+        GlyphRunIter {
+            ids: self.glyph_ids(),
+            info: self.info.iter(),
+            placements: self.placements().iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for GlyphRun<'a> {
+    type Item = GlyphRunItem;
+    type IntoIter = GlyphRunIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl fmt::Debug for GlyphRun<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("GlyphRun").finish()
+        f.debug_struct("GlyphRun")
+            .field("len", &self.len())
+            .field("flags", &self.flags())
+            .field("placement_type", &self.placement_type())
+            .finish()
     }
 }
 
+/// An iterator over the glyph ids of a [`GlyphRun`].
+#[derive(Clone)]
+pub struct GlyphIds<'a> {
+    data: *const u8,
+    size: u8,
+    advance: isize,
+    remaining: usize,
+    _pd: PhantomData<&'a GlyphRun<'a>>,
+}
+
+impl Iterator for GlyphIds<'_> {
+    type Item = GlyphId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let id = unsafe {
+            match self.size {
+                2 => (self.data as *const u16).read_unaligned(),
+                _ => (self.data as *const u32).read_unaligned() as u16,
+            }
+        };
+        self.remaining -= 1;
+        if self.remaining != 0 {
+            self.data = self.data.wrapping_offset(self.advance);
+        }
+        Some(GlyphId(id))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for GlyphIds<'_> {}
+
+impl fmt::Debug for GlyphIds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A glyph of a [`GlyphRun`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphRunItem {
+    pub glyph_id: GlyphId,
+    /// The cluster of the glyph, if the run carries glyph infos.
+    pub cluster: Option<u32>,
+    /// The placement of the glyph, if the run carries placements.
+    pub placement: Option<GlyphPlacement>,
+}
+
+/// An iterator over the glyphs of a [`GlyphRun`].
+#[derive(Clone, Debug)]
+pub struct GlyphRunIter<'a> {
+    ids: GlyphIds<'a>,
+    info: slice::Iter<'a, GlyphInfo>,
+    placements: slice::Iter<'a, GlyphPlacement>,
+}
+
+impl Iterator for GlyphRunIter<'_> {
+    type Item = GlyphRunItem;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let glyph_id = self.ids.next()?;
+        Some(GlyphRunItem {
+            glyph_id,
+            cluster: self.info.next().map(|info| info.cluster),
+            placement: self.placements.next().copied(),
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl ExactSizeIterator for GlyphRunIter<'_> {}
+
+/// The properties a [`FontManager`] uses to pick the best matching face of a
+/// font family.
+///
+/// [`FontManager`]: ../font/struct.FontManager.html
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FontQueryProperties {
+    pub style: FontStyle,
+    pub weight: FontWeight,
+    pub stretch: FontStretch,
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct FontFaceInfo {
@@ -287,14 +501,24 @@ pub struct FontTable<'a> {
 }
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FontFeature {
     pub tag: Tag,
     pub value: u32,
 }
 
+impl FontFeature {
+    #[inline]
+    pub fn new<T: Into<Tag>>(tag: T, value: u32) -> Self {
+        FontFeature {
+            tag: tag.into(),
+            value,
+        }
+    }
+}
+
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FontVariation {
     pub tag: Tag,
     pub value: u32,
@@ -308,7 +532,7 @@ pub struct FontUnicodeCoverage {
 
 #[repr(C)]
 #[derive(Debug)]
-pub struct FontMatrix(pub [f32; 4]);
+pub struct FontMatrix(pub [f64; 4]);
 
 #[repr(C)]
 #[derive(Debug)]
@@ -6,6 +6,10 @@
 //! [`Path`]: ../path/struct.Path.html
 //! [`Context`]: ../context/struct.Context.html
 
+mod box_set;
+
+pub use self::box_set::BoxSet;
+
 mod private {
     use super::*;
     use crate::array::ArrayType;
@@ -278,6 +282,16 @@ pub struct PointI {
     pub y: i32,
 }
 
+impl PointI {
+    /// The point at the origin.
+    pub const ZERO: PointI = PointI::new(0, 0);
+
+    #[inline]
+    pub const fn new(x: i32, y: i32) -> Self {
+        PointI { x, y }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct PointD {
@@ -285,6 +299,16 @@ pub struct PointD {
     pub y: f64,
 }
 
+impl PointD {
+    /// The point at the origin.
+    pub const ZERO: PointD = PointD::new(0.0, 0.0);
+
+    #[inline]
+    pub const fn new(x: f64, y: f64) -> Self {
+        PointD { x, y }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct SizeI {
@@ -292,6 +316,16 @@ pub struct SizeI {
     pub h: i32,
 }
 
+impl SizeI {
+    /// A size with zero width and height.
+    pub const ZERO: SizeI = SizeI::new(0, 0);
+
+    #[inline]
+    pub const fn new(w: i32, h: i32) -> Self {
+        SizeI { w, h }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct SizeD {
@@ -299,6 +333,16 @@ pub struct SizeD {
     pub h: f64,
 }
 
+impl SizeD {
+    /// A size with zero width and height.
+    pub const ZERO: SizeD = SizeD::new(0.0, 0.0);
+
+    #[inline]
+    pub const fn new(w: f64, h: f64) -> Self {
+        SizeD { w, h }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct BoxI {
@@ -308,6 +352,16 @@ pub struct BoxI {
     pub y1: i32,
 }
 
+impl BoxI {
+    /// An empty box at the origin.
+    pub const EMPTY: BoxI = BoxI::new(0, 0, 0, 0);
+
+    #[inline]
+    pub const fn new(x0: i32, y0: i32, x1: i32, y1: i32) -> Self {
+        BoxI { x0, y0, x1, y1 }
+    }
+}
+
 impl Geometry for BoxI {
     const GEO_TYPE: u32 = GeometryType::BoxI as u32;
 }
@@ -321,6 +375,16 @@ pub struct BoxD {
     pub y1: f64,
 }
 
+impl BoxD {
+    /// An empty box at the origin.
+    pub const EMPTY: BoxD = BoxD::new(0.0, 0.0, 0.0, 0.0);
+
+    #[inline]
+    pub const fn new(x0: f64, y0: f64, x1: f64, y1: f64) -> Self {
+        BoxD { x0, y0, x1, y1 }
+    }
+}
+
 impl Geometry for BoxD {
     const GEO_TYPE: u32 = GeometryType::BoxD as u32;
 }
@@ -334,6 +398,16 @@ pub struct RectI {
     pub h: i32,
 }
 
+impl RectI {
+    /// An empty rectangle at the origin.
+    pub const EMPTY: RectI = RectI::new(0, 0, 0, 0);
+
+    #[inline]
+    pub const fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        RectI { x, y, w, h }
+    }
+}
+
 impl Geometry for RectI {
     const GEO_TYPE: u32 = GeometryType::RectI as u32;
 }
@@ -347,6 +421,16 @@ pub struct RectD {
     pub h: f64,
 }
 
+impl RectD {
+    /// An empty rectangle at the origin.
+    pub const EMPTY: RectD = RectD::new(0.0, 0.0, 0.0, 0.0);
+
+    #[inline]
+    pub const fn new(x: f64, y: f64, w: f64, h: f64) -> Self {
+        RectD { x, y, w, h }
+    }
+}
+
 impl Geometry for RectD {
     const GEO_TYPE: u32 = GeometryType::RectD as u32;
 }
@@ -360,6 +444,13 @@ pub struct Line {
     pub y1: f64,
 }
 
+impl Line {
+    #[inline]
+    pub const fn new(x0: f64, y0: f64, x1: f64, y1: f64) -> Self {
+        Line { x0, y0, x1, y1 }
+    }
+}
+
 impl Geometry for Line {
     const GEO_TYPE: u32 = GeometryType::Line as u32;
 }
@@ -375,6 +466,13 @@ pub struct Triangle {
     pub y2: f64,
 }
 
+impl Triangle {
+    #[inline]
+    pub const fn new(x0: f64, y0: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        Triangle { x0, y0, x1, y1, x2, y2 }
+    }
+}
+
 impl Geometry for Triangle {
     const GEO_TYPE: u32 = GeometryType::Triangle as u32;
 }
@@ -390,6 +488,13 @@ pub struct RoundRect {
     pub ry: f64,
 }
 
+impl RoundRect {
+    #[inline]
+    pub const fn new(x: f64, y: f64, w: f64, h: f64, rx: f64, ry: f64) -> Self {
+        RoundRect { x, y, w, h, rx, ry }
+    }
+}
+
 impl Geometry for RoundRect {
     const GEO_TYPE: u32 = GeometryType::RoundRect as u32;
 }
@@ -402,6 +507,13 @@ pub struct Circle {
     pub r: f64,
 }
 
+impl Circle {
+    #[inline]
+    pub const fn new(cx: f64, cy: f64, r: f64) -> Self {
+        Circle { cx, cy, r }
+    }
+}
+
 impl Geometry for Circle {
     const GEO_TYPE: u32 = GeometryType::Circle as u32;
 }
@@ -415,6 +527,13 @@ pub struct Ellipse {
     pub ry: f64,
 }
 
+impl Ellipse {
+    #[inline]
+    pub const fn new(cx: f64, cy: f64, rx: f64, ry: f64) -> Self {
+        Ellipse { cx, cy, rx, ry }
+    }
+}
+
 impl Geometry for Ellipse {
     const GEO_TYPE: u32 = GeometryType::Ellipse as u32;
 }
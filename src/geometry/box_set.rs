@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+
+use crate::region::Region;
+use crate::BooleanOp;
+
+use super::{BoxD, BoxI};
+
+/// A set of non-overlapping boxes with `f64` coordinates, the user space
+/// counterpart of [`Region`].
+///
+/// Like the boxes of a region, the boxes are sorted into horizontal bands
+/// from top to bottom and from left to right within a band, and bands with
+/// the same boxes are merged. Coordinates that are at most the tolerance of
+/// the set apart are treated as equal and boxes thinner than it are dropped,
+/// so that rounding errors don't leave slivers behind. Converting the set
+/// with [`to_region`] rounds only once at the end.
+///
+/// [`Region`]: ../region/struct.Region.html
+/// [`to_region`]: #method.to_region
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoxSet {
+    boxes: Vec<BoxD>,
+    tolerance: f64,
+}
+
+impl BoxSet {
+    /// Creates an empty set with the given tolerance.
+    #[inline]
+    pub fn new(tolerance: f64) -> Self {
+        BoxSet {
+            boxes: Vec::new(),
+            tolerance: tolerance.max(0.0),
+        }
+    }
+
+    /// Creates the union of the given boxes, boxes with `x1 <= x0` or
+    /// `y1 <= y0` are ignored.
+    pub fn from_boxes(boxes: &[BoxD], tolerance: f64) -> Self {
+        let tolerance = tolerance.max(0.0);
+        BoxSet {
+            boxes: combine(boxes, &[], BooleanOp::Or, tolerance),
+            tolerance,
+        }
+    }
+
+    /// The boxes of this set.
+    #[inline]
+    pub fn boxes(&self) -> &[BoxD] {
+        &self.boxes
+    }
+
+    #[inline]
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// The sum of the areas of the boxes.
+    pub fn area(&self) -> f64 {
+        self.boxes
+            .iter()
+            .map(|b| (b.x1 - b.x0) * (b.y1 - b.y0))
+            .sum()
+    }
+
+    /// The smallest box containing all boxes of this set, or `None` if it is
+    /// empty.
+    pub fn bounding_box(&self) -> Option<BoxD> {
+        let first = *self.boxes.first()?;
+        Some(self.boxes.iter().fold(first, |acc, b| {
+            BoxD::new(
+                acc.x0.min(b.x0),
+                acc.y0.min(b.y0),
+                acc.x1.max(b.x1),
+                acc.y1.max(b.y1),
+            )
+        }))
+    }
+
+    /// Combines this set with another one, the result uses the larger of
+    /// both tolerances.
+    pub fn combine(&self, other: &BoxSet, op: BooleanOp) -> BoxSet {
+        let tolerance = self.tolerance.max(other.tolerance);
+        BoxSet {
+            boxes: combine(&self.boxes, &other.boxes, op, tolerance),
+            tolerance,
+        }
+    }
+
+    /// The area covered by either set.
+    #[inline]
+    pub fn union(&self, other: &BoxSet) -> BoxSet {
+        self.combine(other, BooleanOp::Or)
+    }
+
+    /// The area covered by both sets.
+    #[inline]
+    pub fn intersect(&self, other: &BoxSet) -> BoxSet {
+        self.combine(other, BooleanOp::And)
+    }
+
+    /// The area covered by this set but not by the other one.
+    #[inline]
+    pub fn subtract(&self, other: &BoxSet) -> BoxSet {
+        self.combine(other, BooleanOp::Sub)
+    }
+
+    /// Converts this set into a [`Region`], rounding every box outwards to
+    /// whole pixels.
+    ///
+    /// [`Region`]: ../region/struct.Region.html
+    pub fn to_region(&self) -> Region {
+        let mut region = Region::new();
+        for b in &self.boxes {
+            let rounded = BoxI::new(
+                b.x0.floor() as i32,
+                b.y0.floor() as i32,
+                b.x1.ceil() as i32,
+                b.y1.ceil() as i32,
+            );
+            region.combine_rb(&rounded, BooleanOp::Or);
+        }
+        region
+    }
+}
+
+/// Sorted coordinates where values at most the tolerance above the last
+/// distinct coordinate are snapped to it.
+struct Snapped {
+    values: Vec<f64>,
+    snapped: Vec<f64>,
+}
+
+impl Snapped {
+    fn new<I: Iterator<Item = f64>>(values: I, tolerance: f64) -> Self {
+        let mut values = values.collect::<Vec<_>>();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        values.dedup();
+        let mut snapped = Vec::with_capacity(values.len());
+        for &value in &values {
+            // compare against the anchor to not chain values into one
+            match snapped.last() {
+                Some(&last) if value - last <= tolerance => snapped.push(last),
+                _ => snapped.push(value),
+            }
+        }
+        Snapped { values, snapped }
+    }
+
+    fn get(&self, value: f64) -> f64 {
+        match self
+            .values
+            .binary_search_by(|probe| probe.partial_cmp(&value).unwrap_or(Ordering::Less))
+        {
+            Ok(i) => self.snapped[i],
+            Err(_) => value,
+        }
+    }
+
+    /// The distinct snapped values.
+    fn distinct(&self) -> Vec<f64> {
+        let mut distinct = self.snapped.clone();
+        distinct.dedup();
+        distinct
+    }
+}
+
+fn combine(a: &[BoxD], b: &[BoxD], op: BooleanOp, tolerance: f64) -> Vec<BoxD> {
+    let valid = |b: &&BoxD| b.x0 < b.x1 && b.y0 < b.y1;
+    let all = || a.iter().chain(b).filter(valid);
+    let xs = Snapped::new(all().flat_map(|b| vec![b.x0, b.x1]), tolerance);
+    let ys = Snapped::new(all().flat_map(|b| vec![b.y0, b.y1]), tolerance);
+    let snap = |boxes: &[BoxD]| {
+        boxes
+            .iter()
+            .filter(valid)
+            .map(|b| BoxD::new(xs.get(b.x0), ys.get(b.y0), xs.get(b.x1), ys.get(b.y1)))
+            .filter(|b| b.x0 < b.x1 && b.y0 < b.y1)
+            .collect::<Vec<_>>()
+    };
+    let (a, b) = (snap(a), snap(b));
+
+    let mut result: Vec<BoxD> = Vec::new();
+    // the start of the boxes of the last band in `result`
+    let mut last_band = 0;
+    for band in ys.distinct().windows(2) {
+        let (y0, y1) = (band[0], band[1]);
+        let band_spans = |boxes: &[BoxD]| {
+            boxes
+                .iter()
+                .filter(|b| b.y0 <= y0 && b.y1 >= y1)
+                .map(|b| (b.x0, b.x1))
+                .collect::<Vec<_>>()
+        };
+        let spans = combine_spans(&band_spans(&a), &band_spans(&b), op, tolerance);
+        if spans.is_empty() {
+            continue;
+        }
+        // extend the previous band if it has the same spans and touches
+        let previous = &result[last_band..];
+        let continues = previous.len() == spans.len()
+            && previous
+                .iter()
+                .zip(&spans)
+                .all(|(b, &(x0, x1))| b.y1 == y0 && b.x0 == x0 && b.x1 == x1);
+        if continues {
+            result[last_band..].iter_mut().for_each(|b| b.y1 = y1);
+        } else {
+            last_band = result.len();
+            result.extend(spans.into_iter().map(|(x0, x1)| BoxD::new(x0, y0, x1, y1)));
+        }
+    }
+    // drop bands that ended up thinner than the tolerance
+    result.retain(|b| b.y1 - b.y0 > tolerance);
+    result
+}
+
+/// Combines two lists of possibly overlapping horizontal spans into sorted,
+/// disjoint spans wider than the tolerance.
+fn combine_spans(
+    a: &[(f64, f64)],
+    b: &[(f64, f64)],
+    op: BooleanOp,
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let mut edges = a
+        .iter()
+        .chain(b)
+        .flat_map(|&(x0, x1)| vec![x0, x1])
+        .collect::<Vec<_>>();
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    edges.dedup();
+
+    let covers = |spans: &[(f64, f64)], x: f64| spans.iter().any(|&(x0, x1)| x0 <= x && x < x1);
+    let mut result: Vec<(f64, f64)> = Vec::new();
+    for edge in edges.windows(2) {
+        let (x0, x1) = (edge[0], edge[1]);
+        let mid = (x0 + x1) / 2.0;
+        let (in_a, in_b) = (covers(a, mid), covers(b, mid));
+        let inside = match op {
+            BooleanOp::Copy => in_b,
+            BooleanOp::And => in_a && in_b,
+            BooleanOp::Or => in_a || in_b,
+            BooleanOp::Xor => in_a != in_b,
+            BooleanOp::Sub => in_a && !in_b,
+        };
+        if !inside {
+            continue;
+        }
+        match result.last_mut() {
+            Some(last) if last.1 == x0 => last.1 = x1,
+            _ => result.push((x0, x1)),
+        }
+    }
+    result.retain(|&(x0, x1)| x1 - x0 > tolerance);
+    result
+}
+
+#[cfg(test)]
+mod test_box_set {
+    use crate::geometry::box_set::Snapped;
+    use crate::geometry::{BoxD, BoxI, BoxSet};
+    use crate::BooleanOp;
+
+    #[test]
+    fn test_box_set_union() {
+        let set = BoxSet::from_boxes(
+            &[
+                BoxD::new(0.0, 0.0, 10.0, 10.0),
+                BoxD::new(5.0, 5.0, 15.0, 10.0),
+            ],
+            0.0,
+        );
+        assert_eq!(
+            set.boxes(),
+            [
+                BoxD::new(0.0, 0.0, 10.0, 5.0),
+                BoxD::new(0.0, 5.0, 15.0, 10.0)
+            ]
+        );
+        assert_eq!(set.area(), 125.0);
+        assert_eq!(set.bounding_box(), Some(BoxD::new(0.0, 0.0, 15.0, 10.0)));
+    }
+
+    #[test]
+    fn test_snapped_does_not_chain() {
+        let snapped = Snapped::new(vec![0.0, 0.9, 1.8, 2.7, 3.6].into_iter(), 1.0);
+        assert_eq!(snapped.distinct(), [0.0, 1.8, 3.6]);
+        assert_eq!(snapped.get(0.9), 0.0);
+        assert_eq!(snapped.get(2.7), 1.8);
+    }
+
+    #[test]
+    fn test_box_set_tolerance() {
+        let a = BoxSet::from_boxes(&[BoxD::new(0.0, 0.0, 0.1 + 0.2, 1.0)], 1e-9);
+        let b = BoxSet::from_boxes(&[BoxD::new(0.0, 0.0, 0.3, 1.0)], 1e-9);
+        assert!(a.subtract(&b).is_empty());
+        assert!(a.combine(&b, BooleanOp::Xor).is_empty());
+
+        let exact = BoxSet::from_boxes(&[BoxD::new(0.0, 0.0, 0.1 + 0.2, 1.0)], 0.0);
+        assert_eq!(exact.subtract(&b).boxes().len(), 1);
+    }
+
+    #[test]
+    fn test_box_set_ops() {
+        let a = BoxSet::from_boxes(&[BoxD::new(0.5, 0.5, 10.5, 10.5)], 0.0);
+        let b = BoxSet::from_boxes(&[BoxD::new(2.5, 2.5, 4.5, 4.5)], 0.0);
+        assert_eq!(a.intersect(&b), b);
+        let hole = a.subtract(&b);
+        assert_eq!(hole.boxes().len(), 4);
+        assert_eq!(hole.area(), 96.0);
+        assert!(b.subtract(&a).is_empty());
+
+        let region = b.to_region();
+        assert_eq!(region.data(), [BoxI::new(2, 2, 5, 5)]);
+    }
+}
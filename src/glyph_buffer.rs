@@ -1,10 +1,75 @@
-use std::{fmt, ptr};
+use std::ffi::c_void;
+use std::fmt::Write;
+use std::{fmt, mem, ptr, slice};
 
-use crate::error::expect_mem_err;
-use crate::font_defs::{GlyphRun, GlyphRunFlags};
+use crate::error::{expect_mem_err, Error, Result};
+use crate::font::Font;
+use crate::font_defs::{GlyphInfo, GlyphPlacement, GlyphRun, GlyphRunFlags};
 use crate::variant::WrappedBlCore;
 
-pub type GlyphId = u16;
+/// The index of a glyph in a font-face.
+///
+/// Glyph `0` is the `.notdef` glyph fonts use for characters they have no
+/// glyph for.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GlyphId(pub u16);
+
+impl GlyphId {
+    /// The `.notdef` glyph.
+    pub const NOTDEF: GlyphId = GlyphId(0);
+
+    /// Returns the raw value of this glyph id.
+    #[inline]
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    /// Checks whether this is the `.notdef` glyph.
+    #[inline]
+    pub fn is_notdef(self) -> bool {
+        self == Self::NOTDEF
+    }
+}
+
+impl From<u16> for GlyphId {
+    #[inline]
+    fn from(value: u16) -> Self {
+        GlyphId(value)
+    }
+}
+
+impl From<GlyphId> for u16 {
+    #[inline]
+    fn from(id: GlyphId) -> Self {
+        id.0
+    }
+}
+
+impl From<GlyphId> for u32 {
+    #[inline]
+    fn from(id: GlyphId) -> Self {
+        u32::from(id.0)
+    }
+}
+
+impl fmt::Display for GlyphId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Additional spacing applied to shaped text by
+/// [`GlyphBuffer::apply_spacing`], in user units at the font's size.
+///
+/// [`GlyphBuffer::apply_spacing`]: struct.GlyphBuffer.html#method.apply_spacing
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextSpacing {
+    /// The spacing added between all glyphs, also known as tracking.
+    pub letter: f64,
+    /// The spacing added after every space character.
+    pub word: f64,
+}
 
 /// Glyph buffer.
 ///
@@ -58,11 +123,19 @@ impl GlyphBuffer {
         this
     }
 
+    /// Returns a [`GlyphRun`] view of the glyphs, clusters and placements of
+    /// this [`GlyphBuffer`].
     #[inline]
     pub fn glyph_run(&self) -> GlyphRun<'_> {
         unsafe {
+            let info = (*self.core.impl_).glyphInfoData as *const GlyphInfo;
             GlyphRun {
                 raw: &*ffi::blGlyphBufferGetGlyphRun(self.core()),
+                info: if info.is_null() {
+                    &[]
+                } else {
+                    slice::from_raw_parts(info, self.size())
+                },
             }
         }
     }
@@ -72,6 +145,21 @@ impl GlyphBuffer {
         unsafe { ffi::blGlyphBufferGetSize(self.core()) }
     }
 
+    /// Returns the number of characters or glyphs in this [`GlyphBuffer`],
+    /// same as [`size`].
+    ///
+    /// [`size`]: #method.size
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.size()
+    }
+
+    /// Returns true if this [`GlyphBuffer`] contains neither text nor glyphs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
     /// Returns the [`GlyphBuffer`]'s [`GlyphRunFlags`].
     #[inline]
     pub fn flags(&self) -> GlyphRunFlags {
@@ -111,6 +199,111 @@ impl GlyphBuffer {
         self.flags().contains(GlyphRunFlags::INVALID_FONT_DATA)
     }
 
+    /// The placements of the glyphs, see [`GlyphRun::placements`].
+    ///
+    /// [`GlyphRun::placements`]: ../font_defs/struct.GlyphRun.html#method.placements
+    #[inline]
+    pub fn placements(&self) -> &[GlyphPlacement] {
+        self.glyph_run().placements()
+    }
+
+    /// The mutable placements of the glyphs, for adjusting the advances and
+    /// offsets computed by [`Font::shape`] or [`Font::position_glyphs`] before
+    /// rendering.
+    ///
+    /// [`Font::shape`]: ../font/struct.Font.html#method.shape
+    /// [`Font::position_glyphs`]: ../font/struct.Font.html#method.position_glyphs
+    pub fn placements_mut(&mut self) -> &mut [GlyphPlacement] {
+        let len = self.glyph_run().placements().len();
+        if len == 0 {
+            return &mut [];
+        }
+        unsafe {
+            // the placement data is owned by this buffer, glyph buffers are
+            // never shared
+            let raw = &*ffi::blGlyphBufferGetGlyphRun(self.core());
+            slice::from_raw_parts_mut(raw.placementData as *mut GlyphPlacement, len)
+        }
+    }
+
+    /// Adds the given spacing to the advances of the glyphs of this buffer,
+    /// which must have been shaped from `text` with `font`.
+    ///
+    /// Letter spacing is added after every glyph but the last one. Word
+    /// spacing is added to the glyphs produced from a space character.
+    ///
+    /// Returns [`Error::InvalidValue`] and leaves the advances untouched if
+    /// the font has a size of zero or the spacing doesn't fit the advances.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn apply_spacing(&mut self, text: &str, font: &Font, spacing: &TextSpacing) -> Result<()> {
+        let scale = f64::from(font.units_per_em()) / f64::from(font.size());
+        let to_units = |spacing: f64| {
+            let units = (spacing * scale).round();
+            if units.is_finite() && units.abs() <= f64::from(i32::MAX) {
+                Ok(units as i32)
+            } else {
+                Err(Error::InvalidValue)
+            }
+        };
+        let letter = to_units(spacing.letter)?;
+        let word = to_units(spacing.word)?;
+        let run = self.glyph_run();
+        let last = run.placements().len().saturating_sub(1);
+        // compute all advances first to not leave them half spaced on errors
+        let advances = run
+            .placements()
+            .iter()
+            .zip(run.clusters())
+            .enumerate()
+            .map(|(i, (placement, cluster))| {
+                let mut advance = Some(placement.advance.x);
+                if i != last {
+                    advance = advance.and_then(|advance| advance.checked_add(letter));
+                }
+                if text
+                    .get(cluster as usize..)
+                    .map_or(false, |rest| rest.starts_with(' '))
+                {
+                    advance = advance.and_then(|advance| advance.checked_add(word));
+                }
+                advance.ok_or(Error::InvalidValue)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (placement, advance) in self.placements_mut().iter_mut().zip(advances) {
+            placement.advance.x = advance;
+        }
+        Ok(())
+    }
+
+    /// Lists the glyphs of this [`GlyphBuffer`] in a stable textual format
+    /// meant for snapshot tests of shaping results.
+    ///
+    /// Every glyph is written on its own line as its index followed by its
+    /// id, and its cluster and placement in font design units if available:
+    ///
+    /// ```text
+    /// 0: id=68 cluster=0 offset=0,0 advance=1098,0
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let mut dump = String::new();
+        for (i, item) in self.glyph_run().iter().enumerate() {
+            let _ = write!(dump, "{}: id={}", i, item.glyph_id);
+            if let Some(cluster) = item.cluster {
+                let _ = write!(dump, " cluster={}", cluster);
+            }
+            if let Some(GlyphPlacement { placement, advance }) = item.placement {
+                let _ = write!(
+                    dump,
+                    " offset={},{} advance={},{}",
+                    placement.x, placement.y, advance.x, advance.y
+                );
+            }
+            dump.push('\n');
+        }
+        dump
+    }
+
     /// Clears the content of this [`GlyphBuffer`] without releasing internal
     /// buffers.
     #[inline]
@@ -122,11 +315,73 @@ impl GlyphBuffer {
     #[inline]
     pub fn set_utf8_text(&mut self, text: &str) {
         unsafe {
-            expect_mem_err(ffi::blGlyphBufferSetText(
-                self.core_mut(),
-                text.as_bytes().as_ptr() as *const _,
+            self.set_text_raw(
+                text.as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8,
+            )
+        };
+    }
+
+    /// Sets UTF-16 encoded text content of this [`GlyphBuffer`].
+    ///
+    /// Unpaired surrogates are replaced and flagged, see
+    /// [`has_invalid_chars`].
+    ///
+    /// [`has_invalid_chars`]: #method.has_invalid_chars
+    #[inline]
+    pub fn set_utf16_text(&mut self, text: &[u16]) {
+        unsafe {
+            self.set_text_raw(
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16,
+            )
+        };
+    }
+
+    /// Sets UTF-32 encoded text content of this [`GlyphBuffer`].
+    ///
+    /// Values that aren't unicode scalar values are replaced and flagged, see
+    /// [`has_invalid_chars`].
+    ///
+    /// [`has_invalid_chars`]: #method.has_invalid_chars
+    #[inline]
+    pub fn set_utf32_text(&mut self, text: &[u32]) {
+        unsafe {
+            self.set_text_raw(
+                text.as_ptr() as *const _,
+                text.len(),
+                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32,
+            )
+        };
+    }
+
+    #[inline]
+    unsafe fn set_text_raw(
+        &mut self,
+        text: *const c_void,
+        len: usize,
+        encoding: ffi::BLTextEncoding::Type,
+    ) {
+        expect_mem_err(ffi::blGlyphBufferSetText(
+            self.core_mut(),
+            text,
+            len,
+            encoding as u32,
+        ))
+    }
+
+    /// Sets the content of this [`GlyphBuffer`] to already mapped glyphs,
+    /// which can be positioned and rendered without being shaped again.
+    #[inline]
+    pub fn set_glyphs(&mut self, glyphs: &[GlyphId]) {
+        unsafe {
+            expect_mem_err(ffi::blGlyphBufferSetGlyphIds(
+                self.core_mut(),
+                glyphs.as_ptr() as *const _,
+                mem::size_of::<GlyphId>() as isize,
+                glyphs.len(),
             ))
         };
     }
@@ -150,3 +405,111 @@ impl fmt::Debug for GlyphBuffer {
         f.debug_struct("GlyphBuffer").finish()
     }
 }
+
+#[cfg(test)]
+mod test_glyph_buffer {
+    use crate::error::Error;
+    use crate::font::FontFace;
+    use crate::glyph_buffer::{GlyphBuffer, GlyphId, TextSpacing};
+    use crate::DataAccessFlags;
+
+    #[test]
+    fn test_position_glyphs() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("abc");
+        font.map_text_to_glyphs(&mut buf).unwrap();
+        font.position_glyphs(&mut buf).unwrap();
+        let advance = buf.placements()[0].advance.x;
+        assert!(advance > 0);
+        buf.placements_mut()[0].advance.x += 100;
+        assert_eq!(buf.placements()[0].advance.x, advance + 100);
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from_utf8_text("ab");
+        font.shape(&mut buf).unwrap();
+        let dump = buf.debug_dump();
+        let lines = dump.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("1: id="), "{}", dump);
+        assert!(lines[1].contains(" cluster=1 offset="), "{}", dump);
+    }
+
+    #[test]
+    fn test_apply_spacing() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(20.0).unwrap();
+        let text = "a b";
+        let mut buf = GlyphBuffer::from_utf8_text(text);
+        font.shape(&mut buf).unwrap();
+        let before = buf.placements().to_vec();
+        let spacing = TextSpacing {
+            letter: 1.0,
+            word: 2.0,
+        };
+        buf.apply_spacing(text, &font, &spacing).unwrap();
+        let unit = f64::from(font.units_per_em()) / 20.0;
+        let deltas = buf
+            .placements()
+            .iter()
+            .zip(&before)
+            .map(|(after, before)| after.advance.x - before.advance.x)
+            .collect::<Vec<_>>();
+        let (letter, word) = (unit.round() as i32, (2.0 * unit).round() as i32);
+        assert_eq!(deltas, [letter, letter + word, 0]);
+
+        let spaced = buf.placements().to_vec();
+        let spacing = TextSpacing {
+            letter: f64::MAX,
+            word: 0.0,
+        };
+        assert!(matches!(
+            buf.apply_spacing(text, &font, &spacing),
+            Err(Error::InvalidValue)
+        ));
+        let spacing = TextSpacing {
+            letter: 0.0,
+            word: f64::from(i32::MAX) / unit,
+        };
+        assert!(matches!(
+            buf.apply_spacing(text, &font, &spacing),
+            Err(Error::InvalidValue)
+        ));
+        assert_eq!(buf.placements(), &spaced[..]);
+    }
+
+    #[test]
+    fn test_set_text_encodings() {
+        let face =
+            FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let glyphs = |buf: &mut GlyphBuffer| {
+            font.shape(buf).unwrap();
+            buf.glyph_run().glyph_ids().collect::<Vec<_>>()
+        };
+        let mut buf = GlyphBuffer::from_utf8_text("aé");
+        let expected = glyphs(&mut buf);
+
+        buf.set_utf16_text(&"aé".encode_utf16().collect::<Vec<_>>());
+        assert_eq!(buf.len(), 2);
+        assert_eq!(glyphs(&mut buf), expected);
+        buf.set_utf32_text(&['a' as u32, 'é' as u32]);
+        assert_eq!(glyphs(&mut buf), expected);
+
+        buf.set_glyphs(&expected);
+        assert!(buf.has_glyphs() && !buf.has_text());
+        assert_eq!(
+            buf.glyph_run().glyph_ids().collect::<Vec<GlyphId>>(),
+            expected
+        );
+        buf.clear();
+        assert!(buf.is_empty());
+    }
+}
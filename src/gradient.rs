@@ -3,16 +3,22 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 use std::ops::{self, RangeBounds};
-use std::{fmt, mem, ptr, slice};
+use std::{cmp, fmt, mem, ptr, slice};
 
 use ffi::BLGradientValue::*;
 
-use crate::error::{expect_mem_err, OutOfMemory};
+use crate::error::{expect_mem_err, out_of_memory, OutOfMemory};
+use crate::geometry::PointD;
 use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
+use crate::rgba::{Rgba32, Rgba64};
 use crate::util::range_to_tuple;
 use crate::variant::WrappedBlCore;
 use crate::ExtendMode;
 
+mod svg;
+
+pub use self::svg::{from_svg_attrs, SvgGradientKind};
+
 mod private {
     pub trait Sealed {}
     impl Sealed for super::Linear {}
@@ -60,6 +66,23 @@ pub struct GradientStop {
     pub rgba: u64,
 }
 
+impl GradientStop {
+    /// Creates a stop at the given offset with a 32-bit color.
+    #[inline]
+    pub fn new<C: Into<Rgba32>>(offset: f64, color: C) -> Self {
+        Self::new64(offset, Rgba64::from(color.into()))
+    }
+
+    /// Creates a stop at the given offset with a 64-bit color.
+    #[inline]
+    pub fn new64<C: Into<Rgba64>>(offset: f64, color: C) -> Self {
+        GradientStop {
+            offset,
+            rgba: color.into().value,
+        }
+    }
+}
+
 /// The values that make up a [`LinearGradient`].
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
@@ -91,13 +114,25 @@ pub struct ConicalGradientValues {
 }
 
 /// A Dynamic Gradient
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum DynamicGradient {
     Linear(LinearGradient),
     Radial(RadialGradient),
     Conical(ConicalGradient),
 }
 
+impl DynamicGradient {
+    /// Wraps a gradient core according to its gradient type.
+    pub(in crate) fn from_core(core: ffi::BLGradientCore) -> Self {
+        let gradient_type = unsafe { u32::from((*core.impl_).gradientType) };
+        match gradient_type {
+            Radial::BL_TYPE => DynamicGradient::Radial(Gradient::from_core(core)),
+            Conical::BL_TYPE => DynamicGradient::Conical(Gradient::from_core(core)),
+            _ => DynamicGradient::Linear(Gradient::from_core(core)),
+        }
+    }
+}
+
 impl From<LinearGradient> for DynamicGradient {
     fn from(g: LinearGradient) -> Self {
         DynamicGradient::Linear(g)
@@ -267,12 +302,16 @@ impl<T: GradientType> Gradient<T> {
     ///
     /// # Panics
     ///
-    /// Panics if blend2d returns an [`OutOfMemory`] error.
+    /// Panics if blend2d returns an [`OutOfMemory`] error with the default
+    /// [`OomPolicy`].
     ///
     /// [`OutOfMemory`]: ../error/enum.Error.html#variant.OutOfMemory
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
     #[inline]
     pub fn reserve(&mut self, n: usize) {
-        self.try_reserve(n).expect("memory allocation failed");
+        if self.try_reserve(n).is_err() {
+            out_of_memory();
+        }
     }
 
     /// Reserves the capacity of gradient stops for at least `n` stops.
@@ -281,6 +320,25 @@ impl<T: GradientType> Gradient<T> {
         unsafe { OutOfMemory::from_errcode(ffi::blGradientReserve(self.core_mut(), n)) }
     }
 
+    /// Returns the number of gradients sharing the values, stops and matrix of
+    /// this gradient, including this one.
+    ///
+    /// Clones share them until one of the gradients is modified.
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.impl_().ref_count()
+    }
+
+    /// Checks whether the values, stops and matrix of this gradient are shared
+    /// with a clone, in which case adding a stop or changing any of them
+    /// copies them first.
+    ///
+    /// A default gradient is never shared.
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        !self.is_none() && self.ref_count() > 1
+    }
+
     /// Shrinks the capacity of gradient stops to fit the current usage.
     #[inline]
     pub fn shrink_to_fit(&mut self) {
@@ -406,17 +464,138 @@ impl<T: GradientType> Gradient<T> {
         };
     }
 
+    /// Like [`add_stop`], but returns a failed allocation instead of
+    /// handling it according to the [`OomPolicy`].
+    ///
+    /// [`add_stop`]: #method.add_stop
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
+    #[inline]
+    pub fn try_add_stop(&mut self, stop: GradientStop) -> std::result::Result<(), OutOfMemory> {
+        unsafe {
+            OutOfMemory::from_errcode(ffi::blGradientAddStopRgba64(
+                self.core_mut(),
+                stop.offset,
+                stop.rgba,
+            ))
+        }
+    }
+
     /// Adds a gradient stop to the buffer.
     #[inline]
-    pub fn add_stop32(&mut self, offset: f64, rgba: u32) {
+    pub fn add_stop32<C: Into<Rgba32>>(&mut self, offset: f64, rgba: C) {
+        let rgba = rgba.into().value;
         unsafe { expect_mem_err(ffi::blGradientAddStopRgba32(self.core_mut(), offset, rgba)) };
     }
 
     /// Adds a gradient stop to the buffer.
     #[inline]
-    pub fn add_stop64(&mut self, offset: f64, rgba: u64) {
+    pub fn add_stop64<C: Into<Rgba64>>(&mut self, offset: f64, rgba: C) {
+        let rgba = rgba.into().value;
         unsafe { expect_mem_err(ffi::blGradientAddStopRgba64(self.core_mut(), offset, rgba)) };
     }
+
+    /// Adds the given offset and color pairs as stops and returns the
+    /// gradient, allowing gradients to be created in a single expression like
+    /// `LinearGradient::linear_from_points(p0, p1).with_stops(&[(0.0,
+    /// 0xFFFFFFFF), (1.0, 0xFF000000)])`.
+    pub fn with_stops<C: Into<Rgba32> + Copy>(mut self, stops: &[(f64, C)]) -> Self {
+        self.extend(
+            stops
+                .iter()
+                .map(|&(offset, color)| GradientStop::new(offset, color)),
+        );
+        self
+    }
+}
+
+impl<T: GradientType> Gradient<T> {
+    /// Returns a copy of this gradient with the alpha of all stops multiplied
+    /// by `factor`, which is clamped to `[0, 1]`.
+    pub fn with_opacity(&self, factor: f64) -> Self {
+        let factor = factor.max(0.0).min(1.0);
+        let stops: Vec<_> = self
+            .iter()
+            .map(|stop| GradientStop {
+                offset: stop.offset,
+                rgba: scale_alpha(stop.rgba, factor),
+            })
+            .collect();
+        self.clone_with_stops(&stops)
+    }
+
+    /// Returns a copy of this gradient that fades in from transparent over the
+    /// first `fade_in` and out to transparent over the last `fade_out` of its
+    /// offset range.
+    ///
+    /// Both lengths are clamped so that they don't overlap. Stops are
+    /// inserted where the fades begin and end if necessary, so the colors of
+    /// the gradient are preserved.
+    pub fn fade_edges(&self, fade_in: f64, fade_out: f64) -> Self {
+        let fade_in = fade_in.max(0.0).min(1.0);
+        let fade_out = fade_out.max(0.0).min(1.0 - fade_in);
+        let stops = self.stops();
+        if stops.is_empty() {
+            return self.clone();
+        }
+        let mut faded = stops.to_vec();
+        for &offset in &[0.0, fade_in, 1.0 - fade_out, 1.0] {
+            if faded.iter().all(|stop| stop.offset != offset) {
+                faded.push(GradientStop {
+                    offset,
+                    rgba: color_at(stops, offset),
+                });
+            }
+        }
+        // stable, so stops sharing an offset keep their order
+        faded.sort_by(|a, b| {
+            a.offset
+                .partial_cmp(&b.offset)
+                .unwrap_or(cmp::Ordering::Equal)
+        });
+        for stop in &mut faded {
+            let mut alpha = 1.0;
+            if stop.offset < fade_in {
+                alpha = stop.offset / fade_in;
+            }
+            if stop.offset > 1.0 - fade_out {
+                alpha = alpha.min((1.0 - stop.offset) / fade_out);
+            }
+            stop.rgba = scale_alpha(stop.rgba, alpha);
+        }
+        self.clone_with_stops(&faded)
+    }
+
+    fn clone_with_stops(&self, stops: &[GradientStop]) -> Self {
+        Self::new(self.values(), self.extend_mode(), stops, self.matrix())
+    }
+}
+
+/// Multiplies the alpha of the non-premultiplied color by `factor`.
+fn scale_alpha(rgba: u64, factor: f64) -> u64 {
+    let color = Rgba64::new(rgba);
+    let alpha = (f64::from(color.a()) * factor).round() as u16;
+    color.with_alpha(alpha).value
+}
+
+/// Interpolates the color of the sorted stops at the given offset.
+fn color_at(stops: &[GradientStop], offset: f64) -> u64 {
+    match stops.iter().position(|stop| stop.offset > offset) {
+        Some(0) => stops[0].rgba,
+        None => stops[stops.len() - 1].rgba,
+        Some(i) => {
+            let (a, b) = (&stops[i - 1], &stops[i]);
+            let t = (offset - a.offset) / (b.offset - a.offset);
+            let (ca, cb) = (
+                Rgba64::new(a.rgba).to_array(),
+                Rgba64::new(b.rgba).to_array(),
+            );
+            let mut color = [0; 4];
+            for (c, (&ca, &cb)) in color.iter_mut().zip(ca.iter().zip(&cb)) {
+                *c = (f64::from(ca) + (f64::from(cb) - f64::from(ca)) * t).round() as u16;
+            }
+            Rgba64::from(color).value
+        },
+    }
 }
 
 impl Gradient<Linear> {
@@ -433,6 +612,18 @@ impl Gradient<Linear> {
         Self::new(values, extend_mode, stops, m)
     }
 
+    /// Creates a linear gradient without stops running from `p0` to `p1`.
+    #[inline]
+    pub fn linear_from_points(p0: PointD, p1: PointD) -> Self {
+        let values = LinearGradientValues {
+            x0: p0.x,
+            y0: p0.y,
+            x1: p1.x,
+            y1: p1.y,
+        };
+        Self::new(&values, Default::default(), &[], None)
+    }
+
     /// Returns the x1 value of this gradient.
     #[inline]
     pub fn x1(&self) -> f64 {
@@ -472,6 +663,20 @@ impl Gradient<Radial> {
         Self::new(values, extend_mode, stops, m)
     }
 
+    /// Creates a radial gradient without stops around the circle at `center`
+    /// with the given radius, focused at `focal`.
+    #[inline]
+    pub fn radial_from_points(center: PointD, focal: PointD, radius: f64) -> Self {
+        let values = RadialGradientValues {
+            x0: center.x,
+            y0: center.y,
+            x1: focal.x,
+            y1: focal.y,
+            r0: radius,
+        };
+        Self::new(&values, Default::default(), &[], None)
+    }
+
     /// Returns the x1 value of this gradient.
     #[inline]
     pub fn x1(&self) -> f64 {
@@ -523,6 +728,18 @@ impl Gradient<Conical> {
         Self::new(values, extend_mode, stops, m)
     }
 
+    /// Creates a conical gradient without stops around `center`, starting at
+    /// the given angle.
+    #[inline]
+    pub fn conical_from_point(center: PointD, angle: f64) -> Self {
+        let values = ConicalGradientValues {
+            x0: center.x,
+            y0: center.y,
+            angle,
+        };
+        Self::new(&values, Default::default(), &[], None)
+    }
+
     /// Returns the angle of this gradient.
     #[inline]
     pub fn angle(&self) -> f64 {
@@ -536,6 +753,96 @@ impl Gradient<Conical> {
     }
 }
 
+/// A builder for [`Gradient`]s, collecting the values, stops, extend mode and
+/// matrix before creating the gradient at once.
+///
+/// [`Gradient`]: struct.Gradient.html
+pub struct GradientBuilder<T: GradientType> {
+    values: T::ValuesType,
+    extend_mode: ExtendMode,
+    stops: Vec<GradientStop>,
+    matrix: Option<Matrix2D>,
+}
+
+impl<T: GradientType> GradientBuilder<T> {
+    /// Creates a builder for a gradient with the given values.
+    #[inline]
+    pub fn new(values: T::ValuesType) -> Self {
+        GradientBuilder {
+            values,
+            extend_mode: Default::default(),
+            stops: Vec::new(),
+            matrix: None,
+        }
+    }
+
+    /// Sets the [`ExtendMode`] of the gradient.
+    ///
+    /// [`ExtendMode`]: ../enum.ExtendMode.html
+    #[inline]
+    pub fn extend_mode(mut self, extend_mode: ExtendMode) -> Self {
+        self.extend_mode = extend_mode;
+        self
+    }
+
+    /// Adds a stop with a 32-bit color.
+    #[inline]
+    pub fn stop<C: Into<Rgba32>>(mut self, offset: f64, color: C) -> Self {
+        self.stops.push(GradientStop::new(offset, color));
+        self
+    }
+
+    /// Adds a stop with a 64-bit color.
+    #[inline]
+    pub fn stop64<C: Into<Rgba64>>(mut self, offset: f64, color: C) -> Self {
+        self.stops.push(GradientStop::new64(offset, color));
+        self
+    }
+
+    /// Sets the transformation matrix of the gradient.
+    #[inline]
+    pub fn matrix(mut self, matrix: &Matrix2D) -> Self {
+        self.matrix = Some(*matrix);
+        self
+    }
+
+    /// Creates the gradient.
+    #[inline]
+    pub fn build(&self) -> Gradient<T> {
+        Gradient::new(
+            &self.values,
+            self.extend_mode,
+            &self.stops,
+            self.matrix.as_ref(),
+        )
+    }
+}
+
+impl<T> fmt::Debug for GradientBuilder<T>
+where
+    T: GradientType,
+    T::ValuesType: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GradientBuilder")
+            .field("values", &self.values)
+            .field("extend_mode", &self.extend_mode)
+            .field("stops", &self.stops)
+            .field("matrix", &self.matrix)
+            .finish()
+    }
+}
+
+impl<T: GradientType> Gradient<T> {
+    /// Returns a [`GradientBuilder`] for a gradient with the given values.
+    ///
+    /// [`GradientBuilder`]: struct.GradientBuilder.html
+    #[inline]
+    pub fn builder(values: T::ValuesType) -> GradientBuilder<T> {
+        GradientBuilder::new(values)
+    }
+}
+
 impl<'a, T: GradientType> From<&'a T::ValuesType> for Gradient<T> {
     #[inline]
     fn from(v: &T::ValuesType) -> Self {
@@ -557,6 +864,18 @@ impl<T: GradientType> MatrixTransform for Gradient<T> {
     }
 }
 
+impl MatrixTransform for DynamicGradient {
+    #[inline]
+    #[doc(hidden)]
+    fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
+        match self {
+            DynamicGradient::Linear(g) => g.apply_matrix_op(op, data),
+            DynamicGradient::Radial(g) => g.apply_matrix_op(op, data),
+            DynamicGradient::Conical(g) => g.apply_matrix_op(op, data),
+        }
+    }
+}
+
 impl<T> Default for Gradient<T>
 where
     T: GradientType,
@@ -662,8 +981,10 @@ impl<T: GradientType> Drop for Gradient<T> {
 #[cfg(test)]
 mod test_gradient {
     use crate::{
+        geometry::PointD,
         gradient::{Conical, Gradient, GradientStop, Linear, LinearGradientValues},
         matrix::{Matrix2D, MatrixTransform},
+        rgba::Rgba32,
         ExtendMode,
     };
 
@@ -719,4 +1040,77 @@ mod test_gradient {
 
         assert_eq!(gradient, default);
     }
+
+    #[test]
+    fn test_gradient_with_opacity() {
+        let stops = [GradientStop {
+            offset: 0.0,
+            rgba: 0xFFFF_1234_5678_9ABC,
+        }];
+        let gradient =
+            Gradient::<Linear>::new(&Default::default(), ExtendMode::PadXPadY, &stops, None);
+        let faded = gradient.with_opacity(0.5);
+        assert_eq!(faded.stops()[0].rgba, 0x8000_1234_5678_9ABC);
+        assert_eq!(gradient.with_opacity(2.0), gradient);
+    }
+
+    #[test]
+    fn test_gradient_fade_edges() {
+        let stops = [
+            GradientStop {
+                offset: 0.0,
+                rgba: 0xFFFF_0000_0000_0000,
+            },
+            GradientStop {
+                offset: 1.0,
+                rgba: 0xFFFF_FFFF_FFFF_FFFF,
+            },
+        ];
+        let gradient =
+            Gradient::<Linear>::new(&Default::default(), ExtendMode::PadXPadY, &stops, None);
+        let faded = gradient.fade_edges(0.25, 0.5);
+        let faded: Vec<_> = faded.iter().map(|stop| (stop.offset, stop.rgba)).collect();
+        assert_eq!(
+            faded,
+            [
+                (0.0, 0x0000_0000_0000_0000),
+                (0.25, 0xFFFF_4000_4000_4000),
+                (0.5, 0xFFFF_8000_8000_8000),
+                (1.0, 0x0000_FFFF_FFFF_FFFF),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gradient_stop_helpers() {
+        let built = Gradient::<Linear>::builder(LinearGradientValues {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 0.0,
+            y1: 100.0,
+        })
+        .stop(0.0, 0xFF_FF_FF_FF)
+        .stop(1.0, Rgba32::BLACK)
+        .build();
+        let from_points = Gradient::linear_from_points(PointD::ZERO, PointD::new(0.0, 100.0))
+            .with_stops(&[(0.0, Rgba32::WHITE), (1.0, Rgba32::BLACK)]);
+        assert_eq!(built, from_points);
+        assert_eq!(
+            from_points.stops()[0],
+            GradientStop::new64(0.0, 0xFFFF_FFFF_FFFF_FFFF)
+        );
+    }
+
+    #[test]
+    fn test_gradient_try_add_stop() {
+        let stops = [
+            GradientStop::new64(0.0, 0xFFFF_0000_0000_0000),
+            GradientStop::new64(1.0, 0xFFFF_FFFF_FFFF_FFFF),
+        ];
+        let mut gradient = Gradient::<Linear>::default();
+        for &stop in &stops {
+            gradient.try_add_stop(stop).unwrap();
+        }
+        assert_eq!(gradient.stops(), &stops);
+    }
 }
@@ -0,0 +1,170 @@
+use crate::error::{Error, Result};
+use crate::matrix::Matrix2D;
+use crate::rgba::Rgba32;
+use crate::svg_attrs;
+use crate::ExtendMode;
+
+use super::{
+    DynamicGradient, GradientStop, LinearGradient, LinearGradientValues, RadialGradient,
+    RadialGradientValues,
+};
+
+/// The kind of an SVG gradient element.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SvgGradientKind {
+    /// A `<linearGradient>` element.
+    Linear,
+    /// A `<radialGradient>` element.
+    Radial,
+}
+
+/// Creates a gradient from the attributes of an SVG `<linearGradient>` or
+/// `<radialGradient>` element and the attributes of its `<stop>` children.
+///
+/// The coordinate attributes, `gradientUnits`, `gradientTransform` and
+/// `spreadMethod` are recognized, stops are read from their `offset`,
+/// `stop-color` and `stop-opacity` attributes and `style` declarations.
+/// Other attributes are ignored, the last one wins if an attribute is given
+/// more than once.
+///
+/// The coordinates of a gradient in `objectBoundingBox` units, the default,
+/// are relative to the unit square. Such a gradient has to be post-transformed
+/// with the matrix mapping the unit square onto the bounding box of the shape
+/// it paints. The percentages of `userSpaceOnUse` coordinates depend on the
+/// viewport, which is unknown here, so they have to be resolved by the caller
+/// including the ones of missing attributes. Returns
+/// [`Error::InvalidValue`] if a recognized attribute has a malformed value or
+/// is an unresolved percentage.
+///
+/// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+pub fn from_svg_attrs(
+    kind: SvgGradientKind,
+    attrs: &[(&str, &str)],
+    stops: &[&[(&str, &str)]],
+) -> Result<DynamicGradient> {
+    let attr = |name: &str| {
+        attrs
+            .iter()
+            .rev()
+            .find(|&&(attr, _)| attr == name)
+            .map(|&(_, value)| value)
+    };
+    let object_bounding_box = match attr("gradientUnits") {
+        None | Some("objectBoundingBox") => true,
+        Some("userSpaceOnUse") => false,
+        Some(_) => return Err(Error::InvalidValue),
+    };
+    let coordinate = |value: &str| {
+        if object_bounding_box {
+            svg_attrs::fraction(value)
+        } else if value.trim_end().ends_with('%') {
+            Err(Error::InvalidValue)
+        } else {
+            svg_attrs::length(value, 0.0)
+        }
+    };
+    let length = |name: &str, default: &str| coordinate(attr(name).unwrap_or(default));
+
+    let matrix =
+        attr("gradientTransform").map_or(Ok(Matrix2D::identity()), svg_attrs::transform)?;
+    let extend_mode = match attr("spreadMethod") {
+        None | Some("pad") => ExtendMode::PadXPadY,
+        Some("reflect") => ExtendMode::ReflectXReflectY,
+        Some("repeat") => ExtendMode::RepeatXRepeatY,
+        Some(_) => return Err(Error::InvalidValue),
+    };
+    let mut gradient_stops = Vec::with_capacity(stops.len());
+    let mut last_offset = 0.0f64;
+    for stop in stops {
+        // offsets are clamped to be increasing
+        let mut stop = parse_stop(stop)?;
+        stop.offset = stop.offset.max(last_offset);
+        last_offset = stop.offset;
+        gradient_stops.push(stop);
+    }
+
+    Ok(match kind {
+        SvgGradientKind::Linear => {
+            let values = LinearGradientValues {
+                x0: length("x1", "0%")?,
+                y0: length("y1", "0%")?,
+                x1: length("x2", "100%")?,
+                y1: length("y2", "0%")?,
+            };
+            LinearGradient::new(&values, extend_mode, &gradient_stops, &matrix).into()
+        },
+        SvgGradientKind::Radial => {
+            let (cx, cy) = (length("cx", "50%")?, length("cy", "50%")?);
+            let values = RadialGradientValues {
+                x0: cx,
+                y0: cy,
+                x1: attr("fx").map_or(Ok(cx), coordinate)?,
+                y1: attr("fy").map_or(Ok(cy), coordinate)?,
+                r0: length("r", "50%")?,
+            };
+            RadialGradient::new(&values, extend_mode, &gradient_stops, &matrix).into()
+        },
+    })
+}
+
+fn parse_stop(attrs: &[(&str, &str)]) -> Result<GradientStop> {
+    let mut offset = 0.0;
+    let mut color = Rgba32::new(0xFF_00_00_00);
+    let mut opacity = 1.0;
+    let style = attrs
+        .iter()
+        .filter(|&&(name, _)| name == "style")
+        .flat_map(|&(_, style)| svg_attrs::declarations(style));
+    // style declarations take precedence over attributes
+    for (name, value) in attrs.iter().copied().chain(style) {
+        match name {
+            "offset" => offset = clamp_unit(svg_attrs::fraction(value)?),
+            "stop-color" => color = svg_attrs::color(value)?,
+            "stop-opacity" => opacity = clamp_unit(svg_attrs::fraction(value)?),
+            _ => {},
+        }
+    }
+    let alpha = (f64::from(color.a()) * opacity).round() as u8;
+    Ok(GradientStop::new(offset, color.with_alpha(alpha)))
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    value.max(0.0).min(1.0)
+}
+
+#[cfg(test)]
+mod test_svg {
+    use crate::gradient::{from_svg_attrs, DynamicGradient, SvgGradientKind};
+    use crate::matrix::Matrix2D;
+    use crate::rgba::{Rgba32, Rgba64};
+    use crate::ExtendMode;
+
+    #[test]
+    fn test_from_svg_attrs() {
+        let attrs = [
+            ("x2", "50%"),
+            ("spreadMethod", "reflect"),
+            ("gradientTransform", "scale(2)"),
+        ];
+        let stops: [&[(&str, &str)]; 2] = [
+            &[("offset", "0.5"), ("stop-color", "red")],
+            &[
+                ("offset", "0.25"),
+                ("style", "stop-color: #00f; stop-opacity: 0"),
+            ],
+        ];
+        let gradient = match from_svg_attrs(SvgGradientKind::Linear, &attrs, &stops).unwrap() {
+            DynamicGradient::Linear(gradient) => gradient,
+            _ => panic!("expected a linear gradient"),
+        };
+        assert_eq!(gradient.x1(), 0.5);
+        assert_eq!(gradient.extend_mode(), ExtendMode::ReflectXReflectY);
+        assert_eq!(*gradient.matrix(), Matrix2D::scaling(2.0, 2.0));
+        let stops = gradient.stops();
+        assert_eq!((stops[0].offset, stops[1].offset), (0.5, 0.5));
+        assert_eq!(stops[1].rgba, Rgba64::from(Rgba32::new(0xFF)).value);
+
+        let user_space = [("gradientUnits", "userSpaceOnUse"), ("cx", "10%")];
+        assert!(from_svg_attrs(SvgGradientKind::Radial, &user_space, &[]).is_err());
+    }
+}
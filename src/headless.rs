@@ -0,0 +1,94 @@
+//! One call rendering into encoded images, for services and tests that only
+//! need the rendered bytes.
+use crate::array::Array;
+use crate::codec::ImageCodec;
+use crate::context::{CompOp, Context};
+use crate::error::{Error, Result};
+use crate::image::{Image, ImageFormat};
+use crate::rgba::Rgba32;
+
+/// Renders an image of the given size filled with the background color by
+/// running `draw` on a context targeting it.
+///
+/// The context is created with the default single threaded options, so the
+/// same closure always renders the same pixels. The image has the
+/// [`ImageFormat::PRgb32`] format, a transparent background leaves it
+/// cleared.
+///
+/// [`ImageFormat::PRgb32`]: ../image/enum.ImageFormat.html#variant.PRgb32
+pub fn render<C, F>(width: i32, height: i32, background: C, draw: F) -> Result<Image>
+where
+    C: Into<Rgba32>,
+    F: FnOnce(&mut Context<'_>) -> Result<()>,
+{
+    let mut image = Image::new(width, height, ImageFormat::PRgb32)?;
+    let mut ctx = Context::new(&mut image)?;
+    let background = background.into();
+    if background.a() == 0 {
+        ctx.clear_all()?;
+    } else {
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_style_rgba32(background);
+        ctx.fill_all()?;
+        ctx.set_comp_op(CompOp::SrcOver);
+    }
+    draw(&mut ctx)?;
+    ctx.end()?;
+    Ok(image)
+}
+
+/// Like [`render`] but returns the image encoded as PNG.
+///
+/// Returns [`Error::ImageNoMatchingCodec`] if blend2d was built without the
+/// PNG codec.
+///
+/// [`render`]: fn.render.html
+/// [`Error::ImageNoMatchingCodec`]: ../error/enum.Error.html#variant.ImageNoMatchingCodec
+pub fn render_png<C, F>(width: i32, height: i32, background: C, draw: F) -> Result<Vec<u8>>
+where
+    C: Into<Rgba32>,
+    F: FnOnce(&mut Context<'_>) -> Result<()>,
+{
+    let codecs = ImageCodec::built_in_codecs();
+    let codec = codecs
+        .find_codec_by_name("PNG")
+        .ok_or(Error::ImageNoMatchingCodec)?;
+    let image = render(width, height, background, draw)?;
+    let mut data = Array::<u8>::new();
+    image.write_to_data(&mut data, codec)?;
+    Ok(data.to_vec())
+}
+
+#[cfg(test)]
+mod test_headless {
+    use crate::error::Error;
+    use crate::headless::{render, render_png};
+
+    #[test]
+    fn test_render() {
+        let image = render(4, 4, 0xFF_FF_FF_FF, |ctx| {
+            ctx.set_fill_style_rgba32(0xFF_FF_00_00);
+            ctx.fill_rect(0.0, 0.0, 2.0, 4.0)
+        })
+        .unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(3, 3), Some(0xFF_FF_FF_FF));
+
+        let image = render(2, 2, 0, |_| Ok(())).unwrap();
+        assert_eq!(image.pixel(1, 1), Some(0));
+
+        let failed = render(2, 2, 0, |_| Err(Error::InvalidValue));
+        assert!(matches!(failed, Err(Error::InvalidValue)));
+    }
+
+    #[test]
+    fn test_render_png() {
+        let draw = |ctx: &mut crate::context::Context<'_>| {
+            ctx.set_fill_style_rgba32(0xFF_00_80_FF);
+            ctx.fill_circle(8.0, 8.0, 5.0)
+        };
+        let png = render_png(16, 16, 0xFF_FF_FF_FF, draw).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(png, render_png(16, 16, 0xFF_FF_FF_FF, draw).unwrap());
+    }
+}
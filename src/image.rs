@@ -1,17 +1,45 @@
 //! Image loading and handling.
+mod color_key;
+mod compare;
+mod composite;
+mod convolve;
+mod crop;
+mod data_uri;
+#[cfg(feature = "image")]
+mod image_crate;
+mod linear;
+mod mask;
+mod pixels;
+mod premultiply;
+mod resize;
+mod stats;
+mod stream;
+mod tiles;
+
+pub use self::compare::{CompareOptions, ImageDiff};
+pub use self::convolve::Kernel;
+pub(crate) use self::mask::scale_prgb32;
+pub use self::pixels::{PixelRows, PixelRowsMut, Pixels, PixelsMut};
+pub use self::premultiply::PremultiplyMode;
+pub(crate) use self::premultiply::{premultiply_pixel, unpremultiply_pixel};
+pub use self::stats::{Histogram, ImageStats};
+pub use self::tiles::{Tile, TileMut, Tiles, TilesMut};
+
 use bitflags::bitflags;
 
 use std::ffi::CString;
+use std::marker::PhantomData;
 use std::path::Path;
 use std::{fmt, mem, ops, ptr, slice};
 
 use ffi::{self, BLImageCore};
 
 use crate::array::Array;
-use crate::codec::ImageCodec;
-use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::codec::{ImageCodec, ImageCodecFeatures};
+use crate::context::Context;
+use crate::error::{errcode_to_result, expect_mem_err, Error, Result};
 use crate::geometry::{SizeD, SizeI};
-use crate::variant::WrappedBlCore;
+use crate::variant::{DeepClone, WrappedBlCore};
 
 const IMAGE_SCALE_OPTIONS_ZEROED: ffi::BLImageScaleOptions = ffi::BLImageScaleOptions {
     userFunc: None,
@@ -36,6 +64,17 @@ bl_enum! {
     Default => PRgb32
 }
 
+impl ImageFormat {
+    /// The number of bytes a single pixel of this format occupies.
+    #[inline]
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            ImageFormat::PRgb32 | ImageFormat::XRgb32 => 4,
+            ImageFormat::A8 => 1,
+        }
+    }
+}
+
 use ffi::BLFormatFlags;
 bitflags! {
     /// Pixel format flags.
@@ -206,30 +245,57 @@ impl Image {
         }
     }
 
-    /* FIXME figure out a solution for the lifetime issue
-    #[inline]
-    pub fn new_external(
+    /// Creates an image that renders directly into the given borrowed pixel
+    /// buffer instead of allocating its own pixel data.
+    ///
+    /// `stride` is the number of bytes between the starts of two rows. The
+    /// buffer has to hold at least `stride * height` bytes, including the
+    /// padding of the last row, and be aligned to the size of a pixel,
+    /// otherwise [`Error::InvalidValue`] is returned.
+    ///
+    /// See [`ExternalImage`] for the rules regarding the borrowed buffer.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn from_borrowed_data(
         width: i32,
         height: i32,
         format: ImageFormat,
-        data: &'a mut [u8],
-        stride: isize,
-    ) -> Result<Image<'a>> {
+        data: &mut [u8],
+        stride: usize,
+    ) -> Result<ExternalImage<'_>> {
+        let bpp = format.bytes_per_pixel();
+        if width <= 0 || height <= 0 {
+            return Err(Error::InvalidValue);
+        }
+        let row_len = width as usize * bpp;
+        // the pixel slices of an image always span `stride * height` bytes
+        let required = stride.checked_mul(height as usize);
+        if stride < row_len || required.map_or(true, |required| data.len() < required) {
+            return Err(Error::InvalidValue);
+        }
+        // typed pixel access requires every row to be aligned for its pixels
+        if stride % bpp != 0 || data.as_ptr() as usize % bpp != 0 {
+            return Err(Error::InvalidValue);
+        }
+
+        let mut image = Image::from_core(*Self::none());
         unsafe {
-            let mut this = Image::from_core(*Self::none());
             errcode_to_result(ffi::blImageCreateFromData(
-                this.core_mut(),
+                image.core_mut(),
                 width,
                 height,
                 format.into(),
                 data.as_mut_ptr() as *mut _,
-                stride,
+                stride as isize,
                 None,
                 ptr::null_mut(),
-            ))
-            .map(|_| this)
+            ))?;
         }
-    }*/
+        Ok(ExternalImage {
+            image,
+            _pd: PhantomData,
+        })
+    }
 
     /// Attempts to create a new image with the specified dimensions and image
     /// format by decoding the data with the given codec.
@@ -265,6 +331,48 @@ impl Image {
         }
     }
 
+    /// Returns the number of references to the data of this image.
+    ///
+    /// Cloning an image, creating a [`Pattern`] from it or rendering to it
+    /// with a [`Context`] creates a new reference to its data, the data is
+    /// copied once a shared reference is mutated.
+    ///
+    /// [`Pattern`]: ../pattern/struct.Pattern.html
+    /// [`Context`]: ../context/struct.Context.html
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.impl_().ref_count()
+    }
+
+    /// Checks whether the pixel data of this image is referenced elsewhere,
+    /// in which case writing to the pixels copies them first.
+    ///
+    /// An image that is still rendered to by a [`Context`] is shared, an
+    /// empty image never is.
+    ///
+    /// [`Context`]: ../context/struct.Context.html
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        !self.is_none() && self.ref_count() > 1
+    }
+
+    /// Makes sure the pixel data of this image is referenced by it only, by
+    /// copying the pixels if it [`is_shared`], and returns the image for
+    /// mutation.
+    ///
+    /// Like [`Rc::make_mut`] this makes the point at which the pixels of a
+    /// shared image are copied explicit, which for large images is better
+    /// done up front than on the first write to the pixels.
+    ///
+    /// [`is_shared`]: #method.is_shared
+    /// [`Rc::make_mut`]: https://doc.rust-lang.org/std/rc/struct.Rc.html#method.make_mut
+    pub fn make_mut(&mut self) -> &mut Image {
+        if self.is_shared() {
+            *self = self.clone_deep();
+        }
+        self
+    }
+
     /// This image's format.
     #[inline]
     pub fn format(&self) -> ImageFormat {
@@ -327,6 +435,44 @@ impl Image {
         }
     }
 
+    /// Returns a copy of this image scaled by the given `scale` factor using
+    /// the given filter, or an unscaled deep copy if `scale` is `1.0`.
+    ///
+    /// The returned image does not share its pixel data with this image, so
+    /// it stays unaffected by later rendering. All rendering into this image
+    /// has to be finished before taking the snapshot, that is the rendering
+    /// context has to be ended or dropped.
+    ///
+    /// Returns [`Error::InvalidValue`] if `scale` is not a positive finite
+    /// number.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn snapshot(&self, scale: f64, filter: ImageScaleFilter) -> Result<Image> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(Error::InvalidValue);
+        }
+        if scale == 1.0 {
+            return Ok(self.clone_deep());
+        }
+        let size = self.size();
+        let size = SizeI {
+            w: ((f64::from(size.w) * scale).round() as i32).max(1),
+            h: ((f64::from(size.h) * scale).round() as i32).max(1),
+        };
+        let mut this = Image::from_core(*Self::none());
+        unsafe {
+            let opts = filter.into_options();
+            errcode_to_result(ffi::blImageScale(
+                this.core_mut(),
+                self.core(),
+                &size as *const _ as *const _,
+                filter.filter(),
+                opts.as_ref().map_or(ptr::null(), |opt| opt as *const _),
+            ))
+            .map(|_| this)
+        }
+    }
+
     // FIXME: Allow the closure to return an error
     #[inline]
     pub fn scale_user<F>(&mut self, size: SizeI, radius: f64, mut filter: F) -> Result<()>
@@ -379,6 +525,28 @@ impl Image {
         }
     }
 
+    /// Writes the image to the file at the given path with the built in codec
+    /// matching the path's extension, or with the PNG codec if no codec
+    /// matches.
+    ///
+    /// Returns [`Error::ImageNoMatchingCodec`] if no codec matches and blend2d
+    /// was built without the PNG codec and [`Error::ImageEncoderNotProvided`]
+    /// if the matching codec can only read images.
+    ///
+    /// [`Error::ImageNoMatchingCodec`]: ../error/enum.Error.html#variant.ImageNoMatchingCodec
+    /// [`Error::ImageEncoderNotProvided`]: ../error/enum.Error.html#variant.ImageEncoderNotProvided
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let codecs = ImageCodec::built_in_codecs();
+        let codec = codecs
+            .find_codec_by_extension(&path)
+            .or_else(|| codecs.find_codec_by_name("PNG"))
+            .ok_or(Error::ImageNoMatchingCodec)?;
+        if !codec.has_feature(ImageCodecFeatures::Write) {
+            return Err(Error::ImageEncoderNotProvided);
+        }
+        self.write_to_file(path, codec)
+    }
+
     /// Writes the image to the given [`Array`].
     #[inline]
     pub fn write_to_data(&self, dst: &mut Array<u8>, codec: &ImageCodec) -> Result<()> {
@@ -448,6 +616,13 @@ impl PartialEq for Image {
     }
 }
 
+impl Default for Image {
+    #[inline]
+    fn default() -> Self {
+        Image::from_core(*Self::none())
+    }
+}
+
 impl Clone for Image {
     #[inline]
     fn clone(&self) -> Image {
@@ -461,6 +636,71 @@ impl Drop for Image {
     }
 }
 
+/// An image whose pixel data is a borrowed external buffer, created by
+/// [`Image::from_borrowed_data`].
+///
+/// The buffer is only borrowed for the lifetime of this type, so unlike an
+/// [`Image`] it can't be cloned or shared with patterns. It can be rendered
+/// into with a [`Context`] created by [`context`] and its pixels can be
+/// accessed directly, [`to_image`] creates an owned copy for everything else.
+///
+/// [`Image::from_borrowed_data`]: struct.Image.html#method.from_borrowed_data
+/// [`Image`]: struct.Image.html
+/// [`Context`]: ../context/struct.Context.html
+/// [`context`]: #method.context
+/// [`to_image`]: #method.to_image
+pub struct ExternalImage<'a> {
+    image: Image,
+    _pd: PhantomData<&'a mut [u8]>,
+}
+
+impl ExternalImage<'_> {
+    #[inline]
+    pub fn format(&self) -> ImageFormat {
+        self.image.format()
+    }
+
+    #[inline]
+    pub fn size(&self) -> SizeI {
+        self.image.size()
+    }
+
+    #[inline]
+    pub fn data(&self) -> ImageData<'_> {
+        self.image.data()
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> Pixels<'_> {
+        self.image.pixels()
+    }
+
+    #[inline]
+    pub fn pixels_mut(&mut self) -> PixelsMut<'_> {
+        self.image.pixels_mut()
+    }
+
+    /// Creates a synchronous [`Context`] rendering into the buffer.
+    ///
+    /// [`Context`]: ../context/struct.Context.html
+    #[inline]
+    pub fn context(&mut self) -> Result<Context<'_>> {
+        Context::new(&mut self.image)
+    }
+
+    /// Copies the pixels into a new image that owns its data.
+    #[inline]
+    pub fn to_image(&self) -> Image {
+        self.image.clone_deep()
+    }
+}
+
+impl fmt::Debug for ExternalImage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ExternalImage").field(&self.image).finish()
+    }
+}
+
 /// A struct containing information about an image.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ImageData<'a> {
@@ -471,7 +711,46 @@ pub struct ImageData<'a> {
     pub flags: ImageInfoFlags,
 }
 
+impl<'a> ImageData<'a> {
+    /// Returns the planes of the pixel data.
+    ///
+    /// All pixel formats currently supported by blend2d store their
+    /// components interleaved in a single plane, so this returns exactly one
+    /// plane spanning the whole pixel data. Consumers should nonetheless
+    /// handle multiple planes, as codecs may report more than one plane
+    /// through [`ImageInfo::plane_count`].
+    ///
+    /// [`ImageInfo::plane_count`]: struct.ImageInfo.html#structfield.plane_count
+    pub fn planes(&self) -> Vec<ImagePlane<'a>> {
+        let height = self.size.1.max(1) as usize;
+        vec![ImagePlane {
+            data: self.data,
+            stride: self.data.len() / height,
+        }]
+    }
+}
+
+/// A single plane of pixel data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImagePlane<'a> {
+    /// The pixel data of this plane.
+    pub data: &'a [u8],
+    /// The number of bytes between the starts of two rows.
+    pub stride: usize,
+}
+
+impl<'a> ImagePlane<'a> {
+    /// Returns the row at the given index, including any padding bytes at
+    /// its end.
+    #[inline]
+    pub fn row(&self, y: usize) -> Option<&'a [u8]> {
+        let start = y.checked_mul(self.stride)?;
+        self.data.get(start..start + self.stride)
+    }
+}
+
 /// Image information provided by image codecs.
+#[repr(C)]
 #[derive(Debug)]
 pub struct ImageInfo {
     /// Image size.
@@ -492,9 +771,31 @@ pub struct ImageInfo {
     compression: [u8; 16],
 }
 
+impl ImageInfo {
+    /// Creates the information of a single frame, single plane image of the
+    /// given size and depth, as reported by a [`CustomDecoder`].
+    ///
+    /// [`CustomDecoder`]: ../codec/trait.CustomDecoder.html
+    pub fn new(size: SizeI, depth: u16) -> Self {
+        ImageInfo {
+            size,
+            density: SizeD::default(),
+            flags: ImageInfoFlags::empty(),
+            depth,
+            plane_count: 1,
+            frame_count: 1,
+            format: [0; 16],
+            compression: [0; 16],
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_codec {
-    use crate::image::ImageScaleFilter;
+    use crate::codec::ImageCodec;
+    use crate::context::CompOp;
+    use crate::error::Error;
+    use crate::image::{ImageFormat, ImageScaleFilter};
     use crate::{geometry::SizeI, image::Image, DeepClone};
 
     #[test]
@@ -531,6 +832,25 @@ mod test_codec {
         assert_eq!(image, image2);
     }
 
+    #[test]
+    fn test_image_save() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        image.set_pixel(1, 2, 0xFF_12_34_56);
+        let path = std::env::temp_dir().join("blend2d-rs-test-image-save.png");
+        image.save(&path).unwrap();
+        let codecs = ImageCodec::built_in_codecs();
+        let read = Image::from_path(&path, &codecs).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(read.pixel(1, 2), Some(0xFF_12_34_56));
+
+        // blend2d can only decode JPEG images
+        let path = std::env::temp_dir().join("blend2d-rs-test-image-save.jpg");
+        assert!(matches!(
+            image.save(&path),
+            Err(Error::ImageEncoderNotProvided)
+        ));
+    }
+
     #[test]
     fn test_image_data() {
         let image = Image::new(50, 50, Default::default()).unwrap();
@@ -541,4 +861,84 @@ mod test_codec {
             50 * 50 * image_data.stride
         );
     }
+
+    #[test]
+    fn test_image_ref_count() {
+        let image = Image::new(50, 50, Default::default()).unwrap();
+        assert_eq!(image.ref_count(), 1);
+        assert!(!image.is_shared());
+        let copy = image.clone();
+        assert_eq!(image.ref_count(), 2);
+        assert!(image.is_shared() && copy.is_shared());
+        drop(copy);
+        assert!(!image.is_shared());
+    }
+
+    #[test]
+    fn test_image_make_mut() {
+        let mut image = Image::new(50, 50, Default::default()).unwrap();
+        let copy = image.clone();
+        let ptr = image.make_mut().as_ptr();
+        assert!(!image.is_shared() && !copy.is_shared());
+        assert_ne!(ptr, copy.as_ptr());
+        assert_eq!(image.make_mut().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_image_snapshot() {
+        let image = Image::new(50, 50, Default::default()).unwrap();
+        let copy = image.snapshot(1.0, ImageScaleFilter::Nearest).unwrap();
+        assert_eq!(copy.size(), image.size());
+        assert_ne!(copy.as_ptr(), image.as_ptr());
+        let thumb = image.snapshot(0.5, ImageScaleFilter::Bilinear).unwrap();
+        assert_eq!(thumb.size(), SizeI { w: 25, h: 25 });
+        for &scale in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                image.snapshot(scale, ImageScaleFilter::Nearest),
+                Err(Error::InvalidValue)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_image_from_borrowed_data() {
+        let mut pixels = vec![0u32; 4 * 4];
+        let buf = unsafe { std::slice::from_raw_parts_mut(pixels.as_mut_ptr() as *mut u8, 64) };
+        {
+            let mut image = Image::from_borrowed_data(4, 4, ImageFormat::PRgb32, buf, 16).unwrap();
+            let mut ctx = image.context().unwrap();
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.set_fill_style_rgba32(0xFF_00_FF_00);
+            ctx.fill_all().unwrap();
+            ctx.end().unwrap();
+            let copy = image.to_image();
+            assert_eq!(copy.pixel(3, 3), Some(0xFF_00_FF_00));
+            assert_ne!(copy.data().data.as_ptr(), image.data().data.as_ptr());
+        }
+        assert!(Image::from_borrowed_data(4, 4, ImageFormat::PRgb32, buf, 8).is_err());
+        assert!(Image::from_borrowed_data(8, 8, ImageFormat::PRgb32, buf, 32).is_err());
+        assert!(Image::from_borrowed_data(3, 3, ImageFormat::PRgb32, &mut buf[1..], 12).is_err());
+        assert!(pixels.iter().all(|&px| px == 0xFF_00_FF_00));
+    }
+
+    #[test]
+    fn test_image_from_tight_borrowed_data() {
+        // the last row of a tight buffer lacks the padding of the stride
+        let mut buf = [0u8; 16];
+        assert!(Image::from_borrowed_data(1, 2, ImageFormat::A8, &mut buf[..12], 8).is_err());
+        let image = Image::from_borrowed_data(1, 2, ImageFormat::A8, &mut buf, 8).unwrap();
+        assert_eq!(image.data().data.len(), 16);
+    }
+
+    #[test]
+    fn test_image_data_planes() {
+        let image = Image::new(50, 20, ImageFormat::A8).unwrap();
+        let data = image.data();
+        let planes = data.planes();
+        assert_eq!(planes.len(), 1);
+        assert!(planes[0].stride >= 50);
+        assert_eq!(planes[0].data.len(), planes[0].stride * 20);
+        assert!(planes[0].row(19).is_some());
+        assert!(planes[0].row(20).is_none());
+    }
 }
@@ -0,0 +1,80 @@
+use crate::error::{Error, Result};
+use crate::rgba::Rgba32;
+
+use super::premultiply::unpremultiply_pixel;
+use super::{Image, ImageFormat};
+
+impl Image {
+    /// Makes all pixels whose color matches the color key fully transparent,
+    /// as used by sprite sheets and screenshots that mark transparent areas
+    /// with a color like magenta.
+    ///
+    /// A pixel matches if none of its unpremultiplied color components
+    /// differs by more than `tolerance` from the key, whose alpha is ignored.
+    /// [`ImageFormat::XRgb32`] images are converted to
+    /// [`ImageFormat::PRgb32`] first. Returns the number of pixels that were
+    /// made transparent, or [`Error::InvalidValue`] for
+    /// [`ImageFormat::A8`] images, which have no color.
+    ///
+    /// [`ImageFormat::XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`ImageFormat::PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+    /// [`ImageFormat::A8`]: enum.ImageFormat.html#variant.A8
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn make_transparent(&mut self, color: Rgba32, tolerance: u8) -> Result<usize> {
+        match self.format() {
+            ImageFormat::PRgb32 => {},
+            ImageFormat::XRgb32 => self.convert(ImageFormat::PRgb32)?,
+            ImageFormat::A8 => return Err(Error::InvalidValue),
+        }
+        let key = [color.r(), color.g(), color.b()];
+        let mut count = 0;
+        self.map_prgb32(|px| {
+            if px >> 24 == 0 {
+                return px;
+            }
+            let [_, r, g, b] = unpremultiply_pixel(px).to_be_bytes();
+            let matches = [r, g, b]
+                .iter()
+                .zip(&key)
+                .all(|(&c, &k)| (i16::from(c) - i16::from(k)).abs() <= i16::from(tolerance));
+            if matches {
+                count += 1;
+                0
+            } else {
+                px
+            }
+        });
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod test_color_key {
+    use crate::image::{Image, ImageFormat};
+    use crate::rgba::Rgba32;
+
+    #[test]
+    fn test_make_transparent() {
+        let mut image = Image::new(4, 1, ImageFormat::XRgb32).unwrap();
+        image.set_pixel(0, 0, 0xFF_FF_00_FF);
+        image.set_pixel(1, 0, 0xFF_FA_04_FF);
+        image.set_pixel(2, 0, 0xFF_80_00_FF);
+        image.set_pixel(3, 0, 0xFF_00_FF_00);
+        let magenta = Rgba32::new(0xFF_FF_00_FF);
+        assert_eq!(image.make_transparent(magenta, 8).unwrap(), 2);
+        assert_eq!(image.format(), ImageFormat::PRgb32);
+        assert_eq!(image.pixel(0, 0), Some(0));
+        assert_eq!(image.pixel(1, 0), Some(0));
+        assert_eq!(image.pixel(2, 0), Some(0xFF_80_00_FF));
+        assert_eq!(image.pixel(3, 0), Some(0xFF_00_FF_00));
+
+        // premultiplied pixels are compared by their unpremultiplied color
+        let mut image = Image::new(1, 1, ImageFormat::PRgb32).unwrap();
+        image.set_pixel(0, 0, 0x80_80_00_80);
+        assert_eq!(image.make_transparent(magenta, 0).unwrap(), 1);
+        assert_eq!(image.pixel(0, 0), Some(0));
+
+        let mut mask = Image::new(1, 1, ImageFormat::A8).unwrap();
+        assert!(mask.make_transparent(magenta, 0).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use super::{Image, ImageFormat, ImageScaleFilter};
+use crate::error::{Error, Result};
+
+/// Size of the square windows the similarity index is computed on.
+const SIMILARITY_WINDOW: usize = 8;
+
+/// Tolerances used when comparing two images with [`Image::compare`] and
+/// [`Image::approx_eq`].
+///
+/// The default options require both images to be exactly equal.
+///
+/// [`Image::compare`]: struct.Image.html#method.compare
+/// [`Image::approx_eq`]: struct.Image.html#method.approx_eq
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CompareOptions {
+    /// The largest difference of a single channel for which two pixels are
+    /// still considered equal.
+    pub max_channel_delta: u8,
+    /// The fraction of pixels in `0.0..=1.0` that may differ by more than
+    /// `max_channel_delta`.
+    pub max_differing_pixels: f64,
+    /// A factor both images are scaled by with a bilinear filter before they
+    /// are compared, which evens out small anti-aliasing differences.
+    pub downscale: Option<f64>,
+    /// The minimum structural similarity in `0.0..=1.0` both images need to
+    /// have.
+    pub min_similarity: Option<f64>,
+}
+
+impl Default for CompareOptions {
+    #[inline]
+    fn default() -> Self {
+        CompareOptions {
+            max_channel_delta: 0,
+            max_differing_pixels: 0.0,
+            downscale: None,
+            min_similarity: None,
+        }
+    }
+}
+
+impl CompareOptions {
+    /// Options that accept small anti-aliasing differences, like the ones
+    /// between the different SIMD pipelines of blend2d.
+    pub fn anti_aliasing() -> Self {
+        CompareOptions {
+            max_channel_delta: 2,
+            max_differing_pixels: 0.001,
+            ..Default::default()
+        }
+    }
+}
+
+/// The result of comparing two images with [`Image::compare`].
+///
+/// [`Image::compare`]: struct.Image.html#method.compare
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImageDiff {
+    /// The largest difference of a single channel between two pixels.
+    pub max_channel_delta: u8,
+    /// The number of pixels differing by more than the allowed channel delta.
+    pub differing_pixels: usize,
+    /// The number of compared pixels.
+    pub pixel_count: usize,
+    /// A simplified structural similarity index of both images in
+    /// `0.0..=1.0`, computed on the luminance of non-overlapping windows.
+    pub similarity: f64,
+}
+
+impl ImageDiff {
+    /// The fraction of differing pixels in `0.0..=1.0`.
+    #[inline]
+    pub fn differing_ratio(&self) -> f64 {
+        if self.pixel_count == 0 {
+            0.0
+        } else {
+            self.differing_pixels as f64 / self.pixel_count as f64
+        }
+    }
+
+    /// Returns true if this difference lies within the tolerances of the
+    /// given options.
+    pub fn is_within(&self, options: &CompareOptions) -> bool {
+        self.differing_ratio() <= options.max_differing_pixels
+            && options
+                .min_similarity
+                .map_or(true, |min| self.similarity >= min)
+    }
+}
+
+impl Image {
+    /// Compares this image with another image of the same size using the
+    /// given tolerances.
+    ///
+    /// Pixels are compared as premultiplied 32-bit pixels, so images of
+    /// different formats can be compared with each other. Returns
+    /// [`Error::InvalidValue`] if the sizes of both images differ.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn compare(&self, other: &Image, options: &CompareOptions) -> Result<ImageDiff> {
+        if self.size() != other.size() {
+            return Err(Error::InvalidValue);
+        }
+        match options.downscale {
+            Some(scale) if scale != 1.0 => {
+                let a = self.snapshot(scale, ImageScaleFilter::Bilinear)?;
+                let b = other.snapshot(scale, ImageScaleFilter::Bilinear)?;
+                Ok(compare_pixels(&a, &b, options.max_channel_delta))
+            },
+            _ => Ok(compare_pixels(self, other, options.max_channel_delta)),
+        }
+    }
+
+    /// Returns true if this image equals the other image within the
+    /// tolerances of the given options.
+    ///
+    /// Images of different sizes are never equal.
+    pub fn approx_eq(&self, other: &Image, options: &CompareOptions) -> bool {
+        self.compare(other, options)
+            .map_or(false, |diff| diff.is_within(options))
+    }
+}
+
+fn compare_pixels(a: &Image, b: &Image, tolerance: u8) -> ImageDiff {
+    let (width, height) = (a.width() as usize, a.height() as usize);
+    let (pixels_a, pixels_b) = (premultiplied_pixels(a), premultiplied_pixels(b));
+    let mut max_channel_delta = 0;
+    let mut differing_pixels = 0;
+    for (&pa, &pb) in pixels_a.iter().zip(&pixels_b) {
+        let delta = pa
+            .to_be_bytes()
+            .iter()
+            .zip(&pb.to_be_bytes())
+            .map(|(&ca, &cb)| (i16::from(ca) - i16::from(cb)).abs() as u8)
+            .max()
+            .unwrap_or(0);
+        max_channel_delta = max_channel_delta.max(delta);
+        if delta > tolerance {
+            differing_pixels += 1;
+        }
+    }
+    ImageDiff {
+        max_channel_delta,
+        differing_pixels,
+        pixel_count: width * height,
+        similarity: similarity(&pixels_a, &pixels_b, width, height),
+    }
+}
+
+/// Reads all pixels of the image as premultiplied `0xAARRGGBB` values.
+fn premultiplied_pixels(image: &Image) -> Vec<u32> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    if width == 0 || height == 0 || image.is_empty() {
+        return Vec::new();
+    }
+    let format = image.format();
+    let stride = image.len() / height;
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in image.chunks(stride) {
+        for x in 0..width {
+            pixels.push(match format {
+                ImageFormat::PRgb32 | ImageFormat::XRgb32 => {
+                    let px = &row[x * 4..x * 4 + 4];
+                    let px = u32::from_ne_bytes([px[0], px[1], px[2], px[3]]);
+                    if format == ImageFormat::XRgb32 {
+                        px | 0xFF00_0000
+                    } else {
+                        px
+                    }
+                },
+                ImageFormat::A8 => u32::from(row[x]) << 24,
+            });
+        }
+    }
+    pixels
+}
+
+/// Luminance of a premultiplied pixel, with alpha acting as an additional
+/// weight so that masks compare meaningfully.
+#[inline]
+fn luma(px: u32) -> f64 {
+    let [a, r, g, b] = px.to_be_bytes();
+    0.25 * f64::from(a)
+        + 0.75 * (0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b))
+}
+
+/// Computes the mean structural similarity index over non-overlapping
+/// windows.
+fn similarity(a: &[u32], b: &[u32], width: usize, height: usize) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    let mut total = 0.0;
+    let mut windows = 0;
+    for wy in (0..height).step_by(SIMILARITY_WINDOW) {
+        for wx in (0..width).step_by(SIMILARITY_WINDOW) {
+            let coords = (wy..(wy + SIMILARITY_WINDOW).min(height)).flat_map(|y| {
+                (wx..(wx + SIMILARITY_WINDOW).min(width)).map(move |x| y * width + x)
+            });
+            let samples: Vec<(f64, f64)> = coords.map(|i| (luma(a[i]), luma(b[i]))).collect();
+            let n = samples.len() as f64;
+            let mean_a = samples.iter().map(|s| s.0).sum::<f64>() / n;
+            let mean_b = samples.iter().map(|s| s.1).sum::<f64>() / n;
+            let (mut var_a, mut var_b, mut covar) = (0.0, 0.0, 0.0);
+            for &(la, lb) in &samples {
+                var_a += (la - mean_a) * (la - mean_a);
+                var_b += (lb - mean_b) * (lb - mean_b);
+                covar += (la - mean_a) * (lb - mean_b);
+            }
+            let (var_a, var_b, covar) = (var_a / n, var_b / n, covar / n);
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covar + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+    if windows == 0 {
+        1.0
+    } else {
+        total / f64::from(windows)
+    }
+}
+
+#[cfg(test)]
+mod test_compare {
+    use crate::context::{CompOp, Context};
+    use crate::image::{CompareOptions, Image, ImageFormat};
+
+    fn solid(color: u32) -> Image {
+        let mut image = Image::new(16, 16, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_style_rgba32(color);
+        ctx.fill_all().unwrap();
+        ctx.end().unwrap();
+        image
+    }
+
+    #[test]
+    fn test_compare_tolerance() {
+        let a = solid(0xFF_80_80_80);
+        let b = solid(0xFF_81_80_7F);
+        assert!(!a.approx_eq(&b, &CompareOptions::default()));
+        let options = CompareOptions {
+            max_channel_delta: 1,
+            ..Default::default()
+        };
+        assert!(a.approx_eq(&b, &options));
+        let diff = a.compare(&b, &CompareOptions::default()).unwrap();
+        assert_eq!(diff.max_channel_delta, 1);
+        assert_eq!(diff.differing_pixels, 256);
+        assert!(diff.similarity > 0.99);
+    }
+
+    #[test]
+    fn test_compare_size_mismatch() {
+        let a = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let b = Image::new(4, 5, ImageFormat::PRgb32).unwrap();
+        assert!(a.compare(&b, &CompareOptions::default()).is_err());
+        assert!(!a.approx_eq(&b, &CompareOptions::default()));
+    }
+
+    #[test]
+    fn test_compare_empty() {
+        let diff = Image::default()
+            .compare(&Image::default(), &CompareOptions::default())
+            .unwrap();
+        assert_eq!(diff.pixel_count, 0);
+        assert_eq!(diff.similarity, 1.0);
+    }
+}
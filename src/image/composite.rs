@@ -0,0 +1,118 @@
+use crate::context::{CompOp, Context};
+use crate::error::{Error, Result};
+use crate::geometry::{PointI, RectI};
+use crate::rgba::Rgba32;
+
+use super::Image;
+
+impl Image {
+    /// Composites `src` onto this image at the origin, using the given
+    /// composition operator and opacity.
+    ///
+    /// This is a shorthand for creating a [`Context`] on this image and
+    /// blitting `src` with it, useful for flattening layers.
+    ///
+    /// [`Context`]: ../context/struct.Context.html
+    pub fn composite(&mut self, src: &Image, comp_op: CompOp, opacity: f64) -> Result<()> {
+        self.composite_at(PointI::ZERO, src, None, comp_op, opacity)
+    }
+
+    /// Composites the given area of `src`, or all of it if `src_area` is
+    /// `None`, onto this image at `dst`, using the given composition operator
+    /// and opacity.
+    ///
+    /// Returns [`Error::InvalidValue`] if `opacity` is NaN.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn composite_at<'r, RI>(
+        &mut self,
+        dst: PointI,
+        src: &Image,
+        src_area: RI,
+        comp_op: CompOp,
+        opacity: f64,
+    ) -> Result<()>
+    where
+        RI: Into<Option<&'r RectI>>,
+    {
+        if opacity.is_nan() {
+            return Err(Error::InvalidValue);
+        }
+        let mut ctx = Context::new(self)?;
+        ctx.set_comp_op(comp_op);
+        ctx.set_global_alpha(opacity.max(0.0).min(1.0));
+        ctx.blit_image(&dst, src, src_area)?;
+        ctx.end()
+    }
+
+    /// Replaces all pixels of this image with the given color.
+    pub fn fill<C: Into<Rgba32>>(&mut self, color: C) -> Result<()> {
+        let rect = RectI {
+            x: 0,
+            y: 0,
+            w: self.width(),
+            h: self.height(),
+        };
+        self.fill_rect(&rect, color)
+    }
+
+    /// Replaces the pixels in the given area of this image with the given
+    /// color.
+    pub fn fill_rect<C: Into<Rgba32>>(&mut self, rect: &RectI, color: C) -> Result<()> {
+        let mut ctx = Context::new(self)?;
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_style_rgba32(color);
+        ctx.fill_geometry(rect)?;
+        ctx.end()
+    }
+}
+
+#[cfg(test)]
+mod test_composite {
+    use crate::context::CompOp;
+    use crate::error::Error;
+    use crate::geometry::{PointI, RectI};
+    use crate::image::{Image, ImageFormat};
+    use crate::rgba::Rgba32;
+
+    fn filled(w: i32, h: i32, color: u32) -> Image {
+        let mut image = Image::new(w, h, ImageFormat::PRgb32).unwrap();
+        for y in 0..h as usize {
+            for x in 0..w as usize {
+                image.set_pixel(x, y, color);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_composite() {
+        let mut dst = filled(2, 2, 0);
+        let src = filled(2, 2, 0xFF_FF_00_00);
+        dst.composite(&src, CompOp::SrcOver, 1.0).unwrap();
+        assert_eq!(dst.pixel(1, 1), Some(0xFF_FF_00_00));
+
+        let mut dst = filled(2, 2, 0);
+        let area = RectI::new(0, 0, 1, 1);
+        dst.composite_at(PointI::new(1, 0), &src, &area, CompOp::SrcCopy, 1.0).unwrap();
+        assert_eq!(dst.pixel(0, 0), Some(0));
+        assert_eq!(dst.pixel(1, 0), Some(0xFF_FF_00_00));
+        assert_eq!(dst.pixel(1, 1), Some(0));
+
+        assert!(matches!(
+            dst.composite(&src, CompOp::SrcOver, f64::NAN),
+            Err(Error::InvalidValue)
+        ));
+        assert_eq!(dst.pixel(0, 0), Some(0));
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        image.fill(Rgba32::from_rgba(0xFF, 0x00, 0x00, 0x80)).unwrap();
+        let rect = RectI { x: 2, y: 2, w: 2, h: 2 };
+        image.fill_rect(&rect, Rgba32::WHITE).unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0x80_80_00_00));
+        assert_eq!(image.pixel(3, 3), Some(0xFF_FF_FF_FF));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::error::{Error, Result};
+use crate::ExtendMode;
+
+use super::{Image, ImageFormat, Pixels, PixelsMut};
+
+/// A square convolution kernel for [`Image::convolve`].
+///
+/// The weights are stored row by row, the center weight applies to the pixel
+/// being computed. The bias is added to every resulting channel in the range
+/// of `0.0..=255.0`.
+///
+/// [`Image::convolve`]: struct.Image.html#method.convolve
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    size: usize,
+    weights: Vec<f64>,
+    bias: f64,
+    preserve_alpha: bool,
+}
+
+impl Kernel {
+    /// Creates a kernel with `size` x `size` weights.
+    ///
+    /// Returns [`Error::InvalidValue`] if the size isn't odd or the number of
+    /// weights doesn't match it.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn new(size: usize, weights: &[f64]) -> Result<Kernel> {
+        if size % 2 == 0 || weights.len() != size * size {
+            return Err(Error::InvalidValue);
+        }
+        Ok(Kernel {
+            size,
+            weights: weights.to_vec(),
+            bias: 0.0,
+            preserve_alpha: false,
+        })
+    }
+
+    /// A 3x3 kernel that leaves the image unchanged.
+    pub fn identity() -> Kernel {
+        Self::from_3x3([0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0])
+    }
+
+    /// A 3x3 kernel that sharpens the image.
+    pub fn sharpen() -> Kernel {
+        Self::from_3x3([0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0])
+    }
+
+    /// A 3x3 laplacian kernel that turns edges white and everything else
+    /// black, preserving the alpha of the image.
+    pub fn edge_detect() -> Kernel {
+        Self::from_3x3([-1.0, -1.0, -1.0, -1.0, 8.0, -1.0, -1.0, -1.0, -1.0])
+            .with_preserve_alpha(true)
+    }
+
+    /// A 3x3 kernel that embosses the image from the top left, preserving its
+    /// alpha.
+    pub fn emboss() -> Kernel {
+        Self::from_3x3([-2.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0, 2.0]).with_preserve_alpha(true)
+    }
+
+    /// A 3x3 kernel that averages each pixel with its neighbours.
+    pub fn box_blur() -> Kernel {
+        Self::from_3x3([1.0; 9]).normalized()
+    }
+
+    /// A 3x3 binomial approximation of a gaussian blur.
+    pub fn gaussian_3x3() -> Kernel {
+        Self::from_3x3([1.0, 2.0, 1.0, 2.0, 4.0, 2.0, 1.0, 2.0, 1.0]).normalized()
+    }
+
+    /// A 5x5 binomial approximation of a gaussian blur.
+    pub fn gaussian_5x5() -> Kernel {
+        let row = [1.0, 4.0, 6.0, 4.0, 1.0];
+        let weights = row
+            .iter()
+            .flat_map(|y| row.iter().map(move |x| x * y))
+            .collect::<Vec<_>>();
+        Kernel {
+            size: 5,
+            weights,
+            bias: 0.0,
+            preserve_alpha: false,
+        }
+        .normalized()
+    }
+
+    fn from_3x3(weights: [f64; 9]) -> Kernel {
+        Kernel {
+            size: 3,
+            weights: weights.to_vec(),
+            bias: 0.0,
+            preserve_alpha: false,
+        }
+    }
+
+    /// Returns this kernel with the weights divided by their sum, so that the
+    /// brightness of the image is kept. Kernels whose weights sum up to zero
+    /// are returned unchanged.
+    pub fn normalized(mut self) -> Kernel {
+        let sum = self.weights.iter().sum::<f64>();
+        if sum != 0.0 {
+            self.weights.iter_mut().for_each(|w| *w /= sum);
+        }
+        self
+    }
+
+    /// Returns this kernel with the given bias.
+    #[inline]
+    pub fn with_bias(mut self, bias: f64) -> Kernel {
+        self.bias = bias;
+        self
+    }
+
+    /// Returns this kernel with the alpha of the image either kept as is or
+    /// convolved like the color channels.
+    #[inline]
+    pub fn with_preserve_alpha(mut self, preserve_alpha: bool) -> Kernel {
+        self.preserve_alpha = preserve_alpha;
+        self
+    }
+
+    /// The number of weights per row and column.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    #[inline]
+    pub fn bias(&self) -> f64 {
+        self.bias
+    }
+
+    #[inline]
+    pub fn preserve_alpha(&self) -> bool {
+        self.preserve_alpha
+    }
+}
+
+/// How the coordinates outside of the image are mapped into it along one
+/// axis.
+#[derive(Copy, Clone)]
+enum Edge {
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+impl Edge {
+    fn from_extend_mode(mode: ExtendMode) -> (Edge, Edge) {
+        use self::Edge::*;
+        match mode {
+            ExtendMode::PadXPadY => (Pad, Pad),
+            ExtendMode::RepeatXRepeatY => (Repeat, Repeat),
+            ExtendMode::ReflectXReflectY => (Reflect, Reflect),
+            ExtendMode::PadXRepeatY => (Pad, Repeat),
+            ExtendMode::PadXReflectY => (Pad, Reflect),
+            ExtendMode::RepeatXPadY => (Repeat, Pad),
+            ExtendMode::RepeatXReflectY => (Repeat, Reflect),
+            ExtendMode::ReflectXPadY => (Reflect, Pad),
+            ExtendMode::ReflectXRepeatY => (Reflect, Repeat),
+        }
+    }
+
+    fn resolve(self, i: isize, len: usize) -> usize {
+        let len = len as isize;
+        match self {
+            Edge::Pad => i.max(0).min(len - 1) as usize,
+            Edge::Repeat => i.rem_euclid(len) as usize,
+            // mirrors at the edges, repeating the edge pixels like patterns do
+            Edge::Reflect => {
+                let i = i.rem_euclid(2 * len);
+                (if i < len { i } else { 2 * len - 1 - i }) as usize
+            },
+        }
+    }
+}
+
+impl Image {
+    /// Convolves this image with the given kernel in place.
+    ///
+    /// The channels are convolved as stored, that is premultiplied for
+    /// [`ImageFormat::PRgb32`], and the color channels are clamped to the
+    /// alpha of the result afterwards. [`ImageFormat::XRgb32`] pixels stay
+    /// opaque and [`ImageFormat::A8`] images only have their alpha convolved.
+    /// Pixels outside of the image are taken from inside of it according to
+    /// the `extend_mode`.
+    ///
+    /// [`ImageFormat::PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+    /// [`ImageFormat::XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`ImageFormat::A8`]: enum.ImageFormat.html#variant.A8
+    pub fn convolve(&mut self, kernel: &Kernel, extend_mode: ExtendMode) {
+        let (w, h) = (self.width() as usize, self.height() as usize);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let format = self.format();
+        let source = match self.pixels() {
+            Pixels::Rgb32(rows) => rows.rows().flatten().copied().collect::<Vec<_>>(),
+            Pixels::A8(rows) => rows.rows().flatten().map(|&a| u32::from(a) << 24).collect(),
+        };
+        let (edge_x, edge_y) = Edge::from_extend_mode(extend_mode);
+        let radius = (kernel.size / 2) as isize;
+        let channel = |value: f64| value.round().max(0.0).min(255.0) as u32;
+
+        let mut result = Vec::with_capacity(source.len());
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = [kernel.bias; 4];
+                for (i, &weight) in kernel.weights.iter().enumerate() {
+                    if weight == 0.0 {
+                        continue;
+                    }
+                    let kx = (i % kernel.size) as isize - radius;
+                    let ky = (i / kernel.size) as isize - radius;
+                    let sx = edge_x.resolve(x as isize + kx, w);
+                    let sy = edge_y.resolve(y as isize + ky, h);
+                    let px = source[sy * w + sx].to_be_bytes();
+                    for (sum, &c) in sum.iter_mut().zip(&px) {
+                        *sum += weight * f64::from(c);
+                    }
+                }
+                let alpha = match format {
+                    ImageFormat::XRgb32 => 0xFF,
+                    _ if kernel.preserve_alpha => source[y * w + x] >> 24,
+                    _ => channel(sum[0]),
+                };
+                let color = |sum: f64| match format {
+                    ImageFormat::PRgb32 => channel(sum).min(alpha),
+                    _ => channel(sum),
+                };
+                result.push(alpha << 24 | color(sum[1]) << 16 | color(sum[2]) << 8 | color(sum[3]));
+            }
+        }
+
+        match self.pixels_mut() {
+            PixelsMut::Rgb32(mut rows) => {
+                for (row, pixels) in rows.rows_mut().zip(result.chunks(w)) {
+                    row.copy_from_slice(pixels);
+                }
+            },
+            PixelsMut::A8(mut rows) => {
+                for (row, pixels) in rows.rows_mut().zip(result.chunks(w)) {
+                    for (px, &value) in row.iter_mut().zip(pixels) {
+                        *px = (value >> 24) as u8;
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_convolve {
+    use crate::image::{Image, ImageFormat, Kernel};
+    use crate::ExtendMode;
+
+    fn checker() -> Image {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        for y in 0..4 {
+            for x in 0..4 {
+                let px = if x < 2 { 0xFF_00_00_00 } else { 0xFF_FF_FF_FF };
+                image.set_pixel(x, y, px);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_kernel_new() {
+        assert!(Kernel::new(3, &[0.0; 9]).is_ok());
+        assert!(Kernel::new(4, &[0.0; 16]).is_err());
+        assert!(Kernel::new(3, &[0.0; 8]).is_err());
+        assert_eq!(Kernel::gaussian_5x5().weights().iter().sum::<f64>(), 1.0);
+    }
+
+    #[test]
+    fn test_convolve() {
+        let original = checker();
+        let mut image = original.clone();
+        image.convolve(&Kernel::identity(), ExtendMode::PadXPadY);
+        assert_eq!(image, original);
+
+        let mut image = original.clone();
+        image.convolve(&Kernel::box_blur(), ExtendMode::PadXPadY);
+        assert_eq!(image.pixel(0, 0), Some(0xFF_00_00_00));
+        assert_eq!(image.pixel(1, 1), Some(0xFF_55_55_55));
+        assert_eq!(image.pixel(2, 1), Some(0xFF_AA_AA_AA));
+        assert_eq!(image.pixel(3, 3), Some(0xFF_FF_FF_FF));
+
+        // repeating wraps the white right edge around to the left
+        let mut image = original.clone();
+        image.convolve(&Kernel::box_blur(), ExtendMode::RepeatXRepeatY);
+        assert_eq!(image.pixel(0, 0), Some(0xFF_55_55_55));
+
+        let mut image = original;
+        image.convolve(&Kernel::edge_detect(), ExtendMode::PadXPadY);
+        assert_eq!(image.pixel(0, 0), Some(0xFF_00_00_00));
+        assert_eq!(image.pixel(2, 2), Some(0xFF_FF_FF_FF));
+    }
+
+    #[test]
+    fn test_convolve_a8() {
+        let mut image = Image::new(3, 1, ImageFormat::A8).unwrap();
+        image.set_pixel(1, 0, 0xFF << 24);
+        let kernel = Kernel::new(3, &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0])
+            .unwrap()
+            .normalized();
+        image.convolve(&kernel, ExtendMode::PadXPadY);
+        assert_eq!(image.pixel(0, 0), Some(0x55 << 24));
+        assert_eq!(image.pixel(2, 0), Some(0x55 << 24));
+    }
+}
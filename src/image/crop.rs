@@ -0,0 +1,81 @@
+use crate::error::{Error, Result};
+use crate::geometry::RectI;
+
+use super::Image;
+
+impl Image {
+    /// Returns a copy of the given area of this image in the same format.
+    ///
+    /// The pixel data is copied row by row, so no rendering context is
+    /// involved and the pixels are copied unchanged. Returns
+    /// [`Error::InvalidValue`] if the area is empty or not contained in this
+    /// image.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn cropped(&self, rect: &RectI) -> Result<Image> {
+        let (x, y, w, h) = (
+            i64::from(rect.x),
+            i64::from(rect.y),
+            i64::from(rect.w),
+            i64::from(rect.h),
+        );
+        if w <= 0
+            || h <= 0
+            || x < 0
+            || y < 0
+            || x + w > i64::from(self.width())
+            || y + h > i64::from(self.height())
+        {
+            return Err(Error::InvalidValue);
+        }
+        let mut cropped = Image::new(rect.w, rect.h, self.format())?;
+        let bpp = self.format().bytes_per_pixel();
+        let (src_stride, _) = self.row_layout();
+        let (dst_stride, _) = cropped.row_layout();
+        let row_len = rect.w as usize * bpp;
+        let src: &[u8] = self;
+        let dst: &mut [u8] = &mut cropped;
+        for row in 0..rect.h as usize {
+            let src_start = (rect.y as usize + row) * src_stride + rect.x as usize * bpp;
+            let dst_start = row * dst_stride;
+            dst[dst_start..dst_start + row_len]
+                .copy_from_slice(&src[src_start..src_start + row_len]);
+        }
+        Ok(cropped)
+    }
+
+    /// Crops this image to the given area, see [`cropped`].
+    ///
+    /// The cropped pixels are stored in a new buffer, other images sharing
+    /// the pixel data of this image are unaffected.
+    ///
+    /// [`cropped`]: #method.cropped
+    pub fn crop_in_place(&mut self, rect: &RectI) -> Result<()> {
+        *self = self.cropped(rect)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_crop {
+    use crate::geometry::{RectI, SizeI};
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_cropped() {
+        let mut image = Image::new(8, 8, ImageFormat::A8).unwrap();
+        image.fill(0).unwrap();
+        assert!(image.set_pixel(3, 4, 0xFF00_0000));
+        let cropped = image.cropped(&RectI::new(2, 3, 4, 2)).unwrap();
+        assert_eq!(cropped.size(), SizeI::new(4, 2));
+        assert_eq!(cropped.pixel(1, 1), Some(0xFF00_0000));
+        assert_eq!(cropped.pixel(0, 0), Some(0));
+
+        assert!(image.cropped(&RectI::new(6, 6, 4, 4)).is_err());
+        assert!(image.cropped(&RectI::new(0, 0, 0, 4)).is_err());
+
+        image.crop_in_place(&RectI::new(3, 4, 1, 1)).unwrap();
+        assert_eq!(image.size(), SizeI::new(1, 1));
+        assert_eq!(image.pixel(0, 0), Some(0xFF00_0000));
+    }
+}
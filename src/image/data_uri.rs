@@ -0,0 +1,79 @@
+use crate::array::Array;
+use crate::codec::ImageCodec;
+use crate::error::{Error, Result};
+
+use super::Image;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl Image {
+    /// Encodes the image as PNG and returns it as a base64 encoded
+    /// `data:image/png;base64,...` URI.
+    ///
+    /// This allows embedding rendered images in HTML reports or debugging
+    /// output without writing them to the filesystem. Returns
+    /// [`Error::ImageNoMatchingCodec`] if the built-in PNG codec is not
+    /// available.
+    ///
+    /// [`Error::ImageNoMatchingCodec`]: ../error/enum.Error.html#variant.ImageNoMatchingCodec
+    pub fn to_png_data_uri(&self) -> Result<String> {
+        let codecs = ImageCodec::built_in_codecs();
+        let codec = codecs
+            .find_codec_by_name("PNG")
+            .ok_or(Error::ImageNoMatchingCodec)?;
+        let mut data = Array::<u8>::new();
+        self.write_to_data(&mut data, codec)?;
+        let mut uri = String::from("data:image/png;base64,");
+        encode_base64(&data, &mut uri);
+        Ok(uri)
+    }
+}
+
+/// Appends the padded standard base64 encoding of `data` to `out`.
+fn encode_base64(data: &[u8], out: &mut String) {
+    out.reserve((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_data_uri {
+    use super::encode_base64;
+    use crate::image::Image;
+
+    #[test]
+    fn test_encode_base64() {
+        let encode = |data: &[u8]| {
+            let mut out = String::new();
+            encode_base64(data, &mut out);
+            out
+        };
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_png_data_uri() {
+        let image = Image::new(8, 8, Default::default()).unwrap();
+        let uri = image.to_png_data_uri().unwrap();
+        // the base64 encoded PNG signature
+        assert!(uri.starts_with("data:image/png;base64,iVBORw0KGgo"));
+    }
+}
@@ -0,0 +1,140 @@
+//! Conversions between [`Image`] and the buffers of the [`image`] crate.
+//!
+//! The pixel layouts of both crates differ, blend2d stores premultiplied
+//! `0xAARRGGBB` values in padded rows while the [`image`] crate stores
+//! straight RGBA bytes, so every conversion copies the pixels.
+//!
+//! [`image`]: https://docs.rs/image
+use std::convert::TryFrom;
+use std::ops::Deref;
+
+use ::image::{ImageBuffer, Rgba, RgbaImage};
+
+use super::premultiply::{premultiply_pixel, unpremultiply_pixel};
+use super::{Image, ImageFormat};
+use crate::error::{Error, Result};
+
+impl Image {
+    /// Converts this image into an [`RgbaImage`] with straight alpha.
+    ///
+    /// [`ImageFormat::PRgb32`] pixels are unpremultiplied,
+    /// [`ImageFormat::XRgb32`] pixels become fully opaque and
+    /// [`ImageFormat::A8`] pixels become black with the alpha value of the
+    /// source pixel.
+    ///
+    /// [`RgbaImage`]: https://docs.rs/image/*/image/type.RgbaImage.html
+    pub fn to_rgba_image(&self) -> RgbaImage {
+        let (width, height) = (self.width() as u32, self.height() as u32);
+        let mut out = RgbaImage::new(width, height);
+        if width == 0 || height == 0 || self.is_empty() {
+            return out;
+        }
+        let format = self.format();
+        let stride = self.len() / height as usize;
+        for (y, row) in self.chunks(stride).enumerate() {
+            for x in 0..width {
+                let rgba = match format {
+                    ImageFormat::PRgb32 => unpremultiply(read_pixel(row, x as usize)),
+                    ImageFormat::XRgb32 => {
+                        let [_, r, g, b] = read_pixel(row, x as usize).to_be_bytes();
+                        [r, g, b, 0xFF]
+                    },
+                    ImageFormat::A8 => [0, 0, 0, row[x as usize]],
+                };
+                out.put_pixel(x, y as u32, Rgba(rgba));
+            }
+        }
+        out
+    }
+
+    /// Creates a [`ImageFormat::PRgb32`] image from an [`ImageBuffer`] with
+    /// straight alpha, premultiplying its pixels.
+    ///
+    /// [`ImageBuffer`]: https://docs.rs/image/*/image/struct.ImageBuffer.html
+    pub fn from_rgba_image<C>(buffer: &ImageBuffer<Rgba<u8>, C>) -> Result<Image>
+    where
+        C: Deref<Target = [u8]>,
+    {
+        let (width, height) = buffer.dimensions();
+        if width > i32::max_value() as u32 || height > i32::max_value() as u32 {
+            return Err(Error::ImageTooLarge);
+        }
+        if width == 0 || height == 0 {
+            return Ok(Image::default());
+        }
+        let mut image = Image::new(width as i32, height as i32, ImageFormat::PRgb32)?;
+        let stride = image.len() / height as usize;
+        for (y, row) in image.chunks_mut(stride).enumerate() {
+            for x in 0..width {
+                let px = premultiply(buffer.get_pixel(x, y as u32).0);
+                let offset = x as usize * 4;
+                row[offset..offset + 4].copy_from_slice(&px.to_ne_bytes());
+            }
+        }
+        Ok(image)
+    }
+}
+
+impl From<&'_ Image> for RgbaImage {
+    #[inline]
+    fn from(image: &Image) -> Self {
+        image.to_rgba_image()
+    }
+}
+
+impl<C> TryFrom<&'_ ImageBuffer<Rgba<u8>, C>> for Image
+where
+    C: Deref<Target = [u8]>,
+{
+    type Error = Error;
+
+    #[inline]
+    fn try_from(buffer: &ImageBuffer<Rgba<u8>, C>) -> Result<Self> {
+        Image::from_rgba_image(buffer)
+    }
+}
+
+#[inline]
+fn read_pixel(row: &[u8], x: usize) -> u32 {
+    let offset = x * 4;
+    u32::from_ne_bytes([row[offset], row[offset + 1], row[offset + 2], row[offset + 3]])
+}
+
+fn unpremultiply(px: u32) -> [u8; 4] {
+    let [a, r, g, b] = unpremultiply_pixel(px).to_be_bytes();
+    [r, g, b, a]
+}
+
+fn premultiply([r, g, b, a]: [u8; 4]) -> u32 {
+    premultiply_pixel(u32::from_be_bytes([a, r, g, b]))
+}
+
+#[cfg(test)]
+mod test_image_crate {
+    use ::image::{Rgba, RgbaImage};
+
+    use crate::image::Image;
+
+    #[test]
+    fn test_rgba_image_roundtrip() {
+        let mut buffer = RgbaImage::new(3, 2);
+        buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        buffer.put_pixel(1, 0, Rgba([0, 255, 0, 0]));
+        buffer.put_pixel(2, 1, Rgba([10, 20, 30, 128]));
+        let image = Image::from_rgba_image(&buffer).unwrap();
+        let back = image.to_rgba_image();
+        assert_eq!(back.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        // fully transparent pixels lose their color when premultiplied
+        assert_eq!(back.get_pixel(1, 0), &Rgba([0, 0, 0, 0]));
+        let px = back.get_pixel(2, 1).0;
+        assert_eq!(px[3], 128);
+        assert!((i32::from(px[2]) - 30).abs() <= 1);
+    }
+
+    #[test]
+    fn test_empty_rgba_image() {
+        let image = Image::from_rgba_image(&RgbaImage::new(0, 0)).unwrap();
+        assert!(image.is_empty());
+        assert_eq!(image.to_rgba_image().dimensions(), (0, 0));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::{Error, Result};
+use crate::geometry::SizeI;
+
+use super::{Image, ImageFormat, Pixels, PixelsMut};
+
+impl Image {
+    /// Returns a copy of this image downscaled to the given size in linear
+    /// light.
+    ///
+    /// [`scale`] filters the sRGB encoded pixels directly, which darkens
+    /// fine high contrast details like text or thin lines when shrinking an
+    /// image. This converts the pixels to linear light first, averages the
+    /// source pixels covered by each target pixel weighted by their area and
+    /// converts the result back to sRGB, which makes for accurate thumbnails.
+    /// The alpha channel and [`ImageFormat::A8`] images are averaged as is,
+    /// as alpha is linear already.
+    ///
+    /// Returns [`Error::InvalidValue`] if the size is empty or larger than
+    /// the size of this image in either direction.
+    ///
+    /// [`scale`]: #method.scale
+    /// [`ImageFormat::A8`]: enum.ImageFormat.html#variant.A8
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn downscaled_linear(&self, size: SizeI) -> Result<Image> {
+        let (src_w, src_h) = (self.width(), self.height());
+        if size.w <= 0 || size.h <= 0 || size.w > src_w || size.h > src_h {
+            return Err(Error::InvalidValue);
+        }
+        let (src_w, src_h) = (src_w as usize, src_h as usize);
+        let (dst_w, dst_h) = (size.w as usize, size.h as usize);
+        let format = self.format();
+        let to_linear = srgb_to_linear_table();
+
+        // premultiplied linear [r, g, b, a] values in 0.0..=1.0
+        let linear = match self.pixels() {
+            Pixels::Rgb32(rows) => rows
+                .rows()
+                .flatten()
+                .map(|&px| {
+                    let [a, r, g, b] = px.to_be_bytes();
+                    let a = match format {
+                        ImageFormat::XRgb32 => 1.0,
+                        _ => f32::from(a) / 255.0,
+                    };
+                    if a == 0.0 {
+                        return [0.0; 4];
+                    }
+                    // the stored components are premultiplied in sRGB
+                    let color = |c: u8| to_linear[unpremultiply(c, a)] * a;
+                    [color(r), color(g), color(b), a]
+                })
+                .collect::<Vec<_>>(),
+            Pixels::A8(rows) => rows
+                .rows()
+                .flatten()
+                .map(|&a| [0.0, 0.0, 0.0, f32::from(a) / 255.0])
+                .collect(),
+        };
+
+        // separable area averaging, first the rows then the columns
+        let weights_x = area_weights(src_w, dst_w);
+        let mut rows = vec![[0.0f32; 4]; dst_w * src_h];
+        for y in 0..src_h {
+            let src_row = &linear[y * src_w..(y + 1) * src_w];
+            for (x, weights) in weights_x.iter().enumerate() {
+                rows[y * dst_w + x] = weighted_sum(weights.iter().map(|&(i, w)| (src_row[i], w)));
+            }
+        }
+        let weights_y = area_weights(src_h, dst_h);
+        let mut result = Image::new(size.w, size.h, format)?;
+        match result.pixels_mut() {
+            PixelsMut::Rgb32(mut dst) => {
+                for (y, row) in dst.rows_mut().enumerate() {
+                    for (x, px) in row.iter_mut().enumerate() {
+                        let [r, g, b, a] = weighted_sum(
+                            weights_y[y].iter().map(|&(i, w)| (rows[i * dst_w + x], w)),
+                        );
+                        *px = encode_prgb32(r, g, b, a);
+                    }
+                }
+            },
+            PixelsMut::A8(mut dst) => {
+                for (y, row) in dst.rows_mut().enumerate() {
+                    for (x, px) in row.iter_mut().enumerate() {
+                        let [_, _, _, a] = weighted_sum(
+                            weights_y[y].iter().map(|&(i, w)| (rows[i * dst_w + x], w)),
+                        );
+                        *px = to_u8(a);
+                    }
+                }
+            },
+        }
+        Ok(result)
+    }
+}
+
+/// The sRGB transfer function for all 8-bit values.
+fn srgb_to_linear_table() -> [f32; 256] {
+    let mut table = [0.0; 256];
+    for (i, value) in table.iter_mut().enumerate() {
+        let c = i as f32 / 255.0;
+        *value = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+    }
+    table
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn unpremultiply(c: u8, a: f32) -> usize {
+    (f32::from(c) / a).round().min(255.0) as usize
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value * 255.0).round().max(0.0).min(255.0) as u8
+}
+
+/// Encodes premultiplied linear components as a premultiplied sRGB pixel.
+fn encode_prgb32(r: f32, g: f32, b: f32, a: f32) -> u32 {
+    if a <= 0.0 {
+        return 0;
+    }
+    let color = |c: f32| u32::from(to_u8(linear_to_srgb((c / a).min(1.0)) * a));
+    u32::from(to_u8(a)) << 24 | color(r) << 16 | color(g) << 8 | color(b)
+}
+
+/// The source indices and weights covered by each target index when
+/// shrinking `src` values down to `dst` values, the weights of each target
+/// index sum up to one.
+fn area_weights(src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src as f64 / dst as f64;
+    (0..dst)
+        .map(|i| {
+            let (start, end) = (i as f64 * scale, (i + 1) as f64 * scale);
+            (start.floor() as usize..(end.ceil() as usize).min(src))
+                .map(|s| {
+                    let overlap = end.min(s as f64 + 1.0) - start.max(s as f64);
+                    (s, (overlap / scale) as f32)
+                })
+                .filter(|&(_, w)| w > 0.0)
+                .collect()
+        })
+        .collect()
+}
+
+fn weighted_sum<I: Iterator<Item = ([f32; 4], f32)>>(values: I) -> [f32; 4] {
+    let mut sum = [0.0; 4];
+    for (value, weight) in values {
+        for (sum, c) in sum.iter_mut().zip(&value) {
+            *sum += c * weight;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod test_linear {
+    use crate::geometry::SizeI;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_downscaled_linear() {
+        let mut image = Image::new(4, 2, ImageFormat::PRgb32).unwrap();
+        for y in 0..2 {
+            for x in 0..4 {
+                let px = if x % 2 == 0 {
+                    0xFF_00_00_00
+                } else {
+                    0xFF_FF_FF_FF
+                };
+                image.set_pixel(x, y, px);
+            }
+        }
+        let scaled = image.downscaled_linear(SizeI { w: 2, h: 1 }).unwrap();
+        assert_eq!(scaled.format(), ImageFormat::PRgb32);
+        // half of the light is sRGB 188, not 128
+        assert_eq!(scaled.pixel(0, 0), Some(0xFF_BC_BC_BC));
+        assert_eq!(scaled.pixel(1, 0), Some(0xFF_BC_BC_BC));
+
+        // transparent pixels don't darken their neighbours
+        let mut image = Image::new(2, 1, ImageFormat::PRgb32).unwrap();
+        image.set_pixel(0, 0, 0xFF_FF_00_00);
+        let scaled = image.downscaled_linear(SizeI { w: 1, h: 1 }).unwrap();
+        assert_eq!(scaled.pixel(0, 0), Some(0x80_80_00_00));
+
+        assert!(image.downscaled_linear(SizeI { w: 3, h: 1 }).is_err());
+        assert!(image.downscaled_linear(SizeI { w: 0, h: 1 }).is_err());
+    }
+}
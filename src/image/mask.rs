@@ -0,0 +1,84 @@
+use crate::error::{Error, Result};
+use crate::geometry::PointI;
+
+use super::{Image, ImageFormat, PixelsMut};
+
+impl Image {
+    /// Multiplies all pixels of this image with the alpha values of the given
+    /// [`ImageFormat::A8`] mask placed at `offset`.
+    ///
+    /// Pixels not covered by the mask are cleared, so this clips the image to
+    /// the mask. Rendering into a separate image, applying a mask to it and
+    /// blitting the result is how soft clipping is done, as blend2d contexts
+    /// only support rectangular clipping. See also
+    /// [`Context::blit_masked_image`].
+    ///
+    /// [`Error::InvalidValue`] is returned if the mask is not an
+    /// [`ImageFormat::A8`] image.
+    ///
+    /// [`Context::blit_masked_image`]: ../context/struct.Context.html#method.blit_masked_image
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn apply_mask(&mut self, mask: &Image, offset: PointI) -> Result<()> {
+        if mask.format() != ImageFormat::A8 {
+            return Err(Error::InvalidValue);
+        }
+        let mask_alpha = |x: usize, y: usize| {
+            let mx = x as i64 - i64::from(offset.x);
+            let my = y as i64 - i64::from(offset.y);
+            if mx < 0 || my < 0 {
+                return 0;
+            }
+            mask.pixel(mx as usize, my as usize).map_or(0, |a| a >> 24)
+        };
+        match self.pixels_mut() {
+            PixelsMut::Rgb32(mut rows) => {
+                for (y, row) in rows.rows_mut().enumerate() {
+                    for (x, px) in row.iter_mut().enumerate() {
+                        *px = scale_prgb32(*px, mask_alpha(x, y));
+                    }
+                }
+            },
+            PixelsMut::A8(mut rows) => {
+                for (y, row) in rows.rows_mut().enumerate() {
+                    for (x, px) in row.iter_mut().enumerate() {
+                        *px = (u32::from(*px) * mask_alpha(x, y) / 255) as u8;
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Scales all components of a premultiplied pixel by `alpha / 255`.
+pub(crate) fn scale_prgb32(px: u32, alpha: u32) -> u32 {
+    match alpha {
+        0 => 0,
+        255 => px,
+        _ => {
+            let scale = |shift: u32| (((px >> shift) & 0xFF) * alpha / 255) << shift;
+            scale(24) | scale(16) | scale(8) | scale(0)
+        },
+    }
+}
+
+#[cfg(test)]
+mod test_mask {
+    use crate::geometry::PointI;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_apply_mask() {
+        let mut image = Image::new(3, 1, ImageFormat::PRgb32).unwrap();
+        for x in 0..3 {
+            image.set_pixel(x, 0, 0xFF_FF_80_00);
+        }
+        let mut mask = Image::new(1, 1, ImageFormat::A8).unwrap();
+        mask.set_pixel(0, 0, 0x80 << 24);
+        image.apply_mask(&mask, PointI::new(1, 0)).unwrap();
+        assert_eq!(image.pixel(0, 0), Some(0));
+        assert_eq!(image.pixel(1, 0), Some(0x80_80_40_00));
+        assert_eq!(image.pixel(2, 0), Some(0));
+        assert!(image.apply_mask(&image.clone(), PointI::ZERO).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use std::marker::PhantomData;
+use std::{mem, slice};
+
+use super::{Image, ImageFormat};
+
+/// A typed view of the pixel data of an [`Image`], returned by
+/// [`Image::pixels`].
+///
+/// [`Image::pixels`]: struct.Image.html#method.pixels
+#[derive(Copy, Clone, Debug)]
+pub enum Pixels<'a> {
+    /// Pixels of the [`ImageFormat::PRgb32`] and [`ImageFormat::XRgb32`]
+    /// formats as `0xAARRGGBB` values.
+    Rgb32(PixelRows<'a, u32>),
+    /// Pixels of the [`ImageFormat::A8`] format.
+    A8(PixelRows<'a, u8>),
+}
+
+/// A typed mutable view of the pixel data of an [`Image`], returned by
+/// [`Image::pixels_mut`].
+///
+/// [`Image::pixels_mut`]: struct.Image.html#method.pixels_mut
+#[derive(Debug)]
+pub enum PixelsMut<'a> {
+    /// Pixels of the [`ImageFormat::PRgb32`] and [`ImageFormat::XRgb32`]
+    /// formats as `0xAARRGGBB` values.
+    Rgb32(PixelRowsMut<'a, u32>),
+    /// Pixels of the [`ImageFormat::A8`] format.
+    A8(PixelRowsMut<'a, u8>),
+}
+
+/// The rows of an image's pixel data, excluding the padding at the end of
+/// each row.
+#[derive(Copy, Clone, Debug)]
+pub struct PixelRows<'a, T> {
+    data: &'a [u8],
+    stride: usize,
+    width: usize,
+    _pd: PhantomData<&'a [T]>,
+}
+
+impl<'a, T: Copy> PixelRows<'a, T> {
+    /// The number of pixels per row.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.data.len() / self.stride
+    }
+
+    /// Returns the row at the given index.
+    #[inline]
+    pub fn row(&self, y: usize) -> Option<&'a [T]> {
+        let row = self.data.get(y * self.stride..(y + 1) * self.stride)?;
+        // blend2d allocates its pixel data with strides that are multiples of
+        // the pixel size, and `Image::from_borrowed_data` rejects pointers and
+        // strides that aren't, so every row is aligned for T
+        debug_assert_eq!(row.as_ptr() as usize % mem::align_of::<T>(), 0);
+        Some(unsafe { slice::from_raw_parts(row.as_ptr() as *const T, self.width) })
+    }
+
+    /// Returns an iterator over all rows.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + 'a {
+        let width = self.width;
+        self.data.chunks_exact(self.stride).map(move |row| {
+            // rows are aligned for T, see `row`
+            debug_assert_eq!(row.as_ptr() as usize % mem::align_of::<T>(), 0);
+            unsafe { slice::from_raw_parts(row.as_ptr() as *const T, width) }
+        })
+    }
+
+    /// Returns the pixel at the given coordinates.
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        self.row(y)?.get(x).copied()
+    }
+}
+
+/// The mutable rows of an image's pixel data, excluding the padding at the
+/// end of each row.
+#[derive(Debug)]
+pub struct PixelRowsMut<'a, T> {
+    data: &'a mut [u8],
+    stride: usize,
+    width: usize,
+    _pd: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: Copy> PixelRowsMut<'a, T> {
+    /// The number of pixels per row.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.data.len() / self.stride
+    }
+
+    /// Returns the row at the given index.
+    #[inline]
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [T]> {
+        let width = self.width;
+        let row = self.data.get_mut(y * self.stride..(y + 1) * self.stride)?;
+        // rows are aligned for T, see `PixelRows::row`
+        debug_assert_eq!(row.as_ptr() as usize % mem::align_of::<T>(), 0);
+        Some(unsafe { slice::from_raw_parts_mut(row.as_mut_ptr() as *mut T, width) })
+    }
+
+    /// Returns an iterator over all rows.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let width = self.width;
+        self.data.chunks_exact_mut(self.stride).map(move |row| {
+            // rows are aligned for T, see `PixelRows::row`
+            debug_assert_eq!(row.as_ptr() as usize % mem::align_of::<T>(), 0);
+            unsafe { slice::from_raw_parts_mut(row.as_mut_ptr() as *mut T, width) }
+        })
+    }
+
+    /// Sets the pixel at the given coordinates, returning false if they are
+    /// out of bounds.
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> bool {
+        match self.row_mut(y).and_then(|row| row.get_mut(x)) {
+            Some(px) => {
+                *px = value;
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Image {
+    /// Returns a typed view of this image's pixel data.
+    pub fn pixels(&self) -> Pixels<'_> {
+        let (stride, width) = self.row_layout();
+        let data: &[u8] = self;
+        match self.format() {
+            ImageFormat::PRgb32 | ImageFormat::XRgb32 => Pixels::Rgb32(PixelRows {
+                data,
+                stride,
+                width,
+                _pd: PhantomData,
+            }),
+            ImageFormat::A8 => Pixels::A8(PixelRows {
+                data,
+                stride,
+                width,
+                _pd: PhantomData,
+            }),
+        }
+    }
+
+    /// Returns a typed mutable view of this image's pixel data.
+    ///
+    /// This makes the pixel data of this image unique if it is shared with
+    /// other images.
+    pub fn pixels_mut(&mut self) -> PixelsMut<'_> {
+        let (stride, width) = self.row_layout();
+        let format = self.format();
+        let data: &mut [u8] = self;
+        match format {
+            ImageFormat::PRgb32 | ImageFormat::XRgb32 => PixelsMut::Rgb32(PixelRowsMut {
+                data,
+                stride,
+                width,
+                _pd: PhantomData,
+            }),
+            ImageFormat::A8 => PixelsMut::A8(PixelRowsMut {
+                data,
+                stride,
+                width,
+                _pd: PhantomData,
+            }),
+        }
+    }
+
+    /// Returns the pixel at the given coordinates as a premultiplied
+    /// `0xAARRGGBB` value.
+    ///
+    /// Pixels of [`ImageFormat::A8`] images only have an alpha component.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<u32> {
+        match self.pixels() {
+            Pixels::Rgb32(rows) => rows.get(x, y),
+            Pixels::A8(rows) => rows.get(x, y).map(|a| u32::from(a) << 24),
+        }
+    }
+
+    /// Sets the pixel at the given coordinates to the premultiplied
+    /// `0xAARRGGBB` value, returning false if they are out of bounds.
+    ///
+    /// Only the alpha component is stored for [`ImageFormat::A8`] images.
+    pub fn set_pixel(&mut self, x: usize, y: usize, value: u32) -> bool {
+        match self.pixels_mut() {
+            PixelsMut::Rgb32(mut rows) => rows.set(x, y, value),
+            PixelsMut::A8(mut rows) => rows.set(x, y, (value >> 24) as u8),
+        }
+    }
+
+    /// Returns the stride in bytes and the width in pixels of this image.
+    ///
+    /// Empty images have a stride of `1` and a width of `0`, so that their
+    /// rows can still be chunked.
+    #[inline]
+    pub(super) fn row_layout(&self) -> (usize, usize) {
+        let height = self.height() as usize;
+        if height == 0 || self.len() == 0 {
+            return (1, 0);
+        }
+        (self.len() / height, self.width() as usize)
+    }
+}
+
+#[cfg(test)]
+mod test_pixels {
+    use crate::image::{Image, ImageFormat, Pixels};
+
+    #[test]
+    fn test_pixel_access() {
+        let mut image = Image::new(3, 2, ImageFormat::PRgb32).unwrap();
+        for y in 0..2 {
+            for x in 0..3 {
+                assert!(image.set_pixel(x, y, 0xFF_00_00_00 | (y * 3 + x) as u32));
+            }
+        }
+        assert!(!image.set_pixel(3, 0, 0));
+        assert_eq!(image.pixel(2, 1), Some(0xFF_00_00_05));
+        assert_eq!(image.pixel(0, 2), None);
+        match image.pixels() {
+            Pixels::Rgb32(rows) => {
+                assert_eq!(rows.height(), 2);
+                let row = rows.rows().nth(1).unwrap();
+                assert_eq!(row, &[0xFF_00_00_03, 0xFF_00_00_04, 0xFF_00_00_05]);
+            },
+            Pixels::A8(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_pixel_access_a8() {
+        let mut image = Image::new(2, 2, ImageFormat::A8).unwrap();
+        assert!(image.set_pixel(1, 1, 0x80_12_34_56));
+        assert_eq!(image.pixel(1, 1), Some(0x80_00_00_00));
+    }
+
+    #[test]
+    fn test_pixel_access_empty() {
+        let mut image = Image::default();
+        assert_eq!(image.pixel(0, 0), None);
+        assert!(!image.set_pixel(0, 0, 0));
+        match image.pixels() {
+            Pixels::Rgb32(rows) => {
+                assert_eq!(rows.height(), 0);
+                assert_eq!(rows.rows().count(), 0);
+            },
+            Pixels::A8(rows) => {
+                assert_eq!(rows.height(), 0);
+                assert_eq!(rows.rows().count(), 0);
+            },
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::{Image, ImageFormat, PixelsMut};
+
+/// The precision of [`Image::premultiply`] and [`Image::unpremultiply`].
+///
+/// [`Image::premultiply`]: struct.Image.html#method.premultiply
+/// [`Image::unpremultiply`]: struct.Image.html#method.unpremultiply
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PremultiplyMode {
+    /// Rounds every component to the nearest value.
+    Exact,
+    /// Uses integer approximations that avoid divisions, which may be off
+    /// by one from [`Exact`].
+    ///
+    /// [`Exact`]: #variant.Exact
+    Fast,
+}
+
+impl Default for PremultiplyMode {
+    fn default() -> Self {
+        PremultiplyMode::Exact
+    }
+}
+
+impl Image {
+    /// Premultiplies the color components of all pixels with their alpha in
+    /// place.
+    ///
+    /// This is meant for [`ImageFormat::PRgb32`] images whose pixels were
+    /// imported with straight alpha, the format of the image is not changed.
+    /// Images of the [`ImageFormat::XRgb32`] and [`ImageFormat::A8`] formats
+    /// are left untouched, as their pixels are the same either way.
+    ///
+    /// [`ImageFormat::PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+    /// [`ImageFormat::XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`ImageFormat::A8`]: enum.ImageFormat.html#variant.A8
+    pub fn premultiply(&mut self, mode: PremultiplyMode) {
+        match mode {
+            PremultiplyMode::Exact => self.map_prgb32(premultiply_pixel),
+            PremultiplyMode::Fast => self.map_prgb32(|px| {
+                let a = px >> 24;
+                let mul = |shift: u32| (((px >> shift) & 0xFF) * (a + 1) >> 8) << shift;
+                a << 24 | mul(16) | mul(8) | mul(0)
+            }),
+        }
+    }
+
+    /// Divides the color components of all pixels by their alpha in place,
+    /// the inverse of [`premultiply`].
+    ///
+    /// Fully transparent pixels become transparent black. The formats are
+    /// treated like in [`premultiply`].
+    ///
+    /// [`premultiply`]: #method.premultiply
+    pub fn unpremultiply(&mut self, mode: PremultiplyMode) {
+        match mode {
+            PremultiplyMode::Exact => self.map_prgb32(unpremultiply_pixel),
+            PremultiplyMode::Fast => {
+                // 16.16 fixed point reciprocals of all alpha values
+                let mut reciprocals = [0u32; 256];
+                for (a, reciprocal) in reciprocals.iter_mut().enumerate().skip(1) {
+                    *reciprocal = (255 << 16) / a as u32;
+                }
+                self.map_prgb32(|px| {
+                    let a = px >> 24;
+                    let reciprocal = reciprocals[a as usize];
+                    let unmul = |shift: u32| {
+                        ((((px >> shift) & 0xFF) * reciprocal + 0x8000) >> 16).min(255) << shift
+                    };
+                    a << 24 | unmul(16) | unmul(8) | unmul(0)
+                })
+            },
+        }
+    }
+
+    pub(super) fn map_prgb32<F: FnMut(u32) -> u32>(&mut self, mut f: F) {
+        if self.format() != ImageFormat::PRgb32 {
+            return;
+        }
+        if let PixelsMut::Rgb32(mut rows) = self.pixels_mut() {
+            rows.rows_mut().flatten().for_each(|px| *px = f(*px));
+        }
+    }
+}
+
+/// Premultiplies a `0xAARRGGBB` pixel with straight alpha, rounding to the
+/// nearest value.
+pub(crate) fn premultiply_pixel(px: u32) -> u32 {
+    let a = px >> 24;
+    let mul = |shift: u32| ((((px >> shift) & 0xFF) * a + 127) / 255) << shift;
+    a << 24 | mul(16) | mul(8) | mul(0)
+}
+
+/// Unpremultiplies a premultiplied `0xAARRGGBB` pixel, rounding to the
+/// nearest value.
+pub(crate) fn unpremultiply_pixel(px: u32) -> u32 {
+    let a = px >> 24;
+    if a == 0 {
+        return 0;
+    }
+    let unmul = |shift: u32| ((((px >> shift) & 0xFF) * 255 + a / 2) / a).min(255) << shift;
+    a << 24 | unmul(16) | unmul(8) | unmul(0)
+}
+
+#[cfg(test)]
+mod test_premultiply {
+    use crate::image::{Image, ImageFormat, PremultiplyMode};
+
+    #[test]
+    fn test_premultiply_roundtrip() {
+        for &mode in &[PremultiplyMode::Exact, PremultiplyMode::Fast] {
+            let mut image = Image::new(3, 1, ImageFormat::PRgb32).unwrap();
+            image.set_pixel(0, 0, 0x80_FF_80_00);
+            image.set_pixel(1, 0, 0x00_FF_FF_FF);
+            image.set_pixel(2, 0, 0xFF_12_34_56);
+            image.premultiply(mode);
+            assert_eq!(image.pixel(0, 0), Some(0x80_80_40_00));
+            assert_eq!(image.pixel(1, 0), Some(0));
+            assert_eq!(image.pixel(2, 0), Some(0xFF_12_34_56));
+
+            image.unpremultiply(mode);
+            assert_eq!(image.pixel(0, 0), Some(0x80_FF_80_00));
+            assert_eq!(image.pixel(2, 0), Some(0xFF_12_34_56));
+        }
+
+        let mut image = Image::new(1, 1, ImageFormat::XRgb32).unwrap();
+        image.set_pixel(0, 0, 0x00_FF_FF_FF);
+        image.premultiply(PremultiplyMode::Exact);
+        assert_eq!(image.pixel(0, 0), Some(0x00_FF_FF_FF));
+    }
+}
@@ -0,0 +1,184 @@
+use std::ptr;
+use std::str::FromStr;
+
+use crate::error::{errcode_to_result, Error, Result};
+use crate::geometry::{RectI, SizeI};
+use crate::variant::WrappedBlCore;
+
+use super::{Image, ImageScaleFilter};
+
+impl Image {
+    /// Returns a copy of this image scaled to fit into `max_w` x `max_h`
+    /// pixels while preserving its aspect ratio.
+    ///
+    /// The image is scaled up as well if it is smaller than the given size,
+    /// each side of the result is at least one pixel. Returns
+    /// [`Error::InvalidValue`] if the given size is empty.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn scaled_to_fit(&self, max_w: i32, max_h: i32, filter: ImageScaleFilter) -> Result<Image> {
+        if max_w <= 0 || max_h <= 0 {
+            return Err(Error::InvalidValue);
+        }
+        let size = self.size();
+        let scale =
+            (f64::from(max_w) / f64::from(size.w)).min(f64::from(max_h) / f64::from(size.h));
+        let target = SizeI {
+            w: ((f64::from(size.w) * scale).round() as i32)
+                .max(1)
+                .min(max_w),
+            h: ((f64::from(size.h) * scale).round() as i32)
+                .max(1)
+                .min(max_h),
+        };
+        self.scaled_to_size(target, filter)
+    }
+
+    /// Returns a copy of this image scaled to cover `w` x `h` pixels while
+    /// preserving its aspect ratio, with the overhanging sides cropped so
+    /// that the result is exactly `w` x `h` pixels and centered on the
+    /// original image.
+    ///
+    /// Returns [`Error::InvalidValue`] if the given size is empty.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn scaled_to_fill(&self, w: i32, h: i32, filter: ImageScaleFilter) -> Result<Image> {
+        if w <= 0 || h <= 0 {
+            return Err(Error::InvalidValue);
+        }
+        let size = self.size();
+        let scale = (f64::from(w) / f64::from(size.w)).max(f64::from(h) / f64::from(size.h));
+        let target = SizeI {
+            w: ((f64::from(size.w) * scale).round() as i32).max(w),
+            h: ((f64::from(size.h) * scale).round() as i32).max(h),
+        };
+        let scaled = self.scaled_to_size(target, filter)?;
+        if target.w == w && target.h == h {
+            return Ok(scaled);
+        }
+        scaled.cropped(&RectI::new((target.w - w) / 2, (target.h - h) / 2, w, h))
+    }
+
+    fn scaled_to_size(&self, size: SizeI, filter: ImageScaleFilter) -> Result<Image> {
+        let mut this = Image::from_core(*Self::none());
+        unsafe {
+            let opts = filter.into_options();
+            errcode_to_result(ffi::blImageScale(
+                this.core_mut(),
+                self.core(),
+                &size as *const _ as *const _,
+                filter.filter(),
+                opts.as_ref().map_or(ptr::null(), |opt| opt as *const _),
+            ))
+            .map(|_| this)
+        }
+    }
+}
+
+impl FromStr for ImageScaleFilter {
+    type Err = Error;
+
+    /// Parses a filter from its case-insensitive name, e.g. `"bilinear"`.
+    ///
+    /// The radius of the `sinc`, `lanczos` and `blackman` filters defaults to
+    /// `2.0` and can be given after a colon as in `"lanczos:3"`, the `b` and
+    /// `c` parameters of the `mitchell` filter default to `1/3` and can be
+    /// given as in `"mitchell:0.5,0.25"`. Returns [`Error::InvalidValue`] for
+    /// unknown names and malformed parameters.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().splitn(2, ':');
+        let name = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let params = parts
+            .next()
+            .map(|params| {
+                params
+                    .split(',')
+                    .map(|param| param.trim().parse::<f64>().map_err(|_| Error::InvalidValue))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+        let radius = || match params.as_deref() {
+            None => Ok(2.0),
+            Some(&[radius]) if radius > 0.0 => Ok(radius),
+            _ => Err(Error::InvalidValue),
+        };
+        let filter = match &*name {
+            "nearest" => ImageScaleFilter::Nearest,
+            "bilinear" => ImageScaleFilter::Bilinear,
+            "bicubic" => ImageScaleFilter::Bicubic,
+            "bell" => ImageScaleFilter::Bell,
+            "gauss" => ImageScaleFilter::Gauss,
+            "hermite" => ImageScaleFilter::Hermite,
+            "hanning" => ImageScaleFilter::Hanning,
+            "catrom" => ImageScaleFilter::Catrom,
+            "bessel" => ImageScaleFilter::Bessel,
+            "sinc" => return Ok(ImageScaleFilter::Sinc { radius: radius()? }),
+            "lanczos" => return Ok(ImageScaleFilter::Lanczos { radius: radius()? }),
+            "blackman" => return Ok(ImageScaleFilter::Blackman { radius: radius()? }),
+            "mitchell" => {
+                return match params.as_deref() {
+                    None => Ok(ImageScaleFilter::Mitchell {
+                        b: 1.0 / 3.0,
+                        c: 1.0 / 3.0,
+                    }),
+                    Some(&[b, c]) => Ok(ImageScaleFilter::Mitchell { b, c }),
+                    _ => Err(Error::InvalidValue),
+                };
+            },
+            _ => return Err(Error::InvalidValue),
+        };
+        // the remaining filters take no parameters
+        match params {
+            None => Ok(filter),
+            Some(_) => Err(Error::InvalidValue),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_resize {
+    use crate::geometry::SizeI;
+    use crate::image::{Image, ImageFormat, ImageScaleFilter};
+
+    #[test]
+    fn test_scaled_to_fit_and_fill() {
+        let image = Image::new(200, 100, ImageFormat::PRgb32).unwrap();
+        let fit = image
+            .scaled_to_fit(50, 50, ImageScaleFilter::Bilinear)
+            .unwrap();
+        assert_eq!(fit.size(), SizeI::new(50, 25));
+        let fill = image
+            .scaled_to_fill(50, 50, ImageScaleFilter::Bilinear)
+            .unwrap();
+        assert_eq!(fill.size(), SizeI::new(50, 50));
+        assert!(image
+            .scaled_to_fit(0, 50, ImageScaleFilter::Bilinear)
+            .is_err());
+    }
+
+    #[test]
+    fn test_scale_filter_from_str() {
+        let parse = |s: &str| s.parse::<ImageScaleFilter>().ok();
+        assert!(matches!(
+            parse("Bilinear"),
+            Some(ImageScaleFilter::Bilinear)
+        ));
+        assert!(matches!(
+            parse("lanczos"),
+            Some(ImageScaleFilter::Lanczos { radius }) if radius == 2.0
+        ));
+        assert!(matches!(
+            parse("lanczos: 3"),
+            Some(ImageScaleFilter::Lanczos { radius }) if radius == 3.0
+        ));
+        assert!(matches!(
+            parse("mitchell:0.5,0.25"),
+            Some(ImageScaleFilter::Mitchell { b, c }) if b == 0.5 && c == 0.25
+        ));
+        assert!(parse("nearest:1").is_none());
+        assert!(parse("lanczos:-1").is_none());
+        assert!(parse("box").is_none());
+    }
+}
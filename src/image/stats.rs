@@ -0,0 +1,151 @@
+use super::{Image, ImageFormat, Pixels};
+
+/// The number of pixels per value of each channel of an image, computed by
+/// [`Image::histogram`].
+///
+/// [`Image::histogram`]: struct.Image.html#method.histogram
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    pub red: [u32; 256],
+    pub green: [u32; 256],
+    pub blue: [u32; 256],
+    pub alpha: [u32; 256],
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram {
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+            alpha: [0; 256],
+        }
+    }
+}
+
+/// Luminance and alpha statistics of an image, computed by
+/// [`Image::stats`].
+///
+/// [`Image::stats`]: struct.Image.html#method.stats
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ImageStats {
+    /// The smallest luminance of all pixels.
+    pub min_luminance: u8,
+    /// The largest luminance of all pixels.
+    pub max_luminance: u8,
+    /// The mean luminance of all pixels.
+    pub mean_luminance: f64,
+    /// The mean alpha of all pixels in `0.0..=1.0`.
+    pub alpha_coverage: f64,
+    /// The number of pixels that aren't fully transparent.
+    pub covered_pixels: usize,
+    /// The number of fully opaque pixels.
+    pub opaque_pixels: usize,
+}
+
+impl Image {
+    /// Counts the values of each channel of all pixels.
+    ///
+    /// The color channels are counted as stored, that is premultiplied for
+    /// [`ImageFormat::PRgb32`]. [`ImageFormat::XRgb32`] pixels count as
+    /// opaque and [`ImageFormat::A8`] pixels as black.
+    ///
+    /// [`ImageFormat::PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+    /// [`ImageFormat::XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`ImageFormat::A8`]: enum.ImageFormat.html#variant.A8
+    pub fn histogram(&self) -> Histogram {
+        let mut histogram = Histogram::default();
+        self.for_each_argb(|px| {
+            let [a, r, g, b] = px.to_be_bytes();
+            histogram.alpha[a as usize] += 1;
+            histogram.red[r as usize] += 1;
+            histogram.green[g as usize] += 1;
+            histogram.blue[b as usize] += 1;
+        });
+        histogram
+    }
+
+    /// Computes luminance and alpha statistics of all pixels, which is handy
+    /// for checking that something was drawn at all.
+    ///
+    /// The luminance is computed with the Rec. 601 weights from the color
+    /// channels as stored, so premultiplied pixels count as composited over
+    /// black. The formats are treated like in [`histogram`]. An empty image
+    /// has all statistics set to zero.
+    ///
+    /// [`histogram`]: #method.histogram
+    pub fn stats(&self) -> ImageStats {
+        let mut stats = ImageStats {
+            min_luminance: u8::max_value(),
+            ..Default::default()
+        };
+        let (mut count, mut luminance_sum, mut alpha_sum) = (0u64, 0u64, 0u64);
+        self.for_each_argb(|px| {
+            let [a, r, g, b] = px.to_be_bytes();
+            let luminance =
+                ((299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b) + 500) / 1000) as u8;
+            stats.min_luminance = stats.min_luminance.min(luminance);
+            stats.max_luminance = stats.max_luminance.max(luminance);
+            count += 1;
+            luminance_sum += u64::from(luminance);
+            alpha_sum += u64::from(a);
+            if a != 0 {
+                stats.covered_pixels += 1;
+            }
+            if a == 0xFF {
+                stats.opaque_pixels += 1;
+            }
+        });
+        if count == 0 {
+            return ImageStats::default();
+        }
+        stats.mean_luminance = luminance_sum as f64 / count as f64;
+        stats.alpha_coverage = alpha_sum as f64 / (count as f64 * 255.0);
+        stats
+    }
+
+    /// Calls `f` with every pixel as a `0xAARRGGBB` value.
+    fn for_each_argb<F: FnMut(u32)>(&self, mut f: F) {
+        match self.pixels() {
+            Pixels::Rgb32(rows) => {
+                let alpha = if self.format() == ImageFormat::XRgb32 {
+                    0xFF00_0000
+                } else {
+                    0
+                };
+                rows.rows().flatten().for_each(|&px| f(px | alpha));
+            },
+            Pixels::A8(rows) => rows.rows().flatten().for_each(|&a| f(u32::from(a) << 24)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_stats {
+    use crate::context::Context;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_histogram_and_stats() {
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        {
+            let mut ctx = Context::new(&mut image).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_fill_style_rgba32(0xFF_FF_FF_FF);
+            ctx.fill_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+            ctx.end().unwrap();
+        }
+        let histogram = image.histogram();
+        assert_eq!(histogram.alpha[0xFF], 4);
+        assert_eq!(histogram.red[0], 12);
+
+        let stats = image.stats();
+        assert_eq!((stats.min_luminance, stats.max_luminance), (0, 255));
+        assert_eq!(stats.mean_luminance, 255.0 / 4.0);
+        assert_eq!(stats.alpha_coverage, 0.25);
+        assert_eq!((stats.covered_pixels, stats.opaque_pixels), (4, 4));
+
+        let empty = Image::new(4, 4, ImageFormat::XRgb32).unwrap();
+        assert_eq!(empty.histogram().alpha[0xFF], 16);
+    }
+}
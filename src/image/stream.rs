@@ -0,0 +1,63 @@
+use std::io::{Read, Write};
+
+use crate::array::Array;
+use crate::codec::ImageCodec;
+use crate::error::{errcode_to_result, Error, Result};
+use crate::variant::WrappedBlCore;
+
+use super::Image;
+
+impl Image {
+    /// Reads all data from the reader and decodes it with the first codec of
+    /// the given ones that recognizes it.
+    ///
+    /// The whole stream is buffered in memory before decoding. Returns
+    /// [`Error::Io`] if reading fails.
+    ///
+    /// [`Error::Io`]: ../error/enum.Error.html#variant.Io
+    pub fn from_reader<R: Read>(mut reader: R, codecs: &Array<ImageCodec>) -> Result<Image> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).map_err(|_| Error::Io)?;
+        let mut this = Image::from_core(*Self::none());
+        unsafe {
+            errcode_to_result(ffi::blImageReadFromData(
+                this.core_mut(),
+                data.as_ptr() as *const _,
+                data.len(),
+                codecs.core(),
+            ))
+            .map(|_| this)
+        }
+    }
+
+    /// Encodes the image with the given codec and writes the encoded data to
+    /// the writer.
+    ///
+    /// Returns [`Error::Io`] if writing fails.
+    ///
+    /// [`Error::Io`]: ../error/enum.Error.html#variant.Io
+    pub fn write_to<W: Write>(&self, mut writer: W, codec: &ImageCodec) -> Result<()> {
+        let mut data = Array::<u8>::new();
+        self.write_to_data(&mut data, codec)?;
+        writer.write_all(&data).map_err(|_| Error::Io)
+    }
+}
+
+#[cfg(test)]
+mod test_stream {
+    use crate::codec::ImageCodec;
+    use crate::image::Image;
+
+    #[test]
+    fn test_write_to_and_from_reader() {
+        let codecs = ImageCodec::built_in_codecs();
+        let codec = codecs.find_codec_by_extension("out.PNG").unwrap();
+        assert_eq!(codec.name(), "PNG");
+        let image = Image::new(5, 7, Default::default()).unwrap();
+        let mut data = Vec::new();
+        image.write_to(&mut data, codec).unwrap();
+        let decoded = Image::from_reader(&data[..], &codecs).unwrap();
+        assert_eq!(decoded.size(), image.size());
+        assert!(Image::from_reader(&b"nope"[..], &codecs).is_err());
+    }
+}
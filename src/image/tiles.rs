@@ -0,0 +1,309 @@
+use std::marker::PhantomData;
+use std::slice;
+
+use crate::geometry::RectI;
+
+use super::Image;
+
+/// The position of the next tile of a tiles iterator.
+#[derive(Copy, Clone, Debug)]
+struct TileGrid {
+    width: i32,
+    height: i32,
+    tile_w: i32,
+    tile_h: i32,
+    x: i32,
+    y: i32,
+}
+
+impl TileGrid {
+    fn new(width: i32, height: i32, tile_w: i32, tile_h: i32) -> Self {
+        assert!(tile_w > 0 && tile_h > 0, "tile size must be positive");
+        let y = if width > 0 { 0 } else { height };
+        TileGrid {
+            width,
+            height,
+            tile_w,
+            tile_h,
+            x: 0,
+            y,
+        }
+    }
+
+    fn next(&mut self) -> Option<RectI> {
+        if self.y >= self.height {
+            return None;
+        }
+        let rect = RectI {
+            x: self.x,
+            y: self.y,
+            w: self.tile_w.min(self.width - self.x),
+            h: self.tile_h.min(self.height - self.y),
+        };
+        self.x += self.tile_w;
+        if self.x >= self.width {
+            self.x = 0;
+            self.y += self.tile_h;
+        }
+        Some(rect)
+    }
+
+    fn remaining(&self) -> usize {
+        if self.y >= self.height {
+            return 0;
+        }
+        let columns = ((self.width + self.tile_w - 1) / self.tile_w) as usize;
+        let rows = ((self.height - self.y + self.tile_h - 1) / self.tile_h) as usize;
+        rows * columns - (self.x / self.tile_w) as usize
+    }
+}
+
+/// A rectangular part of an image's pixel data, yielded by [`Image::tiles`].
+///
+/// [`Image::tiles`]: struct.Image.html#method.tiles
+#[derive(Copy, Clone, Debug)]
+pub struct Tile<'a> {
+    rect: RectI,
+    data: &'a [u8],
+    stride: usize,
+    row_len: usize,
+}
+
+impl<'a> Tile<'a> {
+    /// The area of the image covered by this tile.
+    #[inline]
+    pub fn rect(&self) -> RectI {
+        self.rect
+    }
+
+    /// The number of bytes between the starts of two rows of the image.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the pixel data of the row at the given index relative to the
+    /// tile.
+    #[inline]
+    pub fn row(&self, y: usize) -> Option<&'a [u8]> {
+        if y >= self.rect.h as usize {
+            return None;
+        }
+        self.data.get(y * self.stride..y * self.stride + self.row_len)
+    }
+
+    /// Returns an iterator over the pixel data of all rows of this tile.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let this = *self;
+        (0..self.rect.h as usize).filter_map(move |y| this.row(y))
+    }
+}
+
+/// An iterator over the tiles of an image, see [`Image::tiles`].
+///
+/// [`Image::tiles`]: struct.Image.html#method.tiles
+#[derive(Clone, Debug)]
+pub struct Tiles<'a> {
+    data: &'a [u8],
+    stride: usize,
+    bpp: usize,
+    grid: TileGrid,
+}
+
+impl<'a> Iterator for Tiles<'a> {
+    type Item = Tile<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rect = self.grid.next()?;
+        let start = rect.y as usize * self.stride + rect.x as usize * self.bpp;
+        let row_len = rect.w as usize * self.bpp;
+        let end = start + (rect.h as usize - 1) * self.stride + row_len;
+        Some(Tile {
+            rect,
+            data: &self.data[start..end],
+            stride: self.stride,
+            row_len,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.grid.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Tiles<'_> {}
+
+/// A mutable rectangular part of an image's pixel data, yielded by
+/// [`Image::tiles_mut`].
+///
+/// Tiles never overlap, so they can be processed in parallel.
+///
+/// [`Image::tiles_mut`]: struct.Image.html#method.tiles_mut
+#[derive(Debug)]
+pub struct TileMut<'a> {
+    rect: RectI,
+    ptr: *mut u8,
+    stride: usize,
+    row_len: usize,
+    _pd: PhantomData<&'a mut [u8]>,
+}
+
+// A TileMut grants exclusive access to its pixels just like a &mut [u8].
+unsafe impl Send for TileMut<'_> {}
+unsafe impl Sync for TileMut<'_> {}
+
+impl TileMut<'_> {
+    /// The area of the image covered by this tile.
+    #[inline]
+    pub fn rect(&self) -> RectI {
+        self.rect
+    }
+
+    /// The number of bytes between the starts of two rows of the image.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the pixel data of the row at the given index relative to the
+    /// tile.
+    #[inline]
+    pub fn row(&self, y: usize) -> Option<&[u8]> {
+        if y >= self.rect.h as usize {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts(self.ptr.add(y * self.stride), self.row_len) })
+    }
+
+    /// Returns the mutable pixel data of the row at the given index relative
+    /// to the tile.
+    #[inline]
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [u8]> {
+        if y >= self.rect.h as usize {
+            return None;
+        }
+        Some(unsafe { slice::from_raw_parts_mut(self.ptr.add(y * self.stride), self.row_len) })
+    }
+
+    /// Returns an iterator over the mutable pixel data of all rows of this
+    /// tile.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let (ptr, stride, row_len) = (self.ptr, self.stride, self.row_len);
+        // the rows of a tile are disjoint
+        (0..self.rect.h as usize)
+            .map(move |y| unsafe { slice::from_raw_parts_mut(ptr.add(y * stride), row_len) })
+    }
+}
+
+/// An iterator over the mutable tiles of an image, see
+/// [`Image::tiles_mut`].
+///
+/// [`Image::tiles_mut`]: struct.Image.html#method.tiles_mut
+#[derive(Debug)]
+pub struct TilesMut<'a> {
+    ptr: *mut u8,
+    stride: usize,
+    bpp: usize,
+    grid: TileGrid,
+    _pd: PhantomData<&'a mut [u8]>,
+}
+
+unsafe impl Send for TilesMut<'_> {}
+unsafe impl Sync for TilesMut<'_> {}
+
+impl<'a> Iterator for TilesMut<'a> {
+    type Item = TileMut<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rect = self.grid.next()?;
+        let start = rect.y as usize * self.stride + rect.x as usize * self.bpp;
+        Some(TileMut {
+            rect,
+            ptr: unsafe { self.ptr.add(start) },
+            stride: self.stride,
+            row_len: rect.w as usize * self.bpp,
+            _pd: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.grid.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TilesMut<'_> {}
+
+impl Image {
+    /// Returns an iterator over the tiles of this image in row-major order.
+    ///
+    /// Tiles at the right and bottom edges are smaller if the image size is
+    /// not a multiple of the tile size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tile width or height is not positive.
+    pub fn tiles(&self, tile_w: i32, tile_h: i32) -> Tiles<'_> {
+        let (stride, _) = self.row_layout();
+        Tiles {
+            data: self,
+            stride,
+            bpp: self.format().bytes_per_pixel(),
+            grid: TileGrid::new(self.width(), self.height(), tile_w, tile_h),
+        }
+    }
+
+    /// Returns an iterator over the mutable tiles of this image in row-major
+    /// order.
+    ///
+    /// The tiles are disjoint, so they can be sent to different threads for
+    /// processing. This makes the pixel data of this image unique if it is
+    /// shared with other images.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tile width or height is not positive.
+    pub fn tiles_mut(&mut self, tile_w: i32, tile_h: i32) -> TilesMut<'_> {
+        let (stride, _) = self.row_layout();
+        let bpp = self.format().bytes_per_pixel();
+        let grid = TileGrid::new(self.width(), self.height(), tile_w, tile_h);
+        let data: &mut [u8] = self;
+        TilesMut {
+            ptr: data.as_mut_ptr(),
+            stride,
+            bpp,
+            grid,
+            _pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_tiles {
+    use crate::geometry::RectI;
+    use crate::image::{Image, ImageFormat};
+
+    #[test]
+    fn test_tiles() {
+        let mut image = Image::new(5, 3, ImageFormat::A8).unwrap();
+        let tiles = image.tiles_mut(2, 2);
+        assert_eq!(tiles.len(), 6);
+        for (i, mut tile) in tiles.enumerate() {
+            for row in tile.rows_mut() {
+                row.iter_mut().for_each(|px| *px = i as u8);
+            }
+        }
+        let tiles: Vec<_> = image.tiles(2, 2).collect();
+        assert_eq!(tiles[2].rect(), RectI::new(4, 0, 1, 2));
+        assert_eq!(tiles[5].rect(), RectI::new(4, 2, 1, 1));
+        assert_eq!(tiles[4].row(0), Some(&[4, 4][..]));
+        assert_eq!(tiles[4].row(1), None);
+        for y in 0..3 {
+            let expected = [0, 0, 1, 1, 2].iter().map(|i| i + y / 2 * 3);
+            assert!(expected.enumerate().all(|(x, px)| image.pixel(x, y) == Some((px as u32) << 24)));
+        }
+    }
+}
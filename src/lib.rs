@@ -4,12 +4,14 @@
 #[macro_use]
 mod macros;
 
+pub(in crate) mod svg_attrs;
 pub(in crate) mod util;
 pub(in crate) mod variant;
 
 pub use self::variant::DeepClone;
 
 pub mod array;
+pub mod canvas;
 pub mod codec;
 pub mod context;
 pub mod error;
@@ -18,20 +20,68 @@ pub mod font_defs;
 pub mod geometry;
 pub mod glyph_buffer;
 pub mod gradient;
+pub mod headless;
 pub mod image;
 pub mod matrix;
 pub mod path;
 pub mod pattern;
 pub mod prelude;
+pub mod random;
 pub mod region;
+pub mod rgba;
 pub mod runtime;
+pub mod style;
+#[cfg(feature = "svg")]
+pub mod svg;
 
 use bitflags::bitflags;
 
+/// A 4-byte OpenType tag like `b"kern"`.
 #[repr(transparent)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Tag(u32);
 
+impl Tag {
+    /// Creates a tag from its four characters.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; 4]) -> Self {
+        Tag(u32::from_be_bytes(bytes))
+    }
+
+    /// Returns the four characters of this tag.
+    #[inline]
+    pub fn to_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
+
+    /// Returns the raw value of this tag.
+    #[inline]
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for Tag {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Tag(value)
+    }
+}
+
+impl From<[u8; 4]> for Tag {
+    #[inline]
+    fn from(bytes: [u8; 4]) -> Self {
+        Tag::from_bytes(bytes)
+    }
+}
+
+impl From<Tag> for u32 {
+    #[inline]
+    fn from(tag: Tag) -> Self {
+        tag.0
+    }
+}
+
 use ffi::BLBooleanOp::*;
 bl_enum! {
     pub enum BooleanOp {
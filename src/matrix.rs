@@ -1,6 +1,9 @@
 //! 2DMatrix and transforms.
-use crate::error::expect_mem_err;
-use crate::geometry::Point;
+use std::ops::{Mul, MulAssign};
+
+use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::geometry::{BoxD, Point, PointD, RectD};
+use crate::path::Path;
 
 pub(in crate) use self::private::Matrix2DOp;
 mod private {
@@ -33,21 +36,24 @@ mod private {
 pub struct Matrix2D([f64; ffi::BLMatrix2DValue::BL_MATRIX2D_VALUE_COUNT as usize]);
 
 impl Matrix2D {
+    /// The identity matrix.
+    pub const IDENTITY: Matrix2D = Matrix2D::identity();
+
     /// Creates a new matrix.
     #[inline]
-    pub fn new(m00: f64, m01: f64, m10: f64, m11: f64, m20: f64, m21: f64) -> Self {
+    pub const fn new(m00: f64, m01: f64, m10: f64, m11: f64, m20: f64, m21: f64) -> Self {
         Matrix2D([m00, m01, m10, m11, m20, m21])
     }
 
     /// Creates an identity matrix.
     #[inline]
-    pub fn identity() -> Matrix2D {
+    pub const fn identity() -> Matrix2D {
         Matrix2D([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
     }
 
     /// Creates a translation matrix.
     #[inline]
-    pub fn translation(x: f64, y: f64) -> Matrix2D {
+    pub const fn translation(x: f64, y: f64) -> Matrix2D {
         Matrix2D([1.0, 0.0, 0.0, 1.0, x, y])
     }
 
@@ -60,7 +66,7 @@ impl Matrix2D {
 
     /// Creates a scaling matrix.
     #[inline]
-    pub fn scaling(x: f64, y: f64) -> Matrix2D {
+    pub const fn scaling(x: f64, y: f64) -> Matrix2D {
         Matrix2D([x, 0.0, 0.0, y, 0.0, 0.0])
     }
 
@@ -182,12 +188,186 @@ impl Matrix2D {
         let p = p.into_f64();
         self.reset_to_rotation(angle, p[0], p[1]);
     }
+
+    /// Transforms the given point by this matrix.
+    #[inline]
+    pub fn map_point(&self, x: f64, y: f64) -> PointD {
+        let m = &self.0;
+        PointD {
+            x: x * m[0] + y * m[2] + m[4],
+            y: x * m[1] + y * m[3] + m[5],
+        }
+    }
+
+    /// Transforms the given vector by the linear part of this matrix,
+    /// ignoring the translation.
     #[inline]
-    // Inverted src is writtinen into dst
-    pub fn invert(dst: &mut Matrix2D, src: &Matrix2D) {               
-        let src_p = src.0.as_ptr() as *const ffi::BLMatrix2D;
-        let dst_p = dst.0.as_ptr() as *mut ffi::BLMatrix2D;
-        unsafe{ ffi::blMatrix2DInvert(dst_p, src_p)};
+    pub fn map_vector(&self, x: f64, y: f64) -> PointD {
+        let m = &self.0;
+        PointD {
+            x: x * m[0] + y * m[2],
+            y: x * m[1] + y * m[3],
+        }
+    }
+
+    /// Returns the bounding box of the given box transformed by this matrix.
+    pub fn map_box(&self, b: &BoxD) -> BoxD {
+        let corners = [
+            self.map_point(b.x0, b.y0),
+            self.map_point(b.x1, b.y0),
+            self.map_point(b.x0, b.y1),
+            self.map_point(b.x1, b.y1),
+        ];
+        let mut result = BoxD::new(corners[0].x, corners[0].y, corners[0].x, corners[0].y);
+        for p in &corners[1..] {
+            result.x0 = result.x0.min(p.x);
+            result.y0 = result.y0.min(p.y);
+            result.x1 = result.x1.max(p.x);
+            result.y1 = result.y1.max(p.y);
+        }
+        result
+    }
+
+    /// Returns the bounding rectangle of the given rectangle transformed by
+    /// this matrix.
+    pub fn map_rect(&self, r: &RectD) -> RectD {
+        let b = self.map_box(&BoxD::new(r.x, r.y, r.x + r.w, r.y + r.h));
+        RectD::new(b.x0, b.y0, b.x1 - b.x0, b.y1 - b.y0)
+    }
+
+    /// Returns a copy of the given path transformed by this matrix.
+    pub fn map_path(&self, path: &Path) -> Path {
+        let mut path = path.clone();
+        path.transform(self);
+        path
+    }
+
+    /// Returns the determinant of the 2x2 linear part of this matrix.
+    #[inline]
+    pub fn determinant(&self) -> f64 {
+        self.0[0] * self.0[3] - self.0[1] * self.0[2]
+    }
+
+    /// Returns the inverse of this matrix, or `None` if it is not invertible.
+    pub fn inverted(&self) -> Option<Matrix2D> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let [m00, m01, m10, m11, m20, m21] = self.0;
+        let (i00, i01, i10, i11) = (m11 / det, -m01 / det, -m10 / det, m00 / det);
+        Some(Matrix2D([
+            i00,
+            i01,
+            i10,
+            i11,
+            -(m20 * i00 + m21 * i10),
+            -(m20 * i01 + m21 * i11),
+        ]))
+    }
+
+    /// Writes the inverse of `src` into `dst`.
+    ///
+    /// Returns an error and leaves `dst` untouched if `src` is not
+    /// invertible.
+    #[inline]
+    pub fn invert(dst: &mut Matrix2D, src: &Matrix2D) -> Result<()> {
+        unsafe {
+            errcode_to_result(ffi::blMatrix2DInvert(
+                dst as *mut _ as *mut _,
+                src as *const _ as *const _,
+            ))
+        }
+    }
+
+    /// Splits this matrix into its translation, rotation, scaling and
+    /// skewing, see [`Decomposition`]. Returns `None` if the matrix is not
+    /// invertible.
+    ///
+    /// [`Decomposition`]: struct.Decomposition.html
+    pub fn decompose(&self) -> Option<Decomposition> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let [m00, m01, m10, m11, m20, m21] = self.0;
+        let scale_x = m00.hypot(m01);
+        let rotation = m01.atan2(m00);
+        let (sin, cos) = rotation.sin_cos();
+        // the second row relative to the rotated axes
+        let shear = m10 * cos + m11 * sin;
+        let scale_y = det / scale_x;
+        Some(Decomposition {
+            translation: PointD::new(m20, m21),
+            rotation,
+            scale: PointD::new(scale_x, scale_y),
+            skew: (shear / scale_y).atan(),
+        })
+    }
+}
+
+/// The components of an invertible [`Matrix2D`] as returned by
+/// [`Matrix2D::decompose`].
+///
+/// The matrix is equal to scaling, skewing along the x axis, rotating and
+/// translating a point in that order. A negative `scale.y` marks a mirrored
+/// matrix.
+///
+/// [`Matrix2D`]: struct.Matrix2D.html
+/// [`Matrix2D::decompose`]: struct.Matrix2D.html#method.decompose
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Decomposition {
+    pub translation: PointD,
+    /// The rotation angle in radians.
+    pub rotation: f64,
+    pub scale: PointD,
+    /// The skew angle along the x axis in radians.
+    pub skew: f64,
+}
+
+impl Decomposition {
+    /// Composes the components into a matrix again.
+    pub fn to_matrix(&self) -> Matrix2D {
+        let (sin, cos) = self.rotation.sin_cos();
+        Matrix2D::scaling(self.scale.x, self.scale.y)
+            * Matrix2D::new(1.0, 0.0, self.skew.tan(), 1.0, 0.0, 0.0)
+            * Matrix2D::sin_cos(sin, cos, self.translation.x, self.translation.y)
+    }
+}
+
+/// Multiplies two matrices, the resulting matrix transforms a point by the
+/// left matrix first and by the right one afterwards.
+impl Mul for Matrix2D {
+    type Output = Matrix2D;
+
+    #[inline]
+    fn mul(self, rhs: Matrix2D) -> Matrix2D {
+        let [a00, a01, a10, a11, a20, a21] = self.0;
+        let [b00, b01, b10, b11, b20, b21] = rhs.0;
+        Matrix2D([
+            a00 * b00 + a01 * b10,
+            a00 * b01 + a01 * b11,
+            a10 * b00 + a11 * b10,
+            a10 * b01 + a11 * b11,
+            a20 * b00 + a21 * b10 + b20,
+            a20 * b01 + a21 * b11 + b21,
+        ])
+    }
+}
+
+impl MulAssign for Matrix2D {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Matrix2D) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<PointD> for Matrix2D {
+    type Output = PointD;
+
+    #[inline]
+    fn mul(self, rhs: PointD) -> PointD {
+        self.map_point(rhs.x, rhs.y)
     }
 }
 
@@ -354,8 +534,57 @@ mod tests {
     fn test_add() {
         let mut m = Matrix2D::scaling(3., 1.);
         let mut m2 = Matrix2D::identity();
-        Matrix2D::invert(&mut m2, &m);
+        Matrix2D::invert(&mut m2, &m).unwrap();
         m.transform(&m2);
         assert_eq!(m, Matrix2D::identity());
     }
+
+    fn assert_close(a: &Matrix2D, b: &Matrix2D) {
+        for (x, y) in a.0.iter().zip(&b.0) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_linear_algebra() {
+        let a = Matrix2D::new(2.0, 1.0, -1.0, 3.0, 5.0, -7.0);
+        let b = Matrix2D::rotation(0.5, 1.0, 2.0);
+        let mut expected = a;
+        expected.post_transform(&b);
+        assert_close(&(a * b), &expected);
+        let p = (a * b) * PointD::new(3.0, 4.0);
+        let q = b * (a * PointD::new(3.0, 4.0));
+        assert!((p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9);
+
+        let inv = a.inverted().unwrap();
+        assert_close(&(a * inv), &Matrix2D::identity());
+        let mut dst = Matrix2D::identity();
+        Matrix2D::invert(&mut dst, &a).unwrap();
+        assert_close(&dst, &inv);
+        assert!(Matrix2D::scaling(0.0, 1.0).inverted().is_none());
+        assert!(Matrix2D::invert(&mut dst, &Matrix2D::scaling(0.0, 1.0)).is_err());
+
+        let parts = (Matrix2D::skewing(0.3, 0.0) * a).decompose().unwrap();
+        assert_close(&parts.to_matrix(), &(Matrix2D::skewing(0.3, 0.0) * a));
+        let parts = Matrix2D::new(-1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+            .decompose()
+            .unwrap();
+        assert!(parts.scale.y < 0.0);
+        assert!(Matrix2D::scaling(0.0, 1.0).decompose().is_none());
+
+        let r = Matrix2D::rotation(std::f64::consts::FRAC_PI_2, 0.0, 0.0)
+            .map_rect(&RectD::new(0.0, 0.0, 2.0, 1.0));
+        assert!((r.x + 1.0).abs() < 1e-9 && r.y.abs() < 1e-9);
+        assert!((r.w - 1.0).abs() < 1e-9 && (r.h - 2.0).abs() < 1e-9);
+
+        let mut path = Path::new();
+        path.move_to(1.0, 1.0);
+        path.line_to(2.0, 3.0);
+        let mapped = Matrix2D::translation(1.0, 0.0).map_path(&path);
+        assert_eq!(
+            mapped.bounding_box().unwrap(),
+            BoxD::new(2.0, 1.0, 3.0, 3.0)
+        );
+        assert_eq!(path.bounding_box().unwrap(), BoxD::new(1.0, 1.0, 2.0, 3.0));
+    }
 }
\ No newline at end of file
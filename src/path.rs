@@ -1,4 +1,27 @@
 #![allow(clippy::too_many_arguments)]
+mod bounds;
+mod dash;
+mod figures;
+mod fill_rule;
+mod flatten;
+#[cfg(feature = "kurbo")]
+mod kurbo_crate;
+#[cfg(feature = "lyon_path")]
+mod lyon_crate;
+mod measure;
+mod offset;
+mod rough;
+mod segments;
+mod sink;
+mod spans;
+mod stroked;
+mod svg;
+
+pub use self::measure::PathMeasure;
+pub use self::rough::RoughOptions;
+pub use self::segments::{PathFigure, PathFigures, PathSegment, PathSegments};
+pub use self::sink::PathSink;
+
 use bitflags::bitflags;
 
 use std::borrow::Borrow;
@@ -6,11 +29,11 @@ use std::ops::{self, Range};
 use std::{fmt, mem, ptr, slice};
 
 use crate::array::Array;
-use crate::error::{errcode_to_result, expect_mem_err, OutOfMemory};
+use crate::error::{errcode_to_result, expect_mem_err, out_of_memory, OutOfMemory};
 use crate::geometry::{BoxD, FillRule, Geometry, GeometryDirection, HitTest, Point, PointD, RectD};
 use crate::matrix::Matrix2D;
 use crate::util::bl_range;
-use crate::variant::WrappedBlCore;
+use crate::variant::{DeepClone, WrappedBlCore};
 
 use ffi::BLPathCmd::*;
 bl_enum! {
@@ -207,6 +230,29 @@ impl StrokeOptions {
         unsafe { u32::from(self.core.__bindgen_anon_1.__bindgen_anon_1.transformOrder).into() }
     }
 
+    #[inline]
+    pub fn set_dash_offset(&mut self, offset: f64) {
+        self.core.dashOffset = offset;
+    }
+
+    #[inline]
+    pub fn set_dash_array(&mut self, dash_array: &Array<f64>) {
+        unsafe { ffi::blArrayAssignWeak(&mut self.core.dashArray, dash_array.core()) };
+    }
+
+    #[inline]
+    pub fn set_width(&mut self, width: f64) {
+        self.core.width = width;
+    }
+
+    #[inline]
+    pub fn set_join(&mut self, join: StrokeJoin) {
+        #[allow(unused_unsafe)] // nightly has no problem with copy-union writes, stable does though
+        unsafe {
+            self.core.__bindgen_anon_1.__bindgen_anon_1.join = join as u8;
+        }
+    }
+
     #[inline]
     pub fn set_caps(&mut self, cap: StrokeCap) {
         #[allow(unused_unsafe)] // nightly has no problem with copy-union writes, stable does though
@@ -286,9 +332,12 @@ impl Path {
     ///
     /// Panics if blend2d returns an
     /// [`OutOfMemory`](../error/enum.Error.html#variant.OutOfMemory) error
+    /// with the default [`OomPolicy`](../error/enum.OomPolicy.html).
     #[inline]
     pub fn reserve(&mut self, n: usize) {
-        self.try_reserve(n).expect("memory allocation failed");
+        if self.try_reserve(n).is_err() {
+            out_of_memory();
+        }
     }
 
     /// Reserves capacity for at least n items.
@@ -302,6 +351,42 @@ impl Path {
         unsafe { self.impl_().__bindgen_anon_1.view.size }
     }
 
+    /// Returns the number of paths sharing the vertex data of this path,
+    /// including this one.
+    ///
+    /// Clones share the vertices until one of the paths is modified.
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.impl_().ref_count()
+    }
+
+    /// Checks whether the vertex data of this path is shared with a clone, in
+    /// which case the next modification copies all vertices first, no matter
+    /// how few of them it touches.
+    ///
+    /// An empty path created with [`new`] is never shared.
+    ///
+    /// [`new`]: #method.new
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        !self.is_none() && self.ref_count() > 1
+    }
+
+    /// Makes sure the vertices of this path are referenced by it only, by
+    /// copying them if it [`is_shared`], and returns the path for mutation.
+    ///
+    /// This is handy when a clone is the starting point of another path, the
+    /// vertices are then copied right away instead of by whichever call
+    /// happens to modify the path first.
+    ///
+    /// [`is_shared`]: #method.is_shared
+    pub fn make_mut(&mut self) -> &mut Path {
+        if self.is_shared() {
+            *self = self.clone_deep();
+        }
+        self
+    }
+
     /// Returns the currently allocated capacity of the path.
     #[inline]
     pub fn capacity(&self) -> usize {
@@ -650,6 +735,66 @@ impl Path {
     }
 }
 
+/// Variants of the path building functions that return a failed allocation
+/// instead of handling it according to the [`OomPolicy`].
+///
+/// [`OomPolicy`]: ../error/enum.OomPolicy.html
+impl Path {
+    #[inline]
+    pub fn try_move_to(&mut self, x: f64, y: f64) -> std::result::Result<(), OutOfMemory> {
+        unsafe { OutOfMemory::from_errcode(ffi::blPathMoveTo(self.core_mut(), x, y)) }
+    }
+
+    #[inline]
+    pub fn try_line_to(&mut self, x: f64, y: f64) -> std::result::Result<(), OutOfMemory> {
+        unsafe { OutOfMemory::from_errcode(ffi::blPathLineTo(self.core_mut(), x, y)) }
+    }
+
+    #[inline]
+    pub fn try_poly_to(&mut self, poly: &[PointD]) -> std::result::Result<(), OutOfMemory> {
+        unsafe {
+            OutOfMemory::from_errcode(ffi::blPathPolyTo(
+                self.core_mut(),
+                poly.as_ptr() as *const _,
+                poly.len(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn try_quad_to(
+        &mut self,
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+    ) -> std::result::Result<(), OutOfMemory> {
+        unsafe { OutOfMemory::from_errcode(ffi::blPathQuadTo(self.core_mut(), x1, y1, x2, y2)) }
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn try_cubic_to(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) -> std::result::Result<(), OutOfMemory> {
+        unsafe { OutOfMemory::from_errcode(ffi::blPathCubicTo(self.core_mut(), x1, y1, x2, y2, x3, y3)) }
+    }
+
+    #[inline]
+    pub fn try_close(&mut self) -> std::result::Result<(), OutOfMemory> {
+        unsafe { OutOfMemory::from_errcode(ffi::blPathClose(self.core_mut())) }
+    }
+
+    #[inline]
+    pub fn try_add_path(&mut self, other: &Path) -> std::result::Result<(), OutOfMemory> {
+        unsafe {
+            OutOfMemory::from_errcode(ffi::blPathAddPath(
+                self.core_mut(),
+                other.core(),
+                ptr::null(),
+            ))
+        }
+    }
+}
+
 impl Path {
     /// Adds a [`Geometry`] to the path.
     pub fn add_geometry<'m, T, M>(&mut self, g: &T, matrix: M, dir: GeometryDirection)
@@ -997,3 +1142,27 @@ impl Clone for Path {
         Self::from_core(self.init_weak())
     }
 }
+
+#[cfg(test)]
+mod test_path {
+    use crate::path::Path;
+
+    #[test]
+    fn test_try_variants() {
+        let mut other = Path::new();
+        other.move_to(20.0, 0.0);
+        other.line_to(30.0, 0.0);
+
+        let mut path = Path::new();
+        path.try_move_to(0.0, 0.0).unwrap();
+        path.try_line_to(10.0, 0.0).unwrap();
+        path.try_close().unwrap();
+        path.try_add_path(&other).unwrap();
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(10.0, 0.0);
+        expected.close();
+        expected.add_path(&other);
+        assert_eq!(path, expected);
+    }
+}
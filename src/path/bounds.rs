@@ -0,0 +1,182 @@
+use crate::geometry::{BoxD, PointD};
+use crate::matrix::Matrix2D;
+
+use super::{Path, PathCommand};
+
+impl Path {
+    /// Returns the bounding box this path would have after being transformed
+    /// by the given matrix, or `None` if the path has no vertices.
+    ///
+    /// This is equivalent to cloning the path, transforming it and calling
+    /// [`bounding_box`] on the result, but doesn't allocate. Curves are
+    /// bounded exactly by transforming their control points and solving for
+    /// the extrema of the transformed curve.
+    ///
+    /// [`bounding_box`]: #method.bounding_box
+    pub fn bounding_box_transformed(&self, m: &Matrix2D) -> Option<BoxD> {
+        let cmds = self.command_data();
+        let vtx = self.vertex_data();
+        let mut bounds = Bounds::new();
+        let mut last = None;
+        let mut i = 0;
+        while i < cmds.len() {
+            match PathCommand::from(u32::from(cmds[i])) {
+                PathCommand::Move | PathCommand::On => {
+                    let p = m.map_point(vtx[i].x, vtx[i].y);
+                    bounds.add(p);
+                    last = Some(p);
+                    i += 1;
+                },
+                PathCommand::Quad if i + 1 < cmds.len() => {
+                    let p1 = m.map_point(vtx[i].x, vtx[i].y);
+                    let p2 = m.map_point(vtx[i + 1].x, vtx[i + 1].y);
+                    bounds.add_quad(last.unwrap_or(p1), p1, p2);
+                    last = Some(p2);
+                    i += 2;
+                },
+                PathCommand::Cubic if i + 2 < cmds.len() => {
+                    let p1 = m.map_point(vtx[i].x, vtx[i].y);
+                    let p2 = m.map_point(vtx[i + 1].x, vtx[i + 1].y);
+                    let p3 = m.map_point(vtx[i + 2].x, vtx[i + 2].y);
+                    bounds.add_cubic(last.unwrap_or(p1), p1, p2, p3);
+                    last = Some(p3);
+                    i += 3;
+                },
+                PathCommand::Close => i += 1,
+                // truncated curve, the path is invalid from here on
+                PathCommand::Quad | PathCommand::Cubic => break,
+            }
+        }
+        bounds.finish()
+    }
+}
+
+struct Bounds(Option<BoxD>);
+
+impl Bounds {
+    fn new() -> Self {
+        Bounds(None)
+    }
+
+    fn add(&mut self, p: PointD) {
+        let b = self.0.get_or_insert(BoxD::new(p.x, p.y, p.x, p.y));
+        b.x0 = b.x0.min(p.x);
+        b.y0 = b.y0.min(p.y);
+        b.x1 = b.x1.max(p.x);
+        b.y1 = b.y1.max(p.y);
+    }
+
+    fn add_quad(&mut self, p0: PointD, p1: PointD, p2: PointD) {
+        self.add(p2);
+        // the derivative is linear, so there is at most one extremum per axis
+        for t in [
+            quad_extremum(p0.x, p1.x, p2.x),
+            quad_extremum(p0.y, p1.y, p2.y),
+        ]
+        .iter()
+        .flatten()
+        {
+            let mt = 1.0 - t;
+            self.add(PointD {
+                x: mt * mt * p0.x + 2.0 * mt * t * p1.x + t * t * p2.x,
+                y: mt * mt * p0.y + 2.0 * mt * t * p1.y + t * t * p2.y,
+            });
+        }
+    }
+
+    fn add_cubic(&mut self, p0: PointD, p1: PointD, p2: PointD, p3: PointD) {
+        self.add(p3);
+        let tx = cubic_extrema(p0.x, p1.x, p2.x, p3.x);
+        let ty = cubic_extrema(p0.y, p1.y, p2.y, p3.y);
+        for t in tx.iter().chain(ty.iter()).flatten() {
+            let mt = 1.0 - t;
+            let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            self.add(PointD {
+                x: a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+                y: a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+            });
+        }
+    }
+
+    fn finish(self) -> Option<BoxD> {
+        self.0
+    }
+}
+
+#[inline]
+fn in_unit_interval(t: f64) -> Option<f64> {
+    if t > 0.0 && t < 1.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// The parameter of the extremum of a quadratic bezier in one axis.
+fn quad_extremum(p0: f64, p1: f64, p2: f64) -> Option<f64> {
+    let denom = p0 - 2.0 * p1 + p2;
+    if denom == 0.0 {
+        None
+    } else {
+        in_unit_interval((p0 - p1) / denom)
+    }
+}
+
+/// The parameters of the extrema of a cubic bezier in one axis.
+fn cubic_extrema(p0: f64, p1: f64, p2: f64, p3: f64) -> [Option<f64>; 2] {
+    // derivative divided by 3: a*t^2 + b*t + c
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    if a.abs() < 1e-12 {
+        if b == 0.0 {
+            return [None, None];
+        }
+        return [in_unit_interval(-c / b), None];
+    }
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 {
+        return [None, None];
+    }
+    let sq = disc.sqrt();
+    [
+        in_unit_interval((-b + sq) / (2.0 * a)),
+        in_unit_interval((-b - sq) / (2.0 * a)),
+    ]
+}
+
+#[cfg(test)]
+mod test_bounds {
+    use crate::matrix::{Matrix2D, MatrixTransform};
+    use crate::path::Path;
+
+    fn assert_box_eq(a: crate::geometry::BoxD, b: crate::geometry::BoxD) {
+        let eps = 1e-9;
+        assert!((a.x0 - b.x0).abs() < eps, "{:?} != {:?}", a, b);
+        assert!((a.y0 - b.y0).abs() < eps, "{:?} != {:?}", a, b);
+        assert!((a.x1 - b.x1).abs() < eps, "{:?} != {:?}", a, b);
+        assert!((a.y1 - b.y1).abs() < eps, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_bounding_box_transformed() {
+        let mut path = Path::new();
+        path.move_to(10.0, 10.0);
+        path.quad_to(50.0, -20.0, 90.0, 10.0);
+        path.cubic_to(120.0, 40.0, 60.0, 90.0, 10.0, 60.0);
+        path.close();
+
+        let mut m = Matrix2D::identity();
+        m.rotate(0.7);
+        m.scale(2.0, 0.5);
+        m.translate(3.0, -4.0);
+
+        let mut transformed = path.clone();
+        transformed.transform(&m);
+        assert_box_eq(
+            path.bounding_box_transformed(&m).unwrap(),
+            transformed.bounding_box().unwrap(),
+        );
+        assert_eq!(Path::new().bounding_box_transformed(&m), None);
+    }
+}
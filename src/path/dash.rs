@@ -0,0 +1,125 @@
+use super::flatten::flatten_figures;
+use super::{Path, PathMeasure, StrokeOptions};
+
+impl Path {
+    /// Returns the dash phase at the end of this path when it is stroked with
+    /// the dash array of the given [`StrokeOptions`], starting at
+    /// `start_phase`.
+    ///
+    /// The result can be used as the dash offset of a subsequent stroke so
+    /// that its dash pattern continues seamlessly where this path ended, e.g.
+    /// when a polyline is split across tiles. The lengths of all figures are
+    /// accumulated as if they formed a single continuous polyline, curves are
+    /// measured on their flattened approximation.
+    ///
+    /// If the dash array is empty or has no positive length, `start_phase` is
+    /// returned unchanged.
+    pub fn dash_phase_end(&self, options: &StrokeOptions, start_phase: f64) -> f64 {
+        let dashes = options.dash_array();
+        let mut pattern_len: f64 = dashes.iter().sum();
+        // an odd number of dashes is repeated to form an even pattern
+        if dashes.len() % 2 == 1 {
+            pattern_len *= 2.0;
+        }
+        if pattern_len <= 0.0 || !pattern_len.is_finite() {
+            return start_phase;
+        }
+        let length: f64 = flatten_figures(self)
+            .iter()
+            .map(|figure| figure.length())
+            .sum();
+        (start_phase + length).rem_euclid(pattern_len)
+    }
+
+    /// Applies the dash array and offset of the given [`StrokeOptions`] to
+    /// this path and returns the resulting dashes as separate open figures.
+    ///
+    /// Unlike dashing during stroking this makes the dashes available as
+    /// geometry, e.g. for custom dash rendering or exporting dashed outlines.
+    /// Curves are flattened with the default [`ApproximationOptions`], use
+    /// [`PathMeasure::dashed`] for other options. As with
+    /// [`dash_phase_end`], the pattern continues across figures.
+    ///
+    /// [`StrokeOptions`]: struct.StrokeOptions.html
+    /// [`ApproximationOptions`]: struct.ApproximationOptions.html
+    /// [`PathMeasure::dashed`]: struct.PathMeasure.html#method.dashed
+    /// [`dash_phase_end`]: #method.dash_phase_end
+    pub fn dashed(&self, options: &StrokeOptions) -> Path {
+        PathMeasure::new(self, &Default::default())
+            .dashed(options.dash_array(), options.dash_offset())
+    }
+}
+
+#[cfg(test)]
+mod test_dash {
+    use crate::array::Array;
+    use crate::path::{Path, StrokeOptions};
+
+    #[test]
+    fn test_dash_phase_end() {
+        let mut dashes = Array::<f64>::new();
+        dashes.extend_from_slice(&[10.0, 5.0]);
+        let mut options = StrokeOptions::new();
+        options.set_dash_array(&dashes);
+
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(20.0, 0.0);
+        path.line_to(20.0, 20.0);
+        assert_eq!(path.dash_phase_end(&options, 0.0), 10.0);
+        assert_eq!(path.dash_phase_end(&options, 7.0), 2.0);
+
+        options.set_dash_array(&Array::new());
+        assert_eq!(path.dash_phase_end(&options, 3.0), 3.0);
+    }
+
+    #[test]
+    fn test_dashed() {
+        let mut dashes = Array::<f64>::new();
+        dashes.extend_from_slice(&[10.0, 5.0]);
+        let mut options = StrokeOptions::new();
+        options.set_dash_array(&dashes);
+        options.set_dash_offset(5.0);
+
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(30.0, 0.0);
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(5.0, 0.0);
+        expected.move_to(10.0, 0.0);
+        expected.line_to(20.0, 0.0);
+        expected.move_to(25.0, 0.0);
+        expected.line_to(30.0, 0.0);
+        assert_eq!(path.dashed(&options), expected);
+
+        options.set_dash_array(&Array::new());
+        assert_eq!(path.dashed(&options), path);
+
+        // a pattern far below the precision of the path is not applied
+        options.set_dash_array(&Array::from(&[1e-20, 1e-20][..]));
+        assert_eq!(path.dashed(&options), path);
+    }
+
+    #[test]
+    fn test_dashed_figures() {
+        let mut options = StrokeOptions::new();
+        options.set_dash_array(&Array::from(&[4.0, 2.0][..]));
+
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.move_to(0.0, 10.0);
+        path.line_to(10.0, 10.0);
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(4.0, 0.0);
+        expected.move_to(6.0, 0.0);
+        expected.line_to(10.0, 0.0);
+        expected.move_to(2.0, 10.0);
+        expected.line_to(6.0, 10.0);
+        expected.move_to(8.0, 10.0);
+        expected.line_to(10.0, 10.0);
+        assert_eq!(path.dashed(&options), expected);
+    }
+}
@@ -0,0 +1,98 @@
+use std::ops::Range;
+
+use super::{Path, PathCommand};
+
+impl Path {
+    /// Returns the number of figures in this path.
+    pub fn figure_count(&self) -> usize {
+        self.figure_ranges().len()
+    }
+
+    /// Returns whether the figure with the given index ends with a close
+    /// command, or `None` if the path has no such figure.
+    ///
+    /// Open figures are still implicitly closed when the path is filled, this
+    /// only affects stroking. [`close_all_figures`] can be used to make the
+    /// implicit closing explicit.
+    ///
+    /// [`close_all_figures`]: #method.close_all_figures
+    pub fn is_figure_closed(&self, index: usize) -> Option<bool> {
+        let range = self.figure_ranges().into_iter().nth(index)?;
+        Some(self.is_range_closed(&range))
+    }
+
+    /// Closes all open figures of this path, so that stroking the path
+    /// matches the outline that is filled.
+    pub fn close_all_figures(&mut self) {
+        let ranges = self.figure_ranges();
+        if ranges.iter().all(|range| self.is_range_closed(range)) {
+            return;
+        }
+        let mut result = Path::with_capacity(self.len() + ranges.len());
+        for range in ranges {
+            let closed = self.is_range_closed(&range);
+            result.add_path_range(self, range);
+            if !closed {
+                result.close();
+            }
+        }
+        *self = result;
+    }
+
+    /// Returns the vertex ranges of all figures in this path.
+    pub(super) fn figure_ranges(&self) -> Vec<Range<usize>> {
+        let cmds = self.command_data();
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for (i, &cmd) in cmds.iter().enumerate() {
+            match PathCommand::from(u32::from(cmd)) {
+                PathCommand::Move if i > start => {
+                    ranges.push(start..i);
+                    start = i;
+                },
+                PathCommand::Close => {
+                    ranges.push(start..i + 1);
+                    start = i + 1;
+                },
+                _ => (),
+            }
+        }
+        if start < cmds.len() {
+            ranges.push(start..cmds.len());
+        }
+        ranges
+    }
+
+    #[inline]
+    pub(super) fn is_range_closed(&self, range: &Range<usize>) -> bool {
+        let cmds = self.command_data();
+        range.end > range.start
+            && PathCommand::from(u32::from(cmds[range.end - 1])) == PathCommand::Close
+    }
+}
+
+#[cfg(test)]
+mod test_figures {
+    use crate::path::Path;
+
+    #[test]
+    fn test_close_all_figures() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.line_to(10.0, 10.0);
+        path.close();
+        path.move_to(20.0, 0.0);
+        path.line_to(30.0, 0.0);
+        path.line_to(30.0, 10.0);
+        assert_eq!(path.figure_count(), 2);
+        assert_eq!(path.is_figure_closed(0), Some(true));
+        assert_eq!(path.is_figure_closed(1), Some(false));
+        assert_eq!(path.is_figure_closed(2), None);
+
+        path.close_all_figures();
+        assert_eq!(path.figure_count(), 2);
+        assert_eq!(path.is_figure_closed(1), Some(true));
+        assert_eq!(path.len(), 8);
+    }
+}
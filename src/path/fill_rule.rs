@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+use crate::geometry::{FillRule, PointD};
+
+use super::flatten::flatten_figures;
+use super::{Path, PathReverseMode};
+
+/// A figure of a path approximated by a closed polygon.
+struct Figure {
+    range: Range<usize>,
+    polygon: Vec<PointD>,
+    area: f64,
+    sample: Option<PointD>,
+}
+
+impl Figure {
+    fn new(range: Range<usize>, polygon: Vec<PointD>) -> Self {
+        let area = signed_area(&polygon);
+        let sample = inner_sample_point(&polygon, area);
+        Figure {
+            range,
+            polygon,
+            area,
+            sample,
+        }
+    }
+
+    /// The winding direction of the figure, `1` for positive and `-1` for
+    /// negative signed area.
+    #[inline]
+    fn orientation(&self) -> i32 {
+        if self.area < 0.0 {
+            -1
+        } else {
+            1
+        }
+    }
+}
+
+impl Path {
+    /// Rewrites the winding of this path's figures so that filling it with
+    /// the `to` [`FillRule`] produces the same result as filling the original
+    /// path with the `from` [`FillRule`].
+    ///
+    /// Converting from [`FillRule::EvenOdd`] to [`FillRule::NonZero`] reverses
+    /// figures so that nested figures alternate their direction. Converting
+    /// from [`FillRule::NonZero`] to [`FillRule::EvenOdd`] drops figures whose
+    /// boundary does not change the filled state, like a figure nested in
+    /// another figure of the same direction.
+    ///
+    /// Containment is determined by flattening each figure and testing a point
+    /// just inside of it against all other figures, so figures are expected
+    /// to not intersect each other. Open figures are treated as implicitly
+    /// closed, the same way they are when filled.
+    pub fn normalize_fill_rule(&mut self, from: FillRule, to: FillRule) {
+        if from == to || self.is_empty() {
+            return;
+        }
+        let figures: Vec<_> = flatten_figures(self)
+            .into_iter()
+            .map(|figure| Figure::new(figure.range, figure.points))
+            .collect();
+        let mut result = Path::with_capacity(self.len());
+        for (idx, figure) in figures.iter().enumerate() {
+            let sample = match figure.sample {
+                Some(sample) => sample,
+                // degenerate figures don't contribute to the fill
+                None => continue,
+            };
+            let containing = figures
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != idx)
+                .filter(|(_, other)| contains_point(&other.polygon, &sample));
+            match to {
+                FillRule::NonZero => {
+                    let depth = containing.count();
+                    let desired = if depth % 2 == 0 { 1 } else { -1 };
+                    if figure.orientation() == desired {
+                        result.add_path_range(self, figure.range.clone());
+                    } else {
+                        result.add_reversed_path_range(
+                            self,
+                            figure.range.clone(),
+                            PathReverseMode::Complete,
+                        );
+                    }
+                },
+                FillRule::EvenOdd => {
+                    let winding_out: i32 = containing.map(|(_, other)| other.orientation()).sum();
+                    let winding_in = winding_out + figure.orientation();
+                    if (winding_in != 0) != (winding_out != 0) {
+                        result.add_path_range(self, figure.range.clone());
+                    }
+                },
+            }
+        }
+        *self = result;
+    }
+}
+
+pub(super) fn signed_area(polygon: &[PointD]) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        * 0.5
+}
+
+/// Returns a point that lies just inside of the polygon next to its longest
+/// edge, or `None` if the polygon has no area.
+fn inner_sample_point(polygon: &[PointD], area: f64) -> Option<PointD> {
+    if polygon.len() < 3 || area == 0.0 || !area.is_finite() {
+        return None;
+    }
+    let n = polygon.len();
+    let (a, b) = (0..n)
+        .map(|i| (polygon[i], polygon[(i + 1) % n]))
+        .max_by(|(a0, b0), (a1, b1)| {
+            let l0 = (b0.x - a0.x).hypot(b0.y - a0.y);
+            let l1 = (b1.x - a1.x).hypot(b1.y - a1.y);
+            l0.partial_cmp(&l1).unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len = dx.hypot(dy);
+    if len == 0.0 {
+        return None;
+    }
+    // the interior lies to the left of each edge for a positive signed area
+    let eps = len * 1e-6 * area.signum();
+    Some(PointD {
+        x: (a.x + b.x) * 0.5 - dy / len * eps,
+        y: (a.y + b.y) * 0.5 + dx / len * eps,
+    })
+}
+
+/// Even-odd point in polygon test.
+fn contains_point(polygon: &[PointD], p: &PointD) -> bool {
+    let n = polygon.len();
+    let mut inside = false;
+    let mut j = n.wrapping_sub(1);
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod test_fill_rule {
+    use crate::geometry::{FillRule, HitTest, PointD};
+    use crate::path::Path;
+
+    fn square(path: &mut Path, x: f64, y: f64, size: f64, reversed: bool) {
+        path.move_to(x, y);
+        if reversed {
+            path.line_to(x, y + size);
+            path.line_to(x + size, y + size);
+            path.line_to(x + size, y);
+        } else {
+            path.line_to(x + size, y);
+            path.line_to(x + size, y + size);
+            path.line_to(x, y + size);
+        }
+        path.close();
+    }
+
+    #[test]
+    fn test_even_odd_to_non_zero() {
+        let mut path = Path::new();
+        square(&mut path, 0.0, 0.0, 100.0, false);
+        square(&mut path, 25.0, 25.0, 50.0, false);
+        let hole = PointD { x: 50.0, y: 50.0 };
+        assert_eq!(path.hit_test(&hole, FillRule::EvenOdd), HitTest::Out);
+        path.normalize_fill_rule(FillRule::EvenOdd, FillRule::NonZero);
+        assert_eq!(path.hit_test(&hole, FillRule::NonZero), HitTest::Out);
+    }
+
+    #[test]
+    fn test_non_zero_to_even_odd() {
+        let mut path = Path::new();
+        square(&mut path, 0.0, 0.0, 100.0, false);
+        square(&mut path, 25.0, 25.0, 50.0, false);
+        let center = PointD { x: 50.0, y: 50.0 };
+        assert_eq!(path.hit_test(&center, FillRule::NonZero), HitTest::In);
+        path.normalize_fill_rule(FillRule::NonZero, FillRule::EvenOdd);
+        assert_eq!(path.hit_test(&center, FillRule::EvenOdd), HitTest::In);
+    }
+}
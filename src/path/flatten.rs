@@ -0,0 +1,305 @@
+use std::ops::Range;
+
+use crate::geometry::PointD;
+
+use super::{ApproximationOptions, FlattenMode, Path, PathCommand};
+
+/// Number of line segments a curve is approximated by if no tolerance is
+/// given.
+const CURVE_STEPS: usize = 16;
+/// Upper bound of line segments a single curve is approximated by.
+const MAX_CURVE_STEPS: usize = 1024;
+/// Maximum subdivision depth of [`Flattening::Recursive`].
+const MAX_RECURSION: u32 = 16;
+/// The tolerance used for invalid tolerances, blend2d's default.
+const DEFAULT_TOLERANCE: f64 = 0.2;
+
+/// How curves are approximated by line segments.
+#[derive(Copy, Clone, Debug)]
+pub(super) enum Flattening {
+    /// A fixed number of segments per curve.
+    Fixed(usize),
+    /// Uniformly spaced segments, as many as needed to stay within the
+    /// tolerance.
+    Uniform(f64),
+    /// Recursive subdivision until each part is flat within the tolerance.
+    Recursive(f64),
+}
+
+impl Flattening {
+    /// The flattening described by the flatten mode and tolerance of the
+    /// options.
+    pub fn from_options(options: &ApproximationOptions) -> Self {
+        let tolerance = options.flatten_tolerance;
+        let tolerance = if tolerance > 0.0 && tolerance.is_finite() {
+            tolerance
+        } else {
+            DEFAULT_TOLERANCE
+        };
+        match options.flatten_mode() {
+            FlattenMode::Default => Flattening::Uniform(tolerance),
+            FlattenMode::Recursive => Flattening::Recursive(tolerance),
+        }
+    }
+}
+
+/// A figure of a path with its curves approximated by line segments.
+pub(super) struct FlatFigure {
+    /// The vertex range of the figure in the source path.
+    pub range: Range<usize>,
+    pub points: Vec<PointD>,
+    pub closed: bool,
+}
+
+impl FlatFigure {
+    /// The length of the figure, including the closing segment if the figure
+    /// is closed.
+    pub fn length(&self) -> f64 {
+        let open: f64 = self
+            .points
+            .windows(2)
+            .map(|w| distance(w[0], w[1]))
+            .sum();
+        match (self.closed, self.points.first(), self.points.last()) {
+            (true, Some(&first), Some(&last)) => open + distance(last, first),
+            _ => open,
+        }
+    }
+}
+
+impl Path {
+    /// Returns a copy of this path with all curves approximated by line
+    /// segments, such that the approximation deviates no more than
+    /// `tolerance` from the curves.
+    ///
+    /// Uses the [`FlattenMode::Default`] flattening, a tolerance that is not
+    /// positive falls back to blend2d's default tolerance. The figures of the
+    /// resulting path are closed if they were closed in this path.
+    ///
+    /// [`FlattenMode::Default`]: enum.FlattenMode.html#variant.Default
+    pub fn flatten(&self, tolerance: f64) -> Path {
+        let mut options = ApproximationOptions::default();
+        options.set_flatten_mode(FlattenMode::Default);
+        options.flatten_tolerance = tolerance;
+        self.flatten_with(&options)
+    }
+
+    /// Like [`flatten`], but uses the flatten mode and tolerance of the given
+    /// options.
+    ///
+    /// [`flatten`]: #method.flatten
+    pub fn flatten_with(&self, options: &ApproximationOptions) -> Path {
+        let mut path = Path::new();
+        for figure in flatten_figures_with(self, Flattening::from_options(options)) {
+            let mut points = figure.points.iter();
+            if let Some(p) = points.next() {
+                path.move_to(p.x, p.y);
+            }
+            for p in points {
+                path.line_to(p.x, p.y);
+            }
+            if figure.closed {
+                path.close();
+            }
+        }
+        path
+    }
+
+    /// Approximates the curves of this path like [`flatten`] and returns the
+    /// vertices of each figure as a separate polyline.
+    ///
+    /// Closed figures don't repeat their first vertex at the end.
+    ///
+    /// [`flatten`]: #method.flatten
+    pub fn flatten_to_polylines(&self, tolerance: f64) -> Vec<Vec<PointD>> {
+        let mut options = ApproximationOptions::default();
+        options.set_flatten_mode(FlattenMode::Default);
+        options.flatten_tolerance = tolerance;
+        flatten_figures_with(self, Flattening::from_options(&options))
+            .into_iter()
+            .map(|figure| figure.points)
+            .collect()
+    }
+}
+
+/// Splits the path into its figures, approximating curves by a fixed number
+/// of line segments.
+pub(super) fn flatten_figures(path: &Path) -> Vec<FlatFigure> {
+    flatten_figures_with(path, Flattening::Fixed(CURVE_STEPS))
+}
+
+/// Splits the path into its figures, approximating curves by line segments
+/// as described by `flattening`.
+pub(super) fn flatten_figures_with(path: &Path, flattening: Flattening) -> Vec<FlatFigure> {
+    let cmds = path.command_data();
+    let vtx = path.vertex_data();
+    let mut figures = Vec::new();
+    let mut start = 0;
+    let mut points = Vec::new();
+    let mut i = 0;
+    while i < cmds.len() {
+        match PathCommand::from(u32::from(cmds[i])) {
+            PathCommand::Move => {
+                if i > start {
+                    figures.push(FlatFigure {
+                        range: start..i,
+                        points,
+                        closed: false,
+                    });
+                    points = Vec::new();
+                }
+                start = i;
+                points.push(vtx[i]);
+                i += 1;
+            },
+            PathCommand::On => {
+                points.push(vtx[i]);
+                i += 1;
+            },
+            PathCommand::Quad if i + 1 < cmds.len() => {
+                let p0 = points.last().copied().unwrap_or(vtx[i]);
+                flatten_quad(&mut points, flattening, p0, vtx[i], vtx[i + 1]);
+                i += 2;
+            },
+            PathCommand::Cubic if i + 2 < cmds.len() => {
+                let p0 = points.last().copied().unwrap_or(vtx[i]);
+                flatten_cubic(
+                    &mut points,
+                    flattening,
+                    [p0, vtx[i], vtx[i + 1], vtx[i + 2]],
+                );
+                i += 3;
+            },
+            PathCommand::Close => {
+                i += 1;
+                figures.push(FlatFigure {
+                    range: start..i,
+                    points,
+                    closed: true,
+                });
+                points = Vec::new();
+                start = i;
+            },
+            // truncated curve, the path is invalid from here on
+            PathCommand::Quad | PathCommand::Cubic => break,
+        }
+    }
+    if i > start {
+        figures.push(FlatFigure {
+            range: start..i,
+            points,
+            closed: false,
+        });
+    }
+    figures
+}
+
+#[inline]
+pub(super) fn distance(a: PointD, b: PointD) -> f64 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+/// Approximates a quadratic curve by elevating it to the equivalent cubic.
+fn flatten_quad(out: &mut Vec<PointD>, flattening: Flattening, p0: PointD, p1: PointD, p2: PointD) {
+    let elevate = |a: PointD, b: PointD| PointD {
+        x: a.x + (b.x - a.x) * (2.0 / 3.0),
+        y: a.y + (b.y - a.y) * (2.0 / 3.0),
+    };
+    flatten_cubic(out, flattening, [p0, elevate(p0, p1), elevate(p2, p1), p2]);
+}
+
+/// Appends the approximation of the cubic curve to `out`, excluding its
+/// start point.
+fn flatten_cubic(out: &mut Vec<PointD>, flattening: Flattening, curve: [PointD; 4]) {
+    let steps = match flattening {
+        Flattening::Fixed(steps) => steps,
+        Flattening::Uniform(tolerance) => {
+            // Wang's formula for the number of segments that keeps the
+            // approximation within the tolerance
+            let [p0, p1, p2, p3] = curve;
+            let dd = |a: PointD, b: PointD, c: PointD| {
+                (a.x - 2.0 * b.x + c.x).hypot(a.y - 2.0 * b.y + c.y)
+            };
+            let m = dd(p0, p1, p2).max(dd(p1, p2, p3));
+            (0.75 * m / tolerance).sqrt().ceil() as usize
+        },
+        Flattening::Recursive(tolerance) => {
+            subdivide_cubic(out, curve, tolerance, 0);
+            return;
+        },
+    };
+    let steps = steps.max(1).min(MAX_CURVE_STEPS);
+    for step in 1..=steps {
+        out.push(eval_cubic(&curve, step as f64 / steps as f64));
+    }
+}
+
+fn eval_cubic(&[p0, p1, p2, p3]: &[PointD; 4], t: f64) -> PointD {
+    let mt = 1.0 - t;
+    let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+    PointD {
+        x: a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+        y: a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+    }
+}
+
+fn subdivide_cubic(out: &mut Vec<PointD>, curve: [PointD; 4], tolerance: f64, depth: u32) {
+    let [p0, p1, p2, p3] = curve;
+    let chord = distance(p0, p3);
+    // distance of the control points from the chord
+    let deviation = |p: PointD| {
+        if chord > 0.0 {
+            ((p.x - p0.x) * (p3.y - p0.y) - (p.y - p0.y) * (p3.x - p0.x)).abs() / chord
+        } else {
+            distance(p0, p)
+        }
+    };
+    if depth >= MAX_RECURSION || deviation(p1).max(deviation(p2)) <= tolerance {
+        out.push(p3);
+        return;
+    }
+    let mid = |a: PointD, b: PointD| PointD {
+        x: (a.x + b.x) * 0.5,
+        y: (a.y + b.y) * 0.5,
+    };
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let p0123 = mid(p012, p123);
+    subdivide_cubic(out, [p0, p01, p012, p0123], tolerance, depth + 1);
+    subdivide_cubic(out, [p0123, p123, p23, p3], tolerance, depth + 1);
+}
+
+#[cfg(test)]
+mod test_flatten {
+    use crate::geometry::PointD;
+    use crate::path::{Path, PathSegment};
+
+    #[test]
+    fn test_flatten() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(50.0, 100.0, 100.0, 0.0);
+        path.close();
+        path.move_to(200.0, 0.0);
+        path.line_to(210.0, 0.0);
+
+        let coarse = path.flatten(10.0);
+        let fine = path.flatten(0.01);
+        assert!(coarse.len() < fine.len());
+        assert!(fine
+            .iter()
+            .all(|segment| !matches!(segment, PathSegment::QuadTo(..) | PathSegment::CubicTo(..))));
+        assert_eq!(fine.last_vertex(), Some(PointD::new(210.0, 0.0)));
+        assert_eq!(fine.figure_count(), 2);
+        assert_eq!(fine.is_figure_closed(0), Some(true));
+
+        let polylines = path.flatten_to_polylines(0.01);
+        assert_eq!(polylines.len(), 2);
+        assert_eq!(
+            polylines[1],
+            vec![PointD::new(200.0, 0.0), PointD::new(210.0, 0.0)]
+        );
+        // the flattened quad passes through its apex
+        assert!(polylines[0].iter().any(|p| (p.y - 50.0).abs() < 0.01));
+    }
+}
@@ -0,0 +1,82 @@
+//! Conversions between [`Path`] and the path elements of the [`kurbo`] crate.
+//!
+//! [`kurbo`]: https://docs.rs/kurbo
+use std::iter::FromIterator;
+
+use ::kurbo::{PathEl, Point};
+
+use super::{Path, PathSegment, PathSink};
+use crate::geometry::PointD;
+
+#[inline]
+fn point(p: Point) -> PointD {
+    PointD::new(p.x, p.y)
+}
+
+#[inline]
+fn kurbo_point(p: PointD) -> Point {
+    Point::new(p.x, p.y)
+}
+
+impl From<PathEl> for PathSegment {
+    #[inline]
+    fn from(el: PathEl) -> Self {
+        match el {
+            PathEl::MoveTo(p) => PathSegment::MoveTo(point(p)),
+            PathEl::LineTo(p) => PathSegment::LineTo(point(p)),
+            PathEl::QuadTo(p1, p2) => PathSegment::QuadTo(point(p1), point(p2)),
+            PathEl::CurveTo(p1, p2, p3) => PathSegment::CubicTo(point(p1), point(p2), point(p3)),
+            PathEl::ClosePath => PathSegment::Close,
+        }
+    }
+}
+
+impl From<PathSegment> for PathEl {
+    #[inline]
+    fn from(segment: PathSegment) -> Self {
+        match segment {
+            PathSegment::MoveTo(p) => PathEl::MoveTo(kurbo_point(p)),
+            PathSegment::LineTo(p) => PathEl::LineTo(kurbo_point(p)),
+            PathSegment::QuadTo(p1, p2) => PathEl::QuadTo(kurbo_point(p1), kurbo_point(p2)),
+            PathSegment::CubicTo(p1, p2, p3) => {
+                PathEl::CurveTo(kurbo_point(p1), kurbo_point(p2), kurbo_point(p3))
+            },
+            PathSegment::Close => PathEl::ClosePath,
+        }
+    }
+}
+
+impl Extend<PathEl> for Path {
+    fn extend<I: IntoIterator<Item = PathEl>>(&mut self, iter: I) {
+        for el in iter {
+            self.push(el.into());
+        }
+    }
+}
+
+impl FromIterator<PathEl> for Path {
+    fn from_iter<I: IntoIterator<Item = PathEl>>(iter: I) -> Self {
+        let mut path = Path::new();
+        path.extend(iter);
+        path
+    }
+}
+
+#[cfg(test)]
+mod test_kurbo {
+    use ::kurbo::{BezPath, PathEl};
+
+    use crate::path::Path;
+
+    #[test]
+    fn test_kurbo_roundtrip() {
+        let mut bez = BezPath::new();
+        bez.move_to((0.0, 0.0));
+        bez.curve_to((1.0, 2.0), (3.0, 4.0), (5.0, 6.0));
+        bez.close_path();
+        let path: Path = bez.iter().collect();
+        assert_eq!(path.len(), 5);
+        let elements: Vec<PathEl> = path.iter().map(PathEl::from).collect();
+        assert_eq!(elements, bez.elements());
+    }
+}
@@ -0,0 +1,68 @@
+//! Streaming the path events of the [`lyon_path`] crate into a [`Path`].
+//!
+//! [`lyon_path`]: https://docs.rs/lyon_path
+use std::iter::FromIterator;
+
+use ::lyon_path::{math::Point, PathEvent};
+
+use super::{Path, PathSegment, PathSink};
+use crate::geometry::PointD;
+
+#[inline]
+fn point(p: Point) -> PointD {
+    PointD::new(f64::from(p.x), f64::from(p.y))
+}
+
+impl Extend<PathEvent> for Path {
+    /// Appends the events, an `End` event only produces a segment if it closes
+    /// the figure.
+    fn extend<I: IntoIterator<Item = PathEvent>>(&mut self, iter: I) {
+        for event in iter {
+            let segment = match event {
+                PathEvent::Begin { at } => PathSegment::MoveTo(point(at)),
+                PathEvent::Line { to, .. } => PathSegment::LineTo(point(to)),
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    PathSegment::QuadTo(point(ctrl), point(to))
+                },
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => PathSegment::CubicTo(point(ctrl1), point(ctrl2), point(to)),
+                PathEvent::End { close: true, .. } => PathSegment::Close,
+                PathEvent::End { close: false, .. } => continue,
+            };
+            self.push(segment);
+        }
+    }
+}
+
+impl FromIterator<PathEvent> for Path {
+    fn from_iter<I: IntoIterator<Item = PathEvent>>(iter: I) -> Self {
+        let mut path = Path::new();
+        path.extend(iter);
+        path
+    }
+}
+
+#[cfg(test)]
+mod test_lyon {
+    use ::lyon_path::math::point;
+
+    use crate::path::Path;
+
+    #[test]
+    fn test_from_lyon_events() {
+        let mut builder = ::lyon_path::Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(10.0, 0.0));
+        builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+        builder.end(true);
+        let lyon = builder.build();
+        let path: Path = lyon.iter().collect();
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(10.0, 0.0);
+        expected.quad_to(10.0, 10.0, 0.0, 10.0);
+        expected.close();
+        assert_eq!(path, expected);
+    }
+}
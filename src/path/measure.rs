@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use crate::geometry::PointD;
+
+use super::flatten::{distance, flatten_figures_with, Flattening};
+use super::{ApproximationOptions, Path};
+
+/// A flattened figure with a positive length.
+struct Contour {
+    points: Vec<PointD>,
+    /// The distance of each point from the start of the path.
+    distances: Vec<f64>,
+}
+
+impl Contour {
+    #[inline]
+    fn start(&self) -> f64 {
+        self.distances[0]
+    }
+
+    #[inline]
+    fn end(&self) -> f64 {
+        self.distances[self.distances.len() - 1]
+    }
+
+    /// Returns the index of the first point further than `d` from the start
+    /// of the path.
+    fn first_point_after(&self, d: f64) -> usize {
+        match self.distances.binary_search_by(|probe| {
+            if *probe <= d {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }) {
+            Ok(i) | Err(i) => i,
+        }
+    }
+
+    /// Returns the point at the given distance from the start of the path
+    /// and the unit tangent at it.
+    fn point_at(&self, d: f64) -> (PointD, PointD) {
+        let last = self.points.len() - 1;
+        let i = match self
+            .distances
+            .binary_search_by(|probe| probe.partial_cmp(&d).unwrap_or(Ordering::Greater))
+        {
+            Ok(i) | Err(i) => i,
+        };
+        let seg = i.max(1).min(last) - 1;
+        let (a, b) = (self.points[seg], self.points[seg + 1]);
+        let len = self.distances[seg + 1] - self.distances[seg];
+        let t = if len > 0.0 {
+            ((d - self.distances[seg]) / len).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let point = PointD::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+        // zero length segments have no direction, use the closest one that has
+        let tangent_seg = (seg..last)
+            .chain((0..seg).rev())
+            .find(|&j| self.distances[j + 1] > self.distances[j])
+            .unwrap_or(seg);
+        let (a, b) = (self.points[tangent_seg], self.points[tangent_seg + 1]);
+        let len = distance(a, b);
+        let tangent = if len > 0.0 {
+            PointD::new((b.x - a.x) / len, (b.y - a.y) / len)
+        } else {
+            PointD::ZERO
+        };
+        (point, tangent)
+    }
+}
+
+/// Measures the length of a [`Path`] and locates points along it.
+///
+/// Curves are measured on their flattened approximation as described by the
+/// [`ApproximationOptions`] the measure was created with. The figures of the
+/// path are treated as a single continuous polyline, that is distances
+/// accumulate across figures while the moves between them don't add to the
+/// length. Closed figures include their closing segment.
+///
+/// [`Path`]: struct.Path.html
+/// [`ApproximationOptions`]: struct.ApproximationOptions.html
+pub struct PathMeasure {
+    contours: Vec<Contour>,
+}
+
+impl PathMeasure {
+    /// Flattens and measures the given path.
+    pub fn new(path: &Path, options: &ApproximationOptions) -> Self {
+        let mut contours = Vec::new();
+        let mut total = 0.0;
+        for figure in flatten_figures_with(path, Flattening::from_options(options)) {
+            let mut points = figure.points;
+            if figure.closed && points.len() > 1 {
+                points.push(points[0]);
+            }
+            let mut distances = Vec::with_capacity(points.len());
+            let mut length = total;
+            for (i, &p) in points.iter().enumerate() {
+                if i > 0 {
+                    length += distance(points[i - 1], p);
+                }
+                distances.push(length);
+            }
+            if length > total {
+                total = length;
+                contours.push(Contour { points, distances });
+            }
+        }
+        PathMeasure { contours }
+    }
+
+    /// The total length of all figures.
+    #[inline]
+    pub fn length(&self) -> f64 {
+        self.contours.last().map_or(0.0, Contour::end)
+    }
+
+    /// Returns the point at the given distance from the start of the path
+    /// together with the unit tangent of the path at that point.
+    ///
+    /// The distance is clamped to the length of the path. Returns `None` if
+    /// the path has no length.
+    pub fn point_at_distance(&self, distance: f64) -> Option<(PointD, PointD)> {
+        let distance = distance.max(0.0).min(self.length());
+        let contour = self
+            .contours
+            .iter()
+            .find(|contour| contour.end() >= distance)
+            .or_else(|| self.contours.last())?;
+        Some(contour.point_at(distance))
+    }
+
+    /// Extracts the part of the path between the given distances as a new
+    /// path consisting of line segments.
+    ///
+    /// The range is clamped to the length of the path, a range spanning
+    /// multiple figures results in one figure per spanned figure. An empty
+    /// range results in an empty path.
+    pub fn segment(&self, range: Range<f64>) -> Path {
+        let mut path = Path::new();
+        self.append_segment(&mut path, range, false);
+        path
+    }
+
+    /// Extracts the part of the path between the given fractions of its
+    /// length as a new path consisting of line segments.
+    ///
+    /// The range wraps around the end of the path, so `t1` smaller than `t0`
+    /// results in the part from `t0` to the end followed by the part from the
+    /// start to `t1`. If the path ends where it starts, like a single closed
+    /// figure, these parts are joined into one figure. A range of at least
+    /// `1.0` results in the whole path, an empty range in an empty path.
+    pub fn trimmed(&self, t0: f64, t1: f64) -> Path {
+        let mut path = Path::new();
+        let length = self.length();
+        if !t0.is_finite() || !t1.is_finite() {
+            return path;
+        }
+        if t1 - t0 >= 1.0 {
+            self.append_segment(&mut path, 0.0..length, false);
+            return path;
+        }
+        let start = t0.rem_euclid(1.0);
+        let end = start + (t1 - t0).rem_euclid(1.0);
+        self.append_segment(&mut path, start * length..end.min(1.0) * length, false);
+        if end > 1.0 {
+            let join = match (self.contours.first(), self.contours.last()) {
+                (Some(first), Some(last)) => first.points[0] == last.points[last.points.len() - 1],
+                _ => false,
+            };
+            self.append_segment(
+                &mut path,
+                0.0..(end - 1.0) * length,
+                join && !path.is_empty(),
+            );
+        }
+        path
+    }
+
+    /// Appends the part of the path between the given distances to `path`,
+    /// continuing its last figure instead of starting a new one if `join` is
+    /// set.
+    fn append_segment(&self, path: &mut Path, range: Range<f64>, join: bool) {
+        self.append_segment_from(path, range, join, 0);
+    }
+
+    /// Like [`append_segment`], but starts looking for the spanned contours
+    /// at the contour with the index `first`. Returns the index of the last
+    /// spanned contour, so that consecutive ranges don't rescan the contours
+    /// before it.
+    ///
+    /// [`append_segment`]: #method.append_segment
+    fn append_segment_from(
+        &self,
+        path: &mut Path,
+        range: Range<f64>,
+        join: bool,
+        first: usize,
+    ) -> usize {
+        let start = range.start.max(0.0);
+        let end = range.end.min(self.length());
+        if start >= end {
+            return first;
+        }
+        let mut last = first;
+        let spanned = self
+            .contours
+            .iter()
+            .enumerate()
+            .skip(first)
+            .skip_while(|(_, contour)| contour.end() <= start)
+            .take_while(|(_, contour)| contour.start() < end);
+        for (n, (i, contour)) in spanned.enumerate() {
+            last = i;
+            let (from, to) = (start.max(contour.start()), end.min(contour.end()));
+            if n > 0 || !join {
+                let (p, _) = contour.point_at(from);
+                path.move_to(p.x, p.y);
+            }
+            let inner = contour.first_point_after(from);
+            for (p, _) in contour.points[inner..]
+                .iter()
+                .zip(&contour.distances[inner..])
+                .take_while(|&(_, &d)| d < to)
+            {
+                path.line_to(p.x, p.y);
+            }
+            let (p, _) = contour.point_at(to);
+            path.line_to(p.x, p.y);
+        }
+        last
+    }
+
+    /// Applies the dash pattern to the path and returns the dashes as
+    /// separate figures consisting of line segments.
+    ///
+    /// `dashes` alternates between the lengths of dashes and gaps, an odd
+    /// number of lengths is repeated to form an even pattern. `offset` shifts
+    /// the pattern along the path like the dash offset of a stroke. If the
+    /// pattern has no positive length or contains negative lengths, the whole
+    /// path is returned undashed. The same applies to patterns so short that
+    /// the path would be split into more than a million repetitions of it.
+    pub fn dashed(&self, dashes: &[f64], offset: f64) -> Path {
+        // also keeps the lengths of the pattern well above the precision of
+        // the positions on the path, so that every repetition makes progress
+        const MAX_REPETITIONS: f64 = 1_000_000.0;
+
+        let length = self.length();
+        let mut pattern = dashes.to_vec();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(dashes);
+        }
+        let pattern_len: f64 = pattern.iter().sum();
+        if pattern_len <= 0.0
+            || !pattern_len.is_finite()
+            || pattern.iter().any(|&d| d < 0.0)
+            || length / pattern_len > MAX_REPETITIONS
+        {
+            return self.segment(0.0..length);
+        }
+        // find the element of the pattern the path starts in
+        let mut phase = offset.rem_euclid(pattern_len);
+        let mut i = 0;
+        while i < pattern.len() - 1 && phase >= pattern[i] {
+            phase -= pattern[i];
+            i += 1;
+        }
+        let mut path = Path::new();
+        let mut pos = -phase;
+        let mut contour = 0;
+        while pos < length {
+            let end = pos + pattern[i];
+            if i % 2 == 0 && end > 0.0 {
+                contour = self.append_segment_from(&mut path, pos.max(0.0)..end, false, contour);
+            }
+            pos = end;
+            i = (i + 1) % pattern.len();
+        }
+        path
+    }
+}
+
+impl Path {
+    /// The length of this path, measured with the default
+    /// [`ApproximationOptions`].
+    ///
+    /// Use [`PathMeasure`] to measure with different options or to query
+    /// multiple distances without flattening the path each time.
+    ///
+    /// [`ApproximationOptions`]: struct.ApproximationOptions.html
+    /// [`PathMeasure`]: struct.PathMeasure.html
+    pub fn length(&self) -> f64 {
+        PathMeasure::new(self, &Default::default()).length()
+    }
+
+    /// Returns the point at the given distance along this path and the unit
+    /// tangent at it, see [`PathMeasure::point_at_distance`].
+    ///
+    /// [`PathMeasure::point_at_distance`]: struct.PathMeasure.html#method.point_at_distance
+    pub fn point_at_distance(&self, distance: f64) -> Option<(PointD, PointD)> {
+        PathMeasure::new(self, &Default::default()).point_at_distance(distance)
+    }
+
+    /// Extracts the part of this path between the given distances, see
+    /// [`PathMeasure::segment`].
+    ///
+    /// [`PathMeasure::segment`]: struct.PathMeasure.html#method.segment
+    pub fn segment(&self, range: Range<f64>) -> Path {
+        PathMeasure::new(self, &Default::default()).segment(range)
+    }
+
+    /// Extracts the part of this path between the given fractions of its
+    /// length, wrapping around its end, see [`PathMeasure::trimmed`].
+    ///
+    /// Trimming with a growing `t1` draws the path progressively.
+    ///
+    /// [`PathMeasure::trimmed`]: struct.PathMeasure.html#method.trimmed
+    pub fn trimmed(&self, t0: f64, t1: f64) -> Path {
+        PathMeasure::new(self, &Default::default()).trimmed(t0, t1)
+    }
+}
+
+#[cfg(test)]
+mod test_measure {
+    use crate::geometry::PointD;
+    use crate::path::{ApproximationOptions, FlattenMode, Path, PathMeasure};
+
+    #[test]
+    fn test_path_measure_lines() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.line_to(10.0, 10.0);
+        path.move_to(100.0, 100.0);
+        path.line_to(100.0, 105.0);
+        assert_eq!(path.length(), 25.0);
+        assert_eq!(
+            path.point_at_distance(15.0),
+            Some((PointD::new(10.0, 5.0), PointD::new(0.0, 1.0)))
+        );
+        assert_eq!(
+            path.point_at_distance(100.0),
+            Some((PointD::new(100.0, 105.0), PointD::new(0.0, 1.0)))
+        );
+        assert_eq!(Path::new().point_at_distance(0.0), None);
+
+        let mut expected = Path::new();
+        expected.move_to(5.0, 0.0);
+        expected.line_to(10.0, 0.0);
+        expected.line_to(10.0, 10.0);
+        expected.move_to(100.0, 100.0);
+        expected.line_to(100.0, 102.0);
+        assert_eq!(path.segment(5.0..22.0), expected);
+        assert!(path.segment(5.0..5.0).is_empty());
+    }
+
+    #[test]
+    fn test_path_measure_curve() {
+        // a quarter circle of radius 100
+        let k = 0.552_284_749_830_793_4 * 100.0;
+        let mut path = Path::new();
+        path.move_to(100.0, 0.0);
+        path.cubic_to(100.0, k, k, 100.0, 0.0, 100.0);
+        let expected = std::f64::consts::FRAC_PI_2 * 100.0;
+        for &mode in &[FlattenMode::Default, FlattenMode::Recursive] {
+            let mut options = ApproximationOptions::default();
+            options.set_flatten_mode(mode);
+            options.flatten_tolerance = 0.01;
+            let length = PathMeasure::new(&path, &options).length();
+            assert!((length - expected).abs() < 0.1, "{:?}: {}", mode, length);
+        }
+    }
+
+    #[test]
+    fn test_trimmed() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.line_to(10.0, 10.0);
+        path.line_to(0.0, 10.0);
+        path.close();
+
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.line_to(10.0, 0.0);
+        expected.line_to(10.0, 5.0);
+        assert_eq!(path.trimmed(0.0, 0.375), expected);
+
+        // wraps around the start of the closed figure without a move
+        let mut expected = Path::new();
+        expected.move_to(0.0, 5.0);
+        expected.line_to(0.0, 0.0);
+        expected.line_to(5.0, 0.0);
+        assert_eq!(path.trimmed(0.875, 0.125), expected);
+        assert_eq!(path.trimmed(1.875, 2.125), expected);
+
+        assert!(path.trimmed(0.5, 0.5).is_empty());
+        assert_eq!(path.trimmed(-1.0, 1.0).length(), 40.0);
+    }
+}
@@ -0,0 +1,110 @@
+use std::{cmp, ops};
+
+use crate::util::range_to_tuple;
+
+use super::fill_rule::signed_area;
+use super::flatten::flatten_figures;
+use super::{ApproximationOptions, Path, StrokeCap, StrokeJoin, StrokeOptions};
+
+impl Path {
+    /// Returns the outline of this path offset by `distance`, outwards for
+    /// positive and inwards for negative distances.
+    ///
+    /// The offset is computed by blend2d's stroker, so curves are offset
+    /// according to the offset mode and parameter of the given
+    /// [`ApproximationOptions`] and corners are rounded. Each closed figure
+    /// results in a single closed figure, an open figure results in its
+    /// outline at `distance` if the distance is positive and is dropped
+    /// otherwise. Inner offsets larger than the figure are not removed and
+    /// may self-intersect.
+    ///
+    /// [`ApproximationOptions`]: struct.ApproximationOptions.html
+    pub fn offset(&self, distance: f64, options: &ApproximationOptions) -> Path {
+        self.offset_range(.., distance, options)
+    }
+
+    /// Like [`offset`], but only offsets the figures in the given vertex
+    /// range. Figures partially covered by the range are cut at its bounds.
+    ///
+    /// [`offset`]: #method.offset
+    pub fn offset_range<R: ops::RangeBounds<usize>>(
+        &self,
+        range: R,
+        distance: f64,
+        options: &ApproximationOptions,
+    ) -> Path {
+        let (start, end) = range_to_tuple(range, || self.len());
+        let mut stroke = StrokeOptions::new();
+        stroke.set_width(distance.abs() * 2.0);
+        stroke.set_join(StrokeJoin::Round);
+        stroke.set_caps(StrokeCap::Round);
+
+        let mut result = Path::new();
+        for figure in self.figures() {
+            let figure_range = figure.range();
+            let range = figure_range.start.max(start)..figure_range.end.min(end);
+            if range.start >= range.end {
+                continue;
+            }
+            let closed = figure.is_closed() && range.end == figure_range.end;
+            if distance == 0.0 {
+                result.add_path_range(self, range);
+                continue;
+            }
+            let mut outline = Path::new();
+            outline.add_stroked_path_range(self, range, &stroke, options);
+            if !closed {
+                if distance > 0.0 {
+                    result.add_path(&outline);
+                }
+                continue;
+            }
+            // the stroke of a closed figure consists of its outer and inner
+            // offset, tell them apart by their area
+            let areas = flatten_figures(&outline)
+                .into_iter()
+                .map(|flat| (signed_area(&flat.points).abs(), flat.range));
+            let by_area = |(a, _): &(f64, _), (b, _): &(f64, _)| {
+                a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal)
+            };
+            let picked = if distance > 0.0 {
+                areas.max_by(by_area)
+            } else {
+                areas.min_by(by_area)
+            };
+            if let Some((_, range)) = picked {
+                result.add_path_range(&outline, range);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test_offset {
+    use crate::geometry::RectD;
+    use crate::path::{ApproximationOptions, Path};
+
+    #[test]
+    fn test_path_offset() {
+        let mut path = Path::new();
+        path.add_geometry(
+            &RectD::new(10.0, 10.0, 20.0, 20.0),
+            None,
+            Default::default(),
+        );
+        let options = ApproximationOptions::default();
+
+        let outer = path.offset(5.0, &options);
+        assert_eq!(outer.figure_count(), 1);
+        let bounds = outer.bounding_box().unwrap();
+        assert!((bounds.x0 - 5.0).abs() < 1e-6 && (bounds.x1 - 35.0).abs() < 1e-6);
+
+        let inner = path.offset(-5.0, &options);
+        assert_eq!(inner.figure_count(), 1);
+        let bounds = inner.bounding_box().unwrap();
+        assert!((bounds.x0 - 15.0).abs() < 1e-6 && (bounds.x1 - 25.0).abs() < 1e-6);
+
+        assert_eq!(path.offset_range(0..0, 5.0, &options).len(), 0);
+    }
+}
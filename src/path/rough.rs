@@ -0,0 +1,119 @@
+use crate::geometry::PointD;
+use crate::random::Random;
+
+use super::flatten::{distance, flatten_figures};
+use super::Path;
+
+/// Options for generating hand-drawn looking outlines with [`Path::rough`].
+///
+/// [`Path::rough`]: struct.Path.html#method.rough
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoughOptions {
+    /// The maximum distance vertices are moved by in each direction.
+    pub roughness: f64,
+    /// How much segments bend, relative to their length and the roughness.
+    pub bowing: f64,
+    /// The number of times each figure is traced.
+    pub passes: u32,
+    /// The seed of the random generator, the same seed always produces the
+    /// same outline.
+    pub seed: u64,
+}
+
+impl Default for RoughOptions {
+    fn default() -> Self {
+        RoughOptions {
+            roughness: 1.0,
+            bowing: 1.0,
+            passes: 2,
+            seed: 0,
+        }
+    }
+}
+
+impl Path {
+    /// Returns a sketchy, hand-drawn looking version of this path's outline,
+    /// meant to be stroked.
+    ///
+    /// Each figure is flattened and traced [`RoughOptions::passes`] times,
+    /// with every trace moving the vertices by a random amount and bending
+    /// the segments between them. The result is fully determined by the
+    /// options, so the same seed always produces the same path.
+    ///
+    /// [`RoughOptions::passes`]: struct.RoughOptions.html#structfield.passes
+    pub fn rough(&self, options: &RoughOptions) -> Path {
+        let mut rng = Random::new(options.seed);
+        let mut result = Path::new();
+        for figure in flatten_figures(self) {
+            let mut points = figure.points;
+            if figure.closed && points.len() > 2 {
+                points.push(points[0]);
+            }
+            if points.len() < 2 {
+                continue;
+            }
+            for _ in 0..options.passes {
+                rough_polyline(&mut result, &mut rng, &points, options);
+            }
+        }
+        result
+    }
+}
+
+fn rough_polyline(out: &mut Path, rng: &mut Random, points: &[PointD], options: &RoughOptions) {
+    let r = options.roughness;
+    let jitter = |p: PointD, rng: &mut Random| PointD {
+        x: p.x + rng.next_range(-r, r),
+        y: p.y + rng.next_range(-r, r),
+    };
+    let mut prev = jitter(points[0], rng);
+    let first = prev;
+    out.move_to(prev.x, prev.y);
+    for (i, &p) in points.iter().enumerate().skip(1) {
+        // keep closed figures connected at their start
+        let next = if i == points.len() - 1 && p == points[0] {
+            first
+        } else {
+            jitter(p, rng)
+        };
+        let len = distance(prev, next);
+        if len > 0.0 {
+            let bow = options.bowing * r * len * 0.02 * rng.next_range(-1.0, 1.0);
+            let (nx, ny) = ((prev.y - next.y) / len, (next.x - prev.x) / len);
+            let t = rng.next_range(0.4, 0.6);
+            out.quad_to(
+                prev.x + (next.x - prev.x) * t + nx * bow,
+                prev.y + (next.y - prev.y) * t + ny * bow,
+                next.x,
+                next.y,
+            );
+        }
+        prev = next;
+    }
+}
+
+#[cfg(test)]
+mod test_rough {
+    use crate::path::{Path, RoughOptions};
+
+    #[test]
+    fn test_rough_deterministic() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(100.0, 0.0);
+        path.line_to(100.0, 100.0);
+        path.close();
+        let options = RoughOptions::default();
+        let a = path.rough(&options);
+        assert_eq!(a, path.rough(&options));
+        assert_ne!(
+            a,
+            path.rough(&RoughOptions {
+                seed: 1,
+                ..options
+            })
+        );
+        let bbox = a.bounding_box().unwrap();
+        assert!(bbox.x0 > -5.0 && bbox.y0 > -5.0 && bbox.x1 < 105.0 && bbox.y1 < 105.0);
+    }
+}
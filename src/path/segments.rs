@@ -0,0 +1,247 @@
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use crate::geometry::PointD;
+
+use super::{Path, PathCommand};
+
+/// A segment of a [`Path`] with its typed points, as yielded by
+/// [`Path::iter`].
+///
+/// [`Path`]: struct.Path.html
+/// [`Path::iter`]: struct.Path.html#method.iter
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathSegment {
+    MoveTo(PointD),
+    LineTo(PointD),
+    QuadTo(PointD, PointD),
+    CubicTo(PointD, PointD, PointD),
+    Close,
+}
+
+impl PathSegment {
+    /// Returns the point this segment ends at, or `None` for
+    /// [`PathSegment::Close`].
+    ///
+    /// [`PathSegment::Close`]: #variant.Close
+    #[inline]
+    pub fn end_point(&self) -> Option<PointD> {
+        match *self {
+            PathSegment::MoveTo(p) | PathSegment::LineTo(p) => Some(p),
+            PathSegment::QuadTo(_, p) | PathSegment::CubicTo(_, _, p) => Some(p),
+            PathSegment::Close => None,
+        }
+    }
+}
+
+/// An iterator over the [`PathSegment`]s of a [`Path`].
+///
+/// Trailing curves that are missing control points end the iteration.
+///
+/// [`Path`]: struct.Path.html
+/// [`PathSegment`]: enum.PathSegment.html
+#[derive(Debug, Clone)]
+pub struct PathSegments<'a> {
+    cmds: &'a [u8],
+    vertices: &'a [PointD],
+    idx: usize,
+}
+
+impl<'a> PathSegments<'a> {
+    fn new(path: &'a Path, range: Range<usize>) -> Self {
+        PathSegments {
+            cmds: &path.command_data()[range.clone()],
+            vertices: &path.vertex_data()[range],
+            idx: 0,
+        }
+    }
+}
+
+impl Iterator for PathSegments<'_> {
+    type Item = PathSegment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.idx;
+        let (cmds, vtx) = (self.cmds, self.vertices);
+        let (segment, len) = match PathCommand::from(u32::from(*cmds.get(i)?)) {
+            PathCommand::Move => (PathSegment::MoveTo(vtx[i]), 1),
+            PathCommand::On => (PathSegment::LineTo(vtx[i]), 1),
+            PathCommand::Quad if i + 1 < cmds.len() => (PathSegment::QuadTo(vtx[i], vtx[i + 1]), 2),
+            PathCommand::Cubic if i + 2 < cmds.len() => {
+                (PathSegment::CubicTo(vtx[i], vtx[i + 1], vtx[i + 2]), 3)
+            },
+            PathCommand::Close => (PathSegment::Close, 1),
+            PathCommand::Quad | PathCommand::Cubic => {
+                self.idx = cmds.len();
+                return None;
+            },
+        };
+        self.idx += len;
+        Some(segment)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.cmds.len() - self.idx))
+    }
+}
+
+impl FusedIterator for PathSegments<'_> {}
+
+/// A single figure of a [`Path`], as yielded by [`Path::figures`].
+///
+/// A figure starts at a move command, or at the start of the path, and ends
+/// before the next move command or after a close command.
+///
+/// [`Path`]: struct.Path.html
+/// [`Path::figures`]: struct.Path.html#method.figures
+#[derive(Debug, Clone)]
+pub struct PathFigure<'a> {
+    path: &'a Path,
+    range: Range<usize>,
+}
+
+impl<'a> PathFigure<'a> {
+    /// The vertex range of this figure in its path.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns whether this figure ends with a close command.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.path.is_range_closed(&self.range)
+    }
+
+    /// Returns an iterator over the segments of this figure.
+    #[inline]
+    pub fn iter(&self) -> PathSegments<'a> {
+        PathSegments::new(self.path, self.range())
+    }
+
+    /// Returns the vertices of this figure.
+    #[inline]
+    pub fn vertex_data(&self) -> &'a [PointD] {
+        &self.path.vertex_data()[self.range()]
+    }
+}
+
+impl<'a> IntoIterator for &PathFigure<'a> {
+    type Item = PathSegment;
+    type IntoIter = PathSegments<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the [`PathFigure`]s of a [`Path`].
+///
+/// [`Path`]: struct.Path.html
+/// [`PathFigure`]: struct.PathFigure.html
+#[derive(Debug)]
+pub struct PathFigures<'a> {
+    path: &'a Path,
+    ranges: std::vec::IntoIter<Range<usize>>,
+}
+
+impl<'a> Iterator for PathFigures<'a> {
+    type Item = PathFigure<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.path;
+        self.ranges.next().map(|range| PathFigure { path, range })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ranges.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PathFigures<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let path = self.path;
+        self.ranges
+            .next_back()
+            .map(|range| PathFigure { path, range })
+    }
+}
+
+impl ExactSizeIterator for PathFigures<'_> {}
+
+impl Path {
+    /// Returns an iterator over the [`PathSegment`]s of this path.
+    ///
+    /// [`PathSegment`]: enum.PathSegment.html
+    #[inline]
+    pub fn iter(&self) -> PathSegments<'_> {
+        PathSegments::new(self, 0..self.len())
+    }
+
+    /// Returns an iterator over the figures of this path.
+    pub fn figures(&self) -> PathFigures<'_> {
+        PathFigures {
+            path: self,
+            ranges: self.figure_ranges().into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Path {
+    type Item = PathSegment;
+    type IntoIter = PathSegments<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test_segments {
+    use crate::geometry::PointD;
+    use crate::path::{Path, PathSegment};
+
+    #[test]
+    fn test_path_iter() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.quad_to(15.0, 5.0, 10.0, 10.0);
+        path.cubic_to(5.0, 15.0, 0.0, 15.0, 0.0, 10.0);
+        path.close();
+        let p = |x, y| PointD { x, y };
+        let segments: Vec<_> = path.iter().collect();
+        assert_eq!(
+            segments,
+            [
+                PathSegment::MoveTo(p(0.0, 0.0)),
+                PathSegment::LineTo(p(10.0, 0.0)),
+                PathSegment::QuadTo(p(15.0, 5.0), p(10.0, 10.0)),
+                PathSegment::CubicTo(p(5.0, 15.0), p(0.0, 15.0), p(0.0, 10.0)),
+                PathSegment::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_figures() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.close();
+        path.move_to(20.0, 0.0);
+        path.line_to(30.0, 0.0);
+        let figures: Vec<_> = path.figures().collect();
+        assert_eq!(figures.len(), 2);
+        assert!(figures[0].is_closed());
+        assert!(!figures[1].is_closed());
+        assert_eq!(figures[1].range(), 3..5);
+        assert_eq!(figures[1].iter().count(), 2);
+    }
+}
@@ -0,0 +1,86 @@
+use std::iter::FromIterator;
+
+use super::{Path, PathSegment};
+
+/// A push based consumer of [`PathSegment`]s.
+///
+/// This allows geometry to be streamed from external sources into a [`Path`]
+/// without collecting the segments first, and a [`Path`] to be streamed into
+/// external consumers with [`Path::replay`]. Closures taking a
+/// [`PathSegment`] are sinks as well.
+///
+/// [`Path`]: struct.Path.html
+/// [`PathSegment`]: enum.PathSegment.html
+/// [`Path::replay`]: struct.Path.html#method.replay
+pub trait PathSink {
+    /// Consumes the next segment.
+    fn push(&mut self, segment: PathSegment);
+}
+
+impl PathSink for Path {
+    #[inline]
+    fn push(&mut self, segment: PathSegment) {
+        match segment {
+            PathSegment::MoveTo(p) => self.move_to(p.x, p.y),
+            PathSegment::LineTo(p) => self.line_to(p.x, p.y),
+            PathSegment::QuadTo(p1, p2) => self.quad_to(p1.x, p1.y, p2.x, p2.y),
+            PathSegment::CubicTo(p1, p2, p3) => self.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y),
+            PathSegment::Close => self.close(),
+        }
+    }
+}
+
+impl<F: FnMut(PathSegment)> PathSink for F {
+    #[inline]
+    fn push(&mut self, segment: PathSegment) {
+        self(segment)
+    }
+}
+
+impl Path {
+    /// Pushes all segments of this path into the given sink.
+    pub fn replay<S: PathSink + ?Sized>(&self, sink: &mut S) {
+        for segment in self.iter() {
+            sink.push(segment);
+        }
+    }
+}
+
+impl Extend<PathSegment> for Path {
+    fn extend<I: IntoIterator<Item = PathSegment>>(&mut self, iter: I) {
+        for segment in iter {
+            self.push(segment);
+        }
+    }
+}
+
+impl FromIterator<PathSegment> for Path {
+    fn from_iter<I: IntoIterator<Item = PathSegment>>(iter: I) -> Self {
+        let mut path = Path::new();
+        path.extend(iter);
+        path
+    }
+}
+
+#[cfg(test)]
+mod test_sink {
+    use crate::path::{Path, PathSegment, PathSink};
+
+    #[test]
+    fn test_path_sink_roundtrip() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(5.0, 5.0, 10.0, 0.0);
+        path.close();
+        let copy: Path = path.iter().collect();
+        assert_eq!(copy, path);
+
+        let mut count = 0;
+        path.replay(&mut |_: PathSegment| count += 1);
+        assert_eq!(count, 3);
+
+        let mut sink = Path::new();
+        sink.push(PathSegment::MoveTo(Default::default()));
+        assert_eq!(sink.len(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::context::Context;
+use crate::error::Result;
+use crate::image::{Image, ImageFormat, Pixels};
+use crate::matrix::MatrixTransform;
+
+use super::{FillRule, Path};
+
+impl Path {
+    /// Rasterizes this path and calls `f` with every horizontal span of
+    /// pixels that share the same non-zero coverage.
+    ///
+    /// The arguments are the row `y`, the span `x0..x1` with `x1` being
+    /// exclusive and the coverage, where `255` means fully covered. Spans are
+    /// reported row by row from the top, each row from the left, and are in
+    /// the path's coordinates rounded outwards to whole pixels.
+    ///
+    /// The path is rasterized by blend2d into a temporary image the size of
+    /// its bounding box, which must fit into an [`Image`].
+    ///
+    /// [`Image`]: ../image/struct.Image.html
+    pub fn for_each_span<F>(&self, fill_rule: FillRule, mut f: F) -> Result<()>
+    where
+        F: FnMut(i32, i32, i32, u8),
+    {
+        let bbox = match self.bounding_box() {
+            Some(bbox) => bbox,
+            None => return Ok(()),
+        };
+        let (x0, y0) = (bbox.x0.floor(), bbox.y0.floor());
+        let (w, h) = (bbox.x1.ceil() - x0, bbox.y1.ceil() - y0);
+        if w <= 0.0 || h <= 0.0 {
+            return Ok(());
+        }
+        let (x0, y0) = (x0 as i32, y0 as i32);
+
+        let mut mask = Image::new(w as i32, h as i32, ImageFormat::PRgb32)?;
+        {
+            let mut ctx = Context::new(&mut mask)?;
+            ctx.clear_all()?;
+            ctx.translate(-f64::from(x0), -f64::from(y0));
+            ctx.set_fill_rule(fill_rule);
+            ctx.set_fill_style_rgba32(0xFF_FF_FF_FF);
+            ctx.fill_path(self)?;
+            ctx.end()?;
+        }
+
+        let rows = match mask.pixels() {
+            Pixels::Rgb32(rows) => rows,
+            Pixels::A8(_) => unreachable!(),
+        };
+        for (y, row) in rows.rows().enumerate() {
+            let y = y0 + y as i32;
+            let mut start = 0;
+            while start < row.len() {
+                let coverage = (row[start] >> 24) as u8;
+                let len = row[start..]
+                    .iter()
+                    .take_while(|&&pixel| (pixel >> 24) as u8 == coverage)
+                    .count();
+                if coverage != 0 {
+                    f(y, x0 + start as i32, x0 + (start + len) as i32, coverage);
+                }
+                start += len;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_spans {
+    use crate::path::{FillRule, Path};
+
+    #[test]
+    fn test_for_each_span() {
+        let mut path = Path::new();
+        path.move_to(10.0, 20.0);
+        path.line_to(14.0, 20.0);
+        path.line_to(14.0, 22.0);
+        path.line_to(10.0, 22.0);
+        path.close();
+        let mut spans = Vec::new();
+        path.for_each_span(FillRule::NonZero, |y, x0, x1, coverage| {
+            spans.push((y, x0, x1, coverage))
+        })
+        .unwrap();
+        assert_eq!(spans, vec![(20, 10, 14, 255), (21, 10, 14, 255)]);
+
+        let mut half = Path::new();
+        half.move_to(0.0, 0.0);
+        half.line_to(0.5, 0.0);
+        half.line_to(0.5, 1.0);
+        half.line_to(0.0, 1.0);
+        half.close();
+        let mut coverage = Vec::new();
+        half.for_each_span(FillRule::NonZero, |_, _, _, c| coverage.push(c))
+            .unwrap();
+        assert_eq!(coverage.len(), 1);
+        assert!((120..=136).contains(&coverage[0]));
+    }
+}
@@ -0,0 +1,63 @@
+use super::{ApproximationOptions, Path, StrokeOptions};
+
+impl Path {
+    /// Returns the outline of this path stroked with the given options and
+    /// the default [`ApproximationOptions`], meant for final rendering.
+    ///
+    /// [`ApproximationOptions`]: struct.ApproximationOptions.html
+    pub fn stroked(&self, options: &StrokeOptions) -> Path {
+        let mut path = Path::new();
+        path.add_stroked_path(self, options, &Default::default());
+        path
+    }
+
+    /// Returns a coarse outline of this path stroked with the given options,
+    /// meant to be recomputed quickly while the path is being edited
+    /// interactively.
+    ///
+    /// The curves of this path are flattened with `coarse_tolerance` before
+    /// stroking, so the stroker only has to offset line segments. The
+    /// outline deviates up to about `coarse_tolerance` from the outline
+    /// returned by [`stroked`], which should be used once editing is done. A
+    /// tolerance that is not positive falls back to blend2d's default
+    /// tolerance.
+    ///
+    /// [`stroked`]: #method.stroked
+    pub fn stroked_preview(&self, options: &StrokeOptions, coarse_tolerance: f64) -> Path {
+        let mut approx = ApproximationOptions::default();
+        if coarse_tolerance > 0.0 {
+            approx.flatten_tolerance = coarse_tolerance;
+            approx.simplyify_tolerance = coarse_tolerance;
+        }
+        let flat = self.flatten_with(&approx);
+        let mut path = Path::new();
+        path.add_stroked_path(&flat, options, &approx);
+        path
+    }
+}
+
+#[cfg(test)]
+mod test_stroked {
+    use crate::path::{Path, StrokeOptions};
+
+    #[test]
+    fn test_stroked_preview() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.cubic_to(0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+        let mut options = StrokeOptions::new();
+        options.set_width(4.0);
+        assert_eq!(options.width(), 4.0);
+
+        let accurate = path.stroked(&options);
+        let preview = path.stroked_preview(&options, 2.0);
+        assert!(!accurate.is_empty());
+        assert!(preview.len() > 4);
+        let (a, p) = (
+            accurate.bounding_box().unwrap(),
+            preview.bounding_box().unwrap(),
+        );
+        assert!((a.y1 - p.y1).abs() < 2.5, "{:?} {:?}", a, p);
+        assert!((a.x0 - p.x0).abs() < 2.5 && (a.x1 - p.x1).abs() < 2.5);
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt::Write;
+
+use crate::error::{Error, Result};
+use crate::geometry::PointD;
+
+use super::{Path, PathSegment};
+
+impl Path {
+    /// Parses SVG path data, like the `d` attribute of an SVG `<path>`
+    /// element, into a new path.
+    ///
+    /// All commands of the SVG path grammar are supported in their absolute
+    /// and relative forms, arc rotations are given in degrees as in SVG.
+    /// Returns [`Error::InvalidValue`] if the data is malformed, an empty
+    /// string results in an empty path.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn from_svg_path(data: &str) -> Result<Path> {
+        let mut parser = Parser {
+            data: data.as_bytes(),
+            pos: 0,
+        };
+        let mut path = Path::new();
+        let mut current = PointD::ZERO;
+        let mut start = PointD::ZERO;
+        let mut prev_cmd = None;
+        while parser.skip_separators() {
+            let cmd = match parser.command() {
+                Some(cmd) => cmd,
+                // numbers after a command repeat it, after a moveto they
+                // are implicit linetos
+                None => match prev_cmd {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(cmd) if cmd != b'Z' && cmd != b'z' => cmd,
+                    _ => return Err(Error::InvalidValue),
+                },
+            };
+            if prev_cmd.is_none() && cmd != b'M' && cmd != b'm' {
+                return Err(Error::InvalidValue);
+            }
+            let origin = if cmd.is_ascii_lowercase() {
+                current
+            } else {
+                PointD::ZERO
+            };
+            current = match cmd.to_ascii_uppercase() {
+                b'M' => {
+                    let p = parser.point(origin)?;
+                    path.move_to(p.x, p.y);
+                    start = p;
+                    p
+                },
+                b'L' => {
+                    let p = parser.point(origin)?;
+                    path.line_to(p.x, p.y);
+                    p
+                },
+                b'H' => {
+                    let p = PointD::new(origin.x + parser.number()?, current.y);
+                    path.line_to(p.x, p.y);
+                    p
+                },
+                b'V' => {
+                    let p = PointD::new(current.x, origin.y + parser.number()?);
+                    path.line_to(p.x, p.y);
+                    p
+                },
+                b'C' => {
+                    let (p1, p2) = (parser.point(origin)?, parser.point(origin)?);
+                    let p3 = parser.point(origin)?;
+                    path.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                    p3
+                },
+                b'S' => {
+                    let (p2, p3) = (parser.point(origin)?, parser.point(origin)?);
+                    path.smooth_cubic_to(p2.x, p2.y, p3.x, p3.y);
+                    p3
+                },
+                b'Q' => {
+                    let (p1, p2) = (parser.point(origin)?, parser.point(origin)?);
+                    path.quad_to(p1.x, p1.y, p2.x, p2.y);
+                    p2
+                },
+                b'T' => {
+                    let p2 = parser.point(origin)?;
+                    path.smooth_quad_to(p2.x, p2.y);
+                    p2
+                },
+                b'A' => {
+                    let (rx, ry) = (parser.number()?, parser.number()?);
+                    let rotation = parser.number()?;
+                    let (large_arc, sweep) = (parser.flag()?, parser.flag()?);
+                    let p = parser.point(origin)?;
+                    path.elliptic_arc_to(
+                        rx.abs(),
+                        ry.abs(),
+                        rotation.to_radians(),
+                        large_arc,
+                        sweep,
+                        p.x,
+                        p.y,
+                    );
+                    p
+                },
+                b'Z' => {
+                    path.close();
+                    start
+                },
+                _ => return Err(Error::InvalidValue),
+            };
+            prev_cmd = Some(cmd);
+        }
+        Ok(path)
+    }
+
+    /// Serializes this path into SVG path data using absolute commands.
+    ///
+    /// Numbers are written with the shortest representation that parses back
+    /// into the same value, so [`from_svg_path`] reproduces this path exactly.
+    ///
+    /// [`from_svg_path`]: #method.from_svg_path
+    pub fn to_svg_path(&self) -> String {
+        let mut out = String::new();
+        for segment in self.iter() {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            // writing to a String can't fail
+            let _ = match segment {
+                PathSegment::MoveTo(p) => write!(out, "M{} {}", p.x, p.y),
+                PathSegment::LineTo(p) => write!(out, "L{} {}", p.x, p.y),
+                PathSegment::QuadTo(p1, p2) => write!(out, "Q{} {} {} {}", p1.x, p1.y, p2.x, p2.y),
+                PathSegment::CubicTo(p1, p2, p3) => write!(
+                    out,
+                    "C{} {} {} {} {} {}",
+                    p1.x, p1.y, p2.x, p2.y, p3.x, p3.y
+                ),
+                PathSegment::Close => write!(out, "Z"),
+            };
+        }
+        out
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Skips whitespace and commas, returns false if the end was reached.
+    fn skip_separators(&mut self) -> bool {
+        while let Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') | Some(b',') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos < self.data.len()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let cmd = self.peek().filter(u8::is_ascii_alphabetic)?;
+        self.pos += 1;
+        Some(cmd)
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.skip_separators();
+        let start = self.pos;
+        if let Some(b'+') | Some(b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let int_digits = self.digits();
+        let mut frac_digits = 0;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            frac_digits = self.digits();
+        }
+        if int_digits + frac_digits == 0 {
+            return Err(Error::InvalidValue);
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.pos += 1;
+            }
+            // an `e` without exponent digits is not part of the number
+            if self.digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or(Error::InvalidValue)
+    }
+
+    /// Parses an arc flag, which may be directly followed by the next number.
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(Error::InvalidValue),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+
+    fn point(&mut self, origin: PointD) -> Result<PointD> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(PointD::new(origin.x + x, origin.y + y))
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+}
+
+#[cfg(test)]
+mod test_svg {
+    use crate::path::{Path, PathSegment};
+
+    #[test]
+    fn test_from_svg_path() {
+        let path = Path::from_svg_path("M10,10 h20 v20 H10 z m5-5 l1.5e1 0 10 10").unwrap();
+        let mut expected = Path::new();
+        expected.move_to(10.0, 10.0);
+        expected.line_to(30.0, 10.0);
+        expected.line_to(30.0, 30.0);
+        expected.line_to(10.0, 30.0);
+        expected.close();
+        expected.move_to(15.0, 5.0);
+        expected.line_to(30.0, 5.0);
+        expected.line_to(40.0, 15.0);
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn test_from_svg_path_arc() {
+        let path = Path::from_svg_path("M0 0a10 10 0 1110 10").unwrap();
+        assert!(path
+            .iter()
+            .any(|segment| matches!(segment, PathSegment::CubicTo(..))));
+        let end = path.last_vertex().unwrap();
+        assert!((end.x - 10.0).abs() < 1e-9 && (end.y - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_svg_path_invalid() {
+        assert!(Path::from_svg_path("L10 10").is_err());
+        assert!(Path::from_svg_path("M10").is_err());
+        assert!(Path::from_svg_path("M0 0 Z 10 10").is_err());
+        assert!(Path::from_svg_path("M0 0 X").is_err());
+        assert!(Path::from_svg_path("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_svg_path_roundtrip() {
+        let mut path = Path::new();
+        path.move_to(0.5, -1.0);
+        path.quad_to(2.0, 3.0, 4.0, 5.0);
+        path.cubic_to(1.0, 2.0, 3.0, 4.0, 0.1, 1e-7);
+        path.close();
+        let data = path.to_svg_path();
+        assert_eq!(data, "M0.5 -1 Q2 3 4 5 C1 2 3 4 0.1 0.0000001 Z");
+        assert_eq!(Path::from_svg_path(&data).unwrap(), path);
+    }
+}
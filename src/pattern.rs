@@ -5,9 +5,19 @@ use crate::geometry::RectI;
 use crate::image::Image;
 use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
 use crate::util::cast_ref;
-use crate::variant::WrappedBlCore;
+use crate::variant::{DeepClone, WrappedBlCore};
 use crate::ExtendMode;
 
+/// A style that fills with an [`Image`].
+///
+/// The pattern doesn't borrow its image, instead it holds a reference
+/// counted reference to the image's data like a clone of the image does. The
+/// image can therefore be dropped or modified while the pattern still exists,
+/// modifying it copies its data first if it is shared, so the pattern keeps
+/// the pixels it was created with. Use [`set_image`] to pick up changes.
+///
+/// [`Image`]: ../image/struct.Image.html
+/// [`set_image`]: #method.set_image
 #[repr(transparent)]
 pub struct Pattern {
     core: ffi::BLPatternCore,
@@ -24,8 +34,10 @@ unsafe impl WrappedBlCore for Pattern {
 }
 
 impl Pattern {
-    /// Creates a new pattern that borrows the given [`Image`] immutably for its
-    /// lifetime.
+    /// Creates a new pattern referencing the data of the given [`Image`], see
+    /// [`Pattern`] for how the image is shared.
+    ///
+    /// [`Pattern`]: struct.Pattern.html
     pub fn new<'r, 'm, R, M>(image: &Image, area: R, extend_mode: ExtendMode, matrix: M) -> Pattern
     where
         R: Into<Option<&'r RectI>>,
@@ -47,6 +59,47 @@ impl Pattern {
         this
     }
 
+    /// Returns the number of patterns sharing the image reference, area,
+    /// extend mode and matrix of this pattern, including this one.
+    ///
+    /// Note that the pattern's [`Image`] is tracked separately, see
+    /// [`Image::ref_count`](../image/struct.Image.html#method.ref_count).
+    ///
+    /// [`Image`]: ../image/struct.Image.html
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.impl_().ref_count()
+    }
+
+    /// Checks whether the settings of this pattern are shared with a clone, in
+    /// which case changing any of them copies them first.
+    ///
+    /// This says nothing about the pattern's [`Image`], which is shared by
+    /// every pattern created from it. A default pattern is never shared.
+    ///
+    /// [`Image`]: ../image/struct.Image.html
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        !self.is_none() && self.ref_count() > 1
+    }
+
+    /// Makes sure the settings of this pattern are referenced by it only, by
+    /// copying them if it [`is_shared`], and returns the pattern for
+    /// mutation.
+    ///
+    /// Only the pattern itself is copied, its [`Image`] stays shared with the
+    /// other patterns, see [`Image::make_mut`] for that.
+    ///
+    /// [`is_shared`]: #method.is_shared
+    /// [`Image`]: ../image/struct.Image.html
+    /// [`Image::make_mut`]: ../image/struct.Image.html#method.make_mut
+    pub fn make_mut(&mut self) -> &mut Pattern {
+        if self.is_shared() {
+            *self = self.clone_deep();
+        }
+        self
+    }
+
     /// The pattern's [`Image`].
     #[inline]
     pub fn image(&self) -> &Image {
@@ -126,6 +179,19 @@ impl Pattern {
     pub fn matrix(&self) -> &Matrix2D {
         unsafe { cast_ref(&self.impl_().matrix) }
     }
+
+    /// Checks whether the pattern's [`Matrix2D`] is not the identity matrix.
+    ///
+    /// The matrix is modified through [`MatrixTransform`], e.g. with
+    /// [`set_matrix`] and [`reset_matrix`].
+    ///
+    /// [`MatrixTransform`]: ../matrix/trait.MatrixTransform.html
+    /// [`set_matrix`]: ../matrix/trait.MatrixTransform.html#method.set_matrix
+    /// [`reset_matrix`]: ../matrix/trait.MatrixTransform.html#method.reset_matrix
+    #[inline]
+    pub fn has_matrix(&self) -> bool {
+        *self.matrix() != Matrix2D::identity()
+    }
 }
 
 impl MatrixTransform for Pattern {
@@ -149,6 +215,14 @@ impl From<&Image> for Pattern {
     }
 }
 
+impl From<Image> for Pattern {
+    /// Creates a pattern that takes over the reference to the image's data.
+    #[inline]
+    fn from(image: Image) -> Self {
+        Self::from(&image)
+    }
+}
+
 impl PartialEq for Pattern {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -178,3 +252,40 @@ impl Drop for Pattern {
         unsafe { ffi::blPatternReset(&mut self.core) };
     }
 }
+
+#[cfg(test)]
+mod test_pattern {
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::{Matrix2D, MatrixTransform};
+    use crate::pattern::Pattern;
+    use crate::ExtendMode;
+
+    #[test]
+    fn test_pattern_image_is_shared() {
+        let mut image = Image::new(2, 2, ImageFormat::PRgb32).unwrap();
+        image.set_pixel(0, 0, 0xFF_FF_00_00);
+        let pattern = Pattern::from(&image);
+        assert!(image.is_shared());
+
+        image.set_pixel(0, 0, 0xFF_00_00_FF);
+        assert_eq!(pattern.image().pixel(0, 0), Some(0xFF_FF_00_00));
+        drop(image);
+        assert_eq!(pattern.image().pixel(0, 0), Some(0xFF_FF_00_00));
+    }
+
+    #[test]
+    fn test_pattern_accessors() {
+        let image = Image::new(2, 2, ImageFormat::PRgb32).unwrap();
+        let mut pattern = Pattern::from(image);
+        pattern.set_extend_mode(ExtendMode::RepeatXRepeatY);
+        assert_eq!(pattern.extend_mode(), ExtendMode::RepeatXRepeatY);
+
+        assert!(!pattern.has_matrix());
+        let matrix = Matrix2D::scaling(2.0, 3.0);
+        pattern.set_matrix(&matrix);
+        assert_eq!(*pattern.matrix(), matrix);
+        assert!(pattern.has_matrix());
+        pattern.reset_matrix();
+        assert!(!pattern.has_matrix());
+    }
+}
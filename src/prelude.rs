@@ -4,5 +4,7 @@ pub use crate::{
     context::{ClipMode, CompOp, Context, ContextCreateInfo},
     image::{Image, ImageFormat},
     matrix::MatrixTransform,
+    rgba::{Rgba32, Rgba64},
+    style::Style,
     DataAccessFlags, DeepClone,
 };
@@ -0,0 +1,84 @@
+//! Pseudo random number generation.
+use std::fmt;
+
+/// A simple pseudo random number generator.
+///
+/// The generator produces the same sequence of numbers for the same seed on
+/// all platforms, which makes it suitable for reproducible randomized
+/// rendering.
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct Random {
+    core: ffi::BLRandom,
+}
+
+impl Random {
+    /// Creates a new generator initialized with the given seed.
+    pub fn new(seed: u64) -> Self {
+        let mut this = Random {
+            core: ffi::BLRandom { data: [0; 2] },
+        };
+        this.reset(seed);
+        this
+    }
+
+    /// Resets the generator to the state it had when created with the given
+    /// seed.
+    #[inline]
+    pub fn reset(&mut self, seed: u64) {
+        unsafe { ffi::blRandomReset(&mut self.core, seed) };
+    }
+
+    /// Returns the next random `u32`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        unsafe { ffi::blRandomNextUInt32(&mut self.core) }
+    }
+
+    /// Returns the next random `u64`.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        unsafe { ffi::blRandomNextUInt64(&mut self.core) }
+    }
+
+    /// Returns the next random `f64` in the range `[0, 1)`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        unsafe { ffi::blRandomNextDouble(&mut self.core) }
+    }
+
+    /// Returns the next random `f64` in the range `[min, max)`.
+    #[inline]
+    pub fn next_range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+}
+
+impl PartialEq for Random {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.core.data == other.core.data
+    }
+}
+
+impl fmt::Debug for Random {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Random").field("data", &self.core.data).finish()
+    }
+}
+
+#[cfg(test)]
+mod test_random {
+    use crate::random::Random;
+
+    #[test]
+    fn test_random_seed() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let values: Vec<_> = (0..8).map(|_| a.next_u64()).collect();
+        assert!(values.iter().all(|&v| v == b.next_u64()));
+        b.reset(42);
+        assert_eq!(b.next_u64(), values[0]);
+        assert!((0..64).map(|_| a.next_f64()).all(|v| v >= 0.0 && v < 1.0));
+    }
+}
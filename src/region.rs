@@ -1,7 +1,7 @@
 use std::borrow::Borrow;
 use std::{fmt, slice};
 
-use crate::error::{errcode_to_result, expect_mem_err, OutOfMemory};
+use crate::error::{errcode_to_result, expect_mem_err, out_of_memory, Error, OutOfMemory, Result};
 use crate::geometry::{BoxI, HitTest, PointI, RectI};
 use crate::variant::WrappedBlCore;
 use crate::BooleanOp;
@@ -16,6 +16,17 @@ bl_enum! {
     Default => Empty
 }
 
+/// How [`Region::from_rects_unmerged`] treats its input.
+///
+/// [`Region::from_rects_unmerged`]: struct.Region.html#method.from_rects_unmerged
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RegionValidation {
+    /// The caller guarantees that the rectangles are valid region data.
+    Trusted,
+    /// The rectangles are verified to be valid region data first.
+    Checked,
+}
+
 #[repr(transparent)]
 pub struct Region {
     core: ffi::BLRegionCore,
@@ -96,9 +107,12 @@ impl Region {
     ///
     /// Panics if blend2d returns an
     /// [`OutOfMemory`](../error/enum.Error.html#variant.OutOfMemory) error
+    /// with the default [`OomPolicy`](../error/enum.OomPolicy.html).
     #[inline]
     pub fn reserve(&mut self, n: usize) {
-        self.try_reserve(n).expect("memory allocation failed")
+        if self.try_reserve(n).is_err() {
+            out_of_memory();
+        }
     }
 
     /// Reserves capacity for at least n boxes.
@@ -125,6 +139,27 @@ impl Region {
         }
     }
 
+    /// Like [`combine`], but returns a failed allocation instead of handling
+    /// it according to the [`OomPolicy`].
+    ///
+    /// [`combine`]: #method.combine
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
+    #[inline]
+    pub fn try_combine(
+        &mut self,
+        other: &Self,
+        op: BooleanOp,
+    ) -> std::result::Result<(), OutOfMemory> {
+        unsafe {
+            OutOfMemory::from_errcode(ffi::blRegionCombine(
+                self.core_mut(),
+                self.core(),
+                other.core(),
+                op.into(),
+            ))
+        }
+    }
+
     #[inline]
     pub fn combine_rb(&mut self, b: &BoxI, op: BooleanOp) {
         unsafe {
@@ -216,6 +251,62 @@ impl Region {
     }
 }
 
+impl Region {
+    /// Returns the boxes of this region as rectangles.
+    pub fn to_rects(&self) -> Vec<RectI> {
+        self.data()
+            .iter()
+            .map(|b| RectI::new(b.x0, b.y0, b.x1 - b.x0, b.y1 - b.y0))
+            .collect()
+    }
+
+    /// Creates a region from rectangles that already form valid region data,
+    /// skipping the union the `From` conversions compute.
+    ///
+    /// Valid region data consists of non-empty rectangles sorted into
+    /// horizontal bands from top to bottom. The rectangles of a band share
+    /// their vertical extent, are sorted from left to right and don't overlap,
+    /// and bands don't overlap each other. With
+    /// [`RegionValidation::Checked`] this is verified and
+    /// [`Error::InvalidValue`] returned if it doesn't hold, with
+    /// [`RegionValidation::Trusted`] the rectangles are taken as they are.
+    ///
+    /// [`RegionValidation::Checked`]: enum.RegionValidation.html#variant.Checked
+    /// [`RegionValidation::Trusted`]: enum.RegionValidation.html#variant.Trusted
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn from_rects_unmerged(rects: &[RectI], validation: RegionValidation) -> Result<Region> {
+        let boxes: Vec<BoxI> = rects
+            .iter()
+            .map(|r| BoxI::new(r.x, r.y, r.x + r.w, r.y + r.h))
+            .collect();
+        if validation == RegionValidation::Checked && !is_valid_region_data(&boxes) {
+            return Err(Error::InvalidValue);
+        }
+        let mut this = Self::new();
+        unsafe {
+            errcode_to_result(ffi::blRegionAssignBoxIArray(
+                this.core_mut(),
+                boxes.as_ptr() as *const _,
+                boxes.len(),
+            ))?
+        };
+        Ok(this)
+    }
+}
+
+fn is_valid_region_data(boxes: &[BoxI]) -> bool {
+    boxes.iter().all(|b| b.x0 < b.x1 && b.y0 < b.y1)
+        && boxes.windows(2).all(|w| {
+            let (a, b) = (&w[0], &w[1]);
+            if a.y0 == b.y0 {
+                // same band
+                a.y1 == b.y1 && a.x1 <= b.x0
+            } else {
+                a.y1 <= b.y0
+            }
+        })
+}
+
 impl From<BoxI> for Region {
     fn from(b: BoxI) -> Self {
         let mut this = Self::new();
@@ -322,3 +413,45 @@ impl Drop for Region {
         unsafe { ffi::blRegionReset(&mut self.core) };
     }
 }
+
+#[cfg(test)]
+mod test_region {
+    use crate::geometry::RectI;
+    use crate::region::{Region, RegionValidation};
+    use crate::BooleanOp;
+
+    #[test]
+    fn test_region_rects() {
+        let rects = [
+            RectI::new(0, 0, 10, 10),
+            RectI::new(20, 0, 10, 10),
+            RectI::new(0, 10, 30, 5),
+        ];
+        let region = Region::from_rects_unmerged(&rects, RegionValidation::Checked).unwrap();
+        assert_eq!(region.to_rects(), rects);
+        assert_eq!(
+            Region::from(&rects[..]).bounding_box(),
+            region.bounding_box()
+        );
+
+        let overlapping = [RectI::new(0, 0, 10, 10), RectI::new(5, 0, 10, 10)];
+        assert!(Region::from_rects_unmerged(&overlapping, RegionValidation::Checked).is_err());
+        let unsorted = [RectI::new(0, 10, 10, 10), RectI::new(0, 0, 10, 10)];
+        assert!(Region::from_rects_unmerged(&unsorted, RegionValidation::Checked).is_err());
+        assert!(Region::from_rects_unmerged(&[], RegionValidation::Trusted)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_try_combine() {
+        let a = Region::from(RectI::new(0, 0, 10, 10));
+        let b = Region::from(RectI::new(5, 0, 10, 10));
+        let mut expected = a.clone();
+        expected.combine(&b, BooleanOp::Or);
+        let mut region = a;
+        region.try_combine(&b, BooleanOp::Or).unwrap();
+        assert_eq!(region, expected);
+        assert_eq!(region.to_rects(), [RectI::new(0, 0, 15, 10)]);
+    }
+}
@@ -0,0 +1,378 @@
+//! 32-bit and 64-bit RGBA colors.
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::image::{premultiply_pixel, unpremultiply_pixel};
+
+/// A non-premultiplied 32-bit color in the `0xAARRGGBB` format.
+///
+/// This is the format blend2d uses for all its 32-bit color APIs, so a
+/// `u32` can be converted into an [`Rgba32`] and back without any loss.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Rgba32 {
+    pub value: u32,
+}
+
+impl Rgba32 {
+    /// Fully transparent black.
+    pub const TRANSPARENT: Rgba32 = Rgba32::new(0x00_00_00_00);
+    /// Opaque black.
+    pub const BLACK: Rgba32 = Rgba32::new(0xFF_00_00_00);
+    /// Opaque white.
+    pub const WHITE: Rgba32 = Rgba32::new(0xFF_FF_FF_FF);
+
+    /// Creates a color from a `0xAARRGGBB` value.
+    #[inline]
+    pub const fn new(value: u32) -> Self {
+        Rgba32 { value }
+    }
+
+    /// Creates a color from its components.
+    #[inline]
+    pub const fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba32::new((a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// Creates an opaque color from its color components.
+    #[inline]
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba32::from_rgba(r, g, b, 0xFF)
+    }
+
+    #[inline]
+    pub fn r(self) -> u8 {
+        (self.value >> 16) as u8
+    }
+
+    #[inline]
+    pub fn g(self) -> u8 {
+        (self.value >> 8) as u8
+    }
+
+    #[inline]
+    pub fn b(self) -> u8 {
+        self.value as u8
+    }
+
+    #[inline]
+    pub fn a(self) -> u8 {
+        (self.value >> 24) as u8
+    }
+
+    /// Returns the components of this color in `[r, g, b, a]` order.
+    #[inline]
+    pub fn to_array(self) -> [u8; 4] {
+        [self.r(), self.g(), self.b(), self.a()]
+    }
+
+    /// Returns this color with its alpha component replaced.
+    #[inline]
+    pub fn with_alpha(self, a: u8) -> Self {
+        Rgba32::new((self.value & 0x00_FF_FF_FF) | u32::from(a) << 24)
+    }
+
+    /// Returns true if the alpha component is `0xFF`.
+    #[inline]
+    pub fn is_opaque(self) -> bool {
+        self.a() == 0xFF
+    }
+
+    /// Returns true if the alpha component is zero.
+    #[inline]
+    pub fn is_transparent(self) -> bool {
+        self.a() == 0
+    }
+
+    /// Multiplies the color components with the alpha component, producing a
+    /// pixel value as stored in [`ImageFormat::PRgb32`] images.
+    ///
+    /// [`ImageFormat::PRgb32`]: ../image/enum.ImageFormat.html#variant.PRgb32
+    #[inline]
+    pub fn premultiplied(self) -> Self {
+        Rgba32::new(premultiply_pixel(self.value))
+    }
+
+    /// Divides the color components by the alpha component, reverting
+    /// [`premultiplied`](#method.premultiplied) up to rounding errors.
+    #[inline]
+    pub fn unpremultiplied(self) -> Self {
+        Rgba32::new(unpremultiply_pixel(self.value))
+    }
+}
+
+impl From<u32> for Rgba32 {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Rgba32::new(value)
+    }
+}
+
+impl From<Rgba32> for u32 {
+    #[inline]
+    fn from(color: Rgba32) -> Self {
+        color.value
+    }
+}
+
+impl From<[u8; 4]> for Rgba32 {
+    /// Creates a color from its components in `[r, g, b, a]` order.
+    #[inline]
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Rgba32::from_rgba(r, g, b, a)
+    }
+}
+
+impl From<Rgba64> for Rgba32 {
+    /// Narrows the color by dropping the low byte of each component.
+    #[inline]
+    fn from(color: Rgba64) -> Self {
+        let narrow = |c: u16| (c >> 8) as u8;
+        Rgba32::from_rgba(
+            narrow(color.r()),
+            narrow(color.g()),
+            narrow(color.b()),
+            narrow(color.a()),
+        )
+    }
+}
+
+impl FromStr for Rgba32 {
+    type Err = Error;
+
+    /// Parses a CSS style hex color in one of the `RGB`, `RGBA`, `RRGGBB` or
+    /// `RRGGBBAA` forms, optionally prefixed with a `#`.
+    ///
+    /// Note that the alpha component comes last in the textual form, unlike in
+    /// the `0xAARRGGBB` value. Returns [`Error::InvalidValue`] if the string is
+    /// not a valid hex color.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = parse_hex_digits(s)?;
+        let short = |i: usize| digits[i] * 0x11;
+        let long = |i: usize| digits[i * 2] << 4 | digits[i * 2 + 1];
+        match digits.len() {
+            3 => Ok(Rgba32::from_rgb(short(0), short(1), short(2))),
+            4 => Ok(Rgba32::from_rgba(short(0), short(1), short(2), short(3))),
+            6 => Ok(Rgba32::from_rgb(long(0), long(1), long(2))),
+            8 => Ok(Rgba32::from_rgba(long(0), long(1), long(2), long(3))),
+            _ => Err(Error::InvalidValue),
+        }
+    }
+}
+
+/// A non-premultiplied 64-bit color in the `0xAAAARRRRGGGGBBBB` format.
+///
+/// This is the format blend2d uses for all its 64-bit color APIs, so a
+/// `u64` can be converted into an [`Rgba64`] and back without any loss.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Rgba64 {
+    pub value: u64,
+}
+
+impl Rgba64 {
+    /// Fully transparent black.
+    pub const TRANSPARENT: Rgba64 = Rgba64::new(0x0000_0000_0000_0000);
+    /// Opaque black.
+    pub const BLACK: Rgba64 = Rgba64::new(0xFFFF_0000_0000_0000);
+    /// Opaque white.
+    pub const WHITE: Rgba64 = Rgba64::new(0xFFFF_FFFF_FFFF_FFFF);
+
+    /// Creates a color from a `0xAAAARRRRGGGGBBBB` value.
+    #[inline]
+    pub const fn new(value: u64) -> Self {
+        Rgba64 { value }
+    }
+
+    /// Creates a color from its components.
+    #[inline]
+    pub const fn from_rgba(r: u16, g: u16, b: u16, a: u16) -> Self {
+        Rgba64::new((a as u64) << 48 | (r as u64) << 32 | (g as u64) << 16 | b as u64)
+    }
+
+    /// Creates an opaque color from its color components.
+    #[inline]
+    pub const fn from_rgb(r: u16, g: u16, b: u16) -> Self {
+        Rgba64::from_rgba(r, g, b, 0xFFFF)
+    }
+
+    #[inline]
+    pub fn r(self) -> u16 {
+        (self.value >> 32) as u16
+    }
+
+    #[inline]
+    pub fn g(self) -> u16 {
+        (self.value >> 16) as u16
+    }
+
+    #[inline]
+    pub fn b(self) -> u16 {
+        self.value as u16
+    }
+
+    #[inline]
+    pub fn a(self) -> u16 {
+        (self.value >> 48) as u16
+    }
+
+    /// Returns the components of this color in `[r, g, b, a]` order.
+    #[inline]
+    pub fn to_array(self) -> [u16; 4] {
+        [self.r(), self.g(), self.b(), self.a()]
+    }
+
+    /// Returns this color with its alpha component replaced.
+    #[inline]
+    pub fn with_alpha(self, a: u16) -> Self {
+        Rgba64::new((self.value & 0x0000_FFFF_FFFF_FFFF) | u64::from(a) << 48)
+    }
+
+    /// Returns true if the alpha component is `0xFFFF`.
+    #[inline]
+    pub fn is_opaque(self) -> bool {
+        self.a() == 0xFFFF
+    }
+
+    /// Returns true if the alpha component is zero.
+    #[inline]
+    pub fn is_transparent(self) -> bool {
+        self.a() == 0
+    }
+
+    /// Multiplies the color components with the alpha component, rounding
+    /// to the nearest value.
+    #[inline]
+    pub fn premultiplied(self) -> Self {
+        let a = u64::from(self.a());
+        let mul = |c: u16| ((u64::from(c) * a + 0x7FFF) / 0xFFFF) as u16;
+        Rgba64::from_rgba(mul(self.r()), mul(self.g()), mul(self.b()), self.a())
+    }
+
+    /// Divides the color components by the alpha component, reverting
+    /// [`premultiplied`](#method.premultiplied) up to rounding errors.
+    #[inline]
+    pub fn unpremultiplied(self) -> Self {
+        let a = u64::from(self.a());
+        if a == 0 {
+            return Rgba64::TRANSPARENT;
+        }
+        let div = |c: u16| ((u64::from(c) * 0xFFFF + a / 2) / a).min(0xFFFF) as u16;
+        Rgba64::from_rgba(div(self.r()), div(self.g()), div(self.b()), self.a())
+    }
+}
+
+impl From<u64> for Rgba64 {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Rgba64::new(value)
+    }
+}
+
+impl From<Rgba64> for u64 {
+    #[inline]
+    fn from(color: Rgba64) -> Self {
+        color.value
+    }
+}
+
+impl From<[u16; 4]> for Rgba64 {
+    /// Creates a color from its components in `[r, g, b, a]` order.
+    #[inline]
+    fn from([r, g, b, a]: [u16; 4]) -> Self {
+        Rgba64::from_rgba(r, g, b, a)
+    }
+}
+
+impl From<Rgba32> for Rgba64 {
+    /// Widens the color by repeating each 8-bit component, so `0xFF` maps to
+    /// `0xFFFF`.
+    #[inline]
+    fn from(color: Rgba32) -> Self {
+        let widen = |c: u8| u16::from(c) * 0x0101;
+        Rgba64::from_rgba(
+            widen(color.r()),
+            widen(color.g()),
+            widen(color.b()),
+            widen(color.a()),
+        )
+    }
+}
+
+impl FromStr for Rgba64 {
+    type Err = Error;
+
+    /// Parses a hex color in any of the forms accepted by [`Rgba32`], or in
+    /// one of the 16-bit per component `RRRRGGGGBBBB` or `RRRRGGGGBBBBAAAA`
+    /// forms, optionally prefixed with a `#`.
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = parse_hex_digits(s)?;
+        let long = |i: usize| {
+            digits[i * 4..i * 4 + 4]
+                .iter()
+                .fold(0u16, |acc, &d| acc << 4 | u16::from(d))
+        };
+        match digits.len() {
+            12 => Ok(Rgba64::from_rgb(long(0), long(1), long(2))),
+            16 => Ok(Rgba64::from_rgba(long(0), long(1), long(2), long(3))),
+            _ => s.parse::<Rgba32>().map(Rgba64::from),
+        }
+    }
+}
+
+/// Strips an optional leading `#` and converts the remaining hex digits.
+fn parse_hex_digits(s: &str) -> Result<Vec<u8>> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    s.chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8).ok_or(Error::InvalidValue))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_rgba {
+    use crate::rgba::{Rgba32, Rgba64};
+
+    #[test]
+    fn test_rgba32_components() {
+        let color = Rgba32::from_rgba(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(u32::from(color), 0x78_12_34_56);
+        assert_eq!(color.to_array(), [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(color.with_alpha(0xFF), Rgba32::from_rgb(0x12, 0x34, 0x56));
+    }
+
+    #[test]
+    fn test_rgba_parse() {
+        assert_eq!("#F80".parse().ok(), Some(Rgba32::new(0xFF_FF_88_00)));
+        assert_eq!("f808".parse().ok(), Some(Rgba32::new(0x88_FF_88_00)));
+        assert_eq!("#123456".parse().ok(), Some(Rgba32::new(0xFF_12_34_56)));
+        assert_eq!("#12345678".parse().ok(), Some(Rgba32::new(0x78_12_34_56)));
+        assert!("#12345".parse::<Rgba32>().is_err());
+        assert!("#GGG".parse::<Rgba32>().is_err());
+        assert_eq!("#FFF".parse().ok(), Some(Rgba64::WHITE));
+        assert_eq!(
+            "#111122223333".parse().ok(),
+            Some(Rgba64::new(0xFFFF_1111_2222_3333))
+        );
+    }
+
+    #[test]
+    fn test_rgba_premultiply() {
+        let color = Rgba32::from_rgba(0xFF, 0x80, 0x00, 0x80);
+        let premultiplied = color.premultiplied();
+        assert_eq!(premultiplied, Rgba32::from_rgba(0x80, 0x40, 0x00, 0x80));
+        assert_eq!(premultiplied.unpremultiplied(), color);
+        assert_eq!(Rgba32::TRANSPARENT.unpremultiplied(), Rgba32::TRANSPARENT);
+        assert_eq!(Rgba32::from_rgba(1, 0, 0, 128).premultiplied().r(), 1);
+        assert_eq!(Rgba64::from_rgba(1, 0, 0, 0x8000).premultiplied().r(), 1);
+    }
+
+    #[test]
+    fn test_rgba_widen_narrow() {
+        let color = Rgba32::new(0x80_FF_40_00);
+        let wide = Rgba64::from(color);
+        assert_eq!(wide, Rgba64::new(0x8080_FFFF_4040_0000));
+        assert_eq!(Rgba32::from(wide), color);
+    }
+}
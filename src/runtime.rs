@@ -1,6 +1,8 @@
+use std::fmt;
+
 use bitflags::bitflags;
 
-use crate::error::{errcode_to_result, Result};
+use crate::error::{self, errcode_to_result, ErrorHook, OomPolicy, Result};
 
 use ffi::BLRuntimeBuildType::*;
 bl_enum! {
@@ -208,3 +210,96 @@ pub fn query_memory_info() -> Result<MemoryInfo> {
         .map(|_| info)
     }
 }
+
+/// Scopes the use of blend2d's runtime, releasing its pooled resources and
+/// restoring the error handling once dropped.
+///
+/// The runtime itself lives as long as the process, but its memory and
+/// thread pools grow with use. Long running processes can wrap units of work
+/// into a guard to give these resources back deterministically, instead of
+/// calling [`cleanup`] by hand.
+///
+/// [`cleanup`]: fn.cleanup.html
+pub struct RuntimeGuard {
+    flags: CleanupFlags,
+    oom_policy: Option<OomPolicy>,
+    error_hook: Option<Option<ErrorHook>>,
+}
+
+impl RuntimeGuard {
+    /// Creates a guard that cleans up the resources described by the given
+    /// [`CleanupFlags`] when dropped.
+    ///
+    /// [`CleanupFlags`]: struct.CleanupFlags.html
+    #[inline]
+    pub fn new(flags: CleanupFlags) -> Self {
+        RuntimeGuard {
+            flags,
+            oom_policy: None,
+            error_hook: None,
+        }
+    }
+
+    /// Sets the [`OomPolicy`] until the guard is dropped, see
+    /// [`set_oom_policy`].
+    ///
+    /// [`OomPolicy`]: ../error/enum.OomPolicy.html
+    /// [`set_oom_policy`]: ../error/fn.set_oom_policy.html
+    pub fn with_oom_policy(mut self, policy: OomPolicy) -> Self {
+        let previous = error::oom_policy();
+        error::set_oom_policy(policy);
+        self.oom_policy.get_or_insert(previous);
+        self
+    }
+
+    /// Installs the error hook until the guard is dropped, see
+    /// [`set_error_hook`].
+    ///
+    /// [`set_error_hook`]: ../error/fn.set_error_hook.html
+    pub fn with_error_hook(mut self, hook: ErrorHook) -> Self {
+        let previous = error::set_error_hook(Some(hook));
+        self.error_hook.get_or_insert(previous);
+        self
+    }
+
+    /// Cleans up the resources now without waiting for the guard to be
+    /// dropped.
+    #[inline]
+    pub fn cleanup(&self) -> Result<()> {
+        cleanup(self.flags)
+    }
+}
+
+impl fmt::Debug for RuntimeGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuntimeGuard")
+            .field("flags", &self.flags)
+            .field("oom_policy", &self.oom_policy)
+            .finish()
+    }
+}
+
+impl Drop for RuntimeGuard {
+    fn drop(&mut self) {
+        let _ = cleanup(self.flags);
+        if let Some(policy) = self.oom_policy {
+            error::set_oom_policy(policy);
+        }
+        if let Some(hook) = self.error_hook {
+            error::set_error_hook(hook);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_runtime {
+    use crate::runtime::{query_memory_info, CleanupFlags, RuntimeGuard};
+
+    #[test]
+    fn test_runtime_guard() {
+        let guard = RuntimeGuard::new(CleanupFlags::all());
+        assert!(guard.cleanup().is_ok());
+        drop(guard);
+        assert!(query_memory_info().is_ok());
+    }
+}
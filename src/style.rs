@@ -0,0 +1,182 @@
+//! Fill and stroke styles.
+use crate::context::{Context, ContextOpType};
+use crate::gradient::{DynamicGradient, Gradient, GradientType};
+use crate::pattern::Pattern;
+use crate::rgba::{Rgba32, Rgba64};
+
+mod css;
+
+mod private {
+    use super::*;
+
+    pub trait Sealed {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for Rgba32 {}
+    impl Sealed for Rgba64 {}
+    impl<T: GradientType> Sealed for Gradient<T> {}
+    impl Sealed for DynamicGradient {}
+    impl Sealed for Pattern {}
+    impl Sealed for Style {}
+}
+
+/// A type that can be used as the fill or stroke style of a [`Context`].
+///
+/// Implemented by `0xAARRGGBB` colors as `u32` or [`Rgba32`],
+/// `0xAAAARRRRGGGGBBBB` colors as `u64` or [`Rgba64`], gradients, patterns
+/// and [`Style`].
+///
+/// [`Rgba32`]: ../rgba/struct.Rgba32.html
+/// [`Rgba64`]: ../rgba/struct.Rgba64.html
+///
+/// [`Context`]: ../context/struct.Context.html
+pub trait FillStrokeStyle: private::Sealed {
+    #[doc(hidden)]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType);
+}
+
+impl FillStrokeStyle for u32 {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match op {
+            ContextOpType::Fill => ctx.set_fill_style_rgba32(*self),
+            ContextOpType::Stroke => ctx.set_stroke_style_rgba32(*self),
+        }
+    }
+}
+
+impl FillStrokeStyle for u64 {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match op {
+            ContextOpType::Fill => ctx.set_fill_style_rgba64(*self),
+            ContextOpType::Stroke => ctx.set_stroke_style_rgba64(*self),
+        }
+    }
+}
+
+impl FillStrokeStyle for Rgba32 {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        self.value.apply_style(ctx, op)
+    }
+}
+
+impl FillStrokeStyle for Rgba64 {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        self.value.apply_style(ctx, op)
+    }
+}
+
+impl<T: GradientType> FillStrokeStyle for Gradient<T> {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match op {
+            ContextOpType::Fill => ctx.set_fill_style_gradient(self),
+            ContextOpType::Stroke => ctx.set_stroke_style_gradient(self),
+        }
+    }
+}
+
+impl FillStrokeStyle for DynamicGradient {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match self {
+            DynamicGradient::Linear(g) => g.apply_style(ctx, op),
+            DynamicGradient::Radial(g) => g.apply_style(ctx, op),
+            DynamicGradient::Conical(g) => g.apply_style(ctx, op),
+        }
+    }
+}
+
+impl FillStrokeStyle for Pattern {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match op {
+            ContextOpType::Fill => ctx.set_fill_style_pattern(self),
+            ContextOpType::Stroke => ctx.set_stroke_style_pattern(self),
+        }
+    }
+}
+
+impl FillStrokeStyle for Style {
+    #[inline]
+    fn apply_style(&self, ctx: &mut Context<'_>, op: ContextOpType) {
+        match self {
+            Style::Solid(color) => color.apply_style(ctx, op),
+            Style::Gradient(gradient) => gradient.apply_style(ctx, op),
+            Style::Pattern(pattern) => pattern.apply_style(ctx, op),
+        }
+    }
+}
+
+/// A fill or stroke style of a [`Context`].
+///
+/// Gradients and patterns are reference counted, so a style returned by
+/// [`Context::fill_style`] or [`Context::stroke_style`] shares its data with
+/// the context's style.
+///
+/// [`Context`]: ../context/struct.Context.html
+/// [`Context::fill_style`]: ../context/struct.Context.html#method.fill_style
+/// [`Context::stroke_style`]: ../context/struct.Context.html#method.stroke_style
+#[derive(Clone, Debug, PartialEq)]
+pub enum Style {
+    /// A solid color in the `0xAAAARRRRGGGGBBBB` format.
+    Solid(u64),
+    Gradient(DynamicGradient),
+    Pattern(Pattern),
+}
+
+impl Style {
+    /// Creates a solid style from a `0xAARRGGBB` color.
+    #[inline]
+    pub fn from_rgba32<C: Into<Rgba32>>(color: C) -> Self {
+        Style::Solid(Rgba64::from(color.into()).value)
+    }
+}
+
+impl From<u64> for Style {
+    #[inline]
+    fn from(color: u64) -> Self {
+        Style::Solid(color)
+    }
+}
+
+impl From<Rgba32> for Style {
+    #[inline]
+    fn from(color: Rgba32) -> Self {
+        Style::from_rgba32(color)
+    }
+}
+
+impl From<Rgba64> for Style {
+    #[inline]
+    fn from(color: Rgba64) -> Self {
+        Style::Solid(color.value)
+    }
+}
+
+impl From<DynamicGradient> for Style {
+    #[inline]
+    fn from(gradient: DynamicGradient) -> Self {
+        Style::Gradient(gradient)
+    }
+}
+
+impl<T: GradientType> From<Gradient<T>> for Style
+where
+    DynamicGradient: From<Gradient<T>>,
+{
+    #[inline]
+    fn from(gradient: Gradient<T>) -> Self {
+        Style::Gradient(gradient.into())
+    }
+}
+
+impl From<Pattern> for Style {
+    #[inline]
+    fn from(pattern: Pattern) -> Self {
+        Style::Pattern(pattern)
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::{Error, Result};
+use crate::geometry::RectD;
+use crate::gradient::{GradientStop, LinearGradient, LinearGradientValues};
+use crate::rgba::Rgba32;
+use crate::svg_attrs;
+use crate::ExtendMode;
+
+use super::Style;
+
+impl Style {
+    /// Parses a CSS color or a `linear-gradient()` into a style.
+    ///
+    /// Colors can be given in hex, `rgb()`, `rgba()`, `hsl()`, `hsla()` or
+    /// named form. Gradients take an optional direction as an angle or a
+    /// `to <side-or-corner>` keyword followed by color stops with optional
+    /// percentage offsets, the gradient line is laid out for the `bounds` box
+    /// as CSS does for the box of the element.
+    ///
+    /// Returns [`Error::InvalidValue`] if the value can't be parsed.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn from_css(value: &str, bounds: &RectD) -> Result<Style> {
+        let value = value.trim();
+        match value
+            .strip_prefix("linear-gradient(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            Some(args) => linear_gradient(args, bounds).map(Style::from),
+            None => Rgba32::from_css(value).map(Style::from),
+        }
+    }
+}
+
+impl Rgba32 {
+    /// Parses a CSS color in hex, `rgb()`, `rgba()`, `hsl()`, `hsla()` or
+    /// named form.
+    ///
+    /// Returns [`Error::InvalidValue`] if the value can't be parsed.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    #[inline]
+    pub fn from_css(value: &str) -> Result<Rgba32> {
+        svg_attrs::color(value)
+    }
+}
+
+fn linear_gradient(args: &str, bounds: &RectD) -> Result<LinearGradient> {
+    let mut args = split_top_level(args);
+    // points down, the default of `to bottom`
+    let mut direction = (0.0, 1.0);
+    if let Some(first) = args.first() {
+        if let Some(sides) = first.strip_prefix("to ") {
+            direction = side_direction(sides, bounds)?;
+            args.remove(0);
+        } else if let Ok(degrees) = svg_attrs::angle(first) {
+            let radians = degrees.to_radians();
+            direction = (radians.sin(), -radians.cos());
+            args.remove(0);
+        }
+    }
+    if args.len() < 2 {
+        return Err(Error::InvalidValue);
+    }
+
+    let mut colors = Vec::with_capacity(args.len());
+    let mut offsets = Vec::with_capacity(args.len());
+    for arg in args {
+        // the color may contain spaces itself, the offset comes last
+        let (color, offset) = match arg.rfind(char::is_whitespace) {
+            Some(split) if arg.ends_with('%') => {
+                let offset = svg_attrs::fraction(&arg[split..])?;
+                (&arg[..split], Some(offset))
+            },
+            _ => (arg, None),
+        };
+        colors.push(Rgba32::from_css(color)?);
+        offsets.push(offset);
+    }
+    let offsets = resolve_offsets(&offsets);
+
+    // the gradient line runs through the center of the box and is just long
+    // enough for its perpendiculars through the start and end to touch the
+    // corners of the box
+    let (dx, dy) = direction;
+    let half = (bounds.w * dx.abs() + bounds.h * dy.abs()) / 2.0;
+    let (cx, cy) = (bounds.x + bounds.w / 2.0, bounds.y + bounds.h / 2.0);
+    let values = LinearGradientValues {
+        x0: cx - dx * half,
+        y0: cy - dy * half,
+        x1: cx + dx * half,
+        y1: cy + dy * half,
+    };
+    let stops = offsets
+        .into_iter()
+        .zip(colors)
+        .map(|(offset, color)| GradientStop::new(offset, color))
+        .collect::<Vec<_>>();
+    Ok(LinearGradient::new(
+        &values,
+        ExtendMode::PadXPadY,
+        &stops,
+        None,
+    ))
+}
+
+/// The unit direction of a `to <side-or-corner>` keyword. Corners point
+/// perpendicular to the diagonal between the two neighbouring corners, so the
+/// middle of the gradient runs through them.
+fn side_direction(sides: &str, bounds: &RectD) -> Result<(f64, f64)> {
+    let (mut x, mut y) = (0.0, 0.0);
+    for side in sides.split_whitespace() {
+        match side {
+            "left" if x == 0.0 => x = -1.0,
+            "right" if x == 0.0 => x = 1.0,
+            "top" if y == 0.0 => y = -1.0,
+            "bottom" if y == 0.0 => y = 1.0,
+            _ => return Err(Error::InvalidValue),
+        }
+    }
+    let (dx, dy) = match (x != 0.0, y != 0.0) {
+        (true, true) => (x * bounds.h, y * bounds.w),
+        (false, false) => return Err(Error::InvalidValue),
+        _ => (x, y),
+    };
+    let len = dx.hypot(dy);
+    if len == 0.0 {
+        Ok((x, y))
+    } else {
+        Ok((dx / len, dy / len))
+    }
+}
+
+/// Fills in missing stop offsets, the first and last stop default to the
+/// ends of the gradient and the stops in between are spread evenly between
+/// their positioned neighbours. Offsets never decrease.
+fn resolve_offsets(offsets: &[Option<f64>]) -> Vec<f64> {
+    let last = offsets.len() - 1;
+    let mut resolved = offsets
+        .iter()
+        .enumerate()
+        .map(|(i, offset)| match (offset, i) {
+            (Some(offset), _) => Some(*offset),
+            (None, 0) => Some(0.0),
+            (None, i) if i == last => Some(1.0),
+            (None, _) => None,
+        })
+        .collect::<Vec<_>>();
+    let mut max = 0.0f64;
+    for offset in resolved.iter_mut().flatten() {
+        max = max.max(*offset);
+        *offset = max;
+    }
+    let mut start = 0;
+    while start < last {
+        let end = start
+            + 1
+            + resolved[start + 1..]
+                .iter()
+                .position(Option::is_some)
+                .unwrap();
+        let (from, to) = (resolved[start].unwrap(), resolved[end].unwrap());
+        for i in start + 1..end {
+            let t = (i - start) as f64 / (end - start) as f64;
+            resolved[i] = Some(from + (to - from) * t);
+        }
+        start = end;
+    }
+    resolved.into_iter().map(Option::unwrap).collect()
+}
+
+/// Splits the arguments of a CSS function at the commas that aren't nested
+/// in parentheses.
+fn split_top_level(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(args[start..].trim());
+    parts
+}
+
+#[cfg(test)]
+mod test_css {
+    use crate::geometry::RectD;
+    use crate::gradient::DynamicGradient;
+    use crate::rgba::Rgba32;
+    use crate::style::Style;
+
+    #[test]
+    fn test_css_colors() {
+        let red = Rgba32::new(0xFF_FF_00_00);
+        for value in &[
+            "#f00",
+            "red",
+            "rgb(255, 0, 0)",
+            "rgb(100% 0% 0%)",
+            "hsl(0, 100%, 50%)",
+        ] {
+            assert_eq!(Rgba32::from_css(value).unwrap(), red, "{}", value);
+        }
+        assert_eq!(
+            Rgba32::from_css("rgba(0, 0, 255, 0.5)").unwrap(),
+            Rgba32::new(0x80_00_00_FF)
+        );
+        assert_eq!(
+            Rgba32::from_css("hsl(120deg 100% 25% / 50%)").unwrap(),
+            Rgba32::new(0x80_00_80_00)
+        );
+        assert!(Rgba32::from_css("rgb(1, 2)").is_err());
+        assert!(Rgba32::from_css("nocolor").is_err());
+    }
+
+    #[test]
+    fn test_css_linear_gradient() {
+        let bounds = RectD::new(0.0, 0.0, 100.0, 50.0);
+        let style = Style::from_css(
+            "linear-gradient(to right, red, rgb(0, 0, 255) 80%)",
+            &bounds,
+        )
+        .unwrap();
+        let gradient = match style {
+            Style::Gradient(DynamicGradient::Linear(gradient)) => gradient,
+            _ => panic!("expected a linear gradient"),
+        };
+        assert_eq!((gradient.x0(), gradient.y0()), (0.0, 25.0));
+        assert_eq!((gradient.x1(), gradient.y1()), (100.0, 25.0));
+        let offsets = gradient
+            .stops()
+            .iter()
+            .map(|s| s.offset)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [0.0, 0.8]);
+
+        let style = Style::from_css("linear-gradient(red, lime, blue)", &bounds).unwrap();
+        let gradient = match style {
+            Style::Gradient(DynamicGradient::Linear(gradient)) => gradient,
+            _ => panic!("expected a linear gradient"),
+        };
+        assert_eq!((gradient.y0(), gradient.y1()), (0.0, 50.0));
+        let offsets = gradient
+            .stops()
+            .iter()
+            .map(|s| s.offset)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [0.0, 0.5, 1.0]);
+
+        assert!(Style::from_css("linear-gradient(red)", &bounds).is_err());
+        assert_eq!(
+            Style::from_css("#00ff00", &bounds).unwrap(),
+            Style::from_rgba32(0xFF_00_FF_00)
+        );
+    }
+}
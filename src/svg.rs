@@ -0,0 +1,616 @@
+//! Parsing and rendering of SVG documents.
+//!
+//! Only a static subset of SVG 1.1 is supported, that is groups, paths, the
+//! basic shapes, solid and gradient fills and strokes, transforms and the
+//! `viewBox` of the root element. Presentation attributes and `style`
+//! declarations are both recognized and inherited like in SVG. Text, images,
+//! `use` references, clipping, masking, filters, markers and dashing are
+//! ignored, as are unknown elements and properties.
+use std::collections::HashMap;
+
+use roxmltree::Node as XmlNode;
+
+use crate::context::Context;
+use crate::error::{Error, Result};
+use crate::geometry::{Circle, Ellipse, GeometryDirection, RectD, RoundRect};
+use crate::gradient::{from_svg_attrs, DynamicGradient, SvgGradientKind};
+use crate::image::{Image, ImageFormat};
+use crate::matrix::{Matrix2D, MatrixTransform};
+use crate::path::{FillRule, Path, StrokeCap, StrokeJoin};
+use crate::rgba::Rgba32;
+use crate::style::Style;
+use crate::svg_attrs;
+
+/// A parsed SVG document that can be rendered any number of times.
+#[derive(Debug, Clone)]
+pub struct SvgDocument {
+    width: f64,
+    height: f64,
+    view_box: Matrix2D,
+    nodes: Vec<Node>,
+    gradients: HashMap<String, SvgGradient>,
+}
+
+impl SvgDocument {
+    /// Parses an SVG document.
+    ///
+    /// The size of the document is taken from the `width` and `height`
+    /// attributes of the root element and falls back to the size of its
+    /// `viewBox`. Returns [`Error::InvalidValue`] if the document isn't
+    /// well-formed XML, its root isn't an `svg` element, its size can't be
+    /// determined or a supported attribute has a malformed value.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn parse(text: &str) -> Result<SvgDocument> {
+        let xml = roxmltree::Document::parse(text).map_err(|_| Error::InvalidValue)?;
+        let root = xml.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(Error::InvalidValue);
+        }
+
+        let view_box = root
+            .attribute("viewBox")
+            .map(svg_attrs::number_list)
+            .transpose()?;
+        let view_box = match view_box.as_deref() {
+            Some(&[x, y, w, h]) if w > 0.0 && h > 0.0 => Some(RectD::new(x, y, w, h)),
+            Some(_) => return Err(Error::InvalidValue),
+            None => None,
+        };
+        let size = |name: &str, view_box_size: Option<f64>| match root.attribute(name) {
+            Some(value) => svg_attrs::length(value, view_box_size.unwrap_or(0.0)),
+            None => view_box_size.ok_or(Error::InvalidValue),
+        };
+        let width = size("width", view_box.map(|b| b.w))?;
+        let height = size("height", view_box.map(|b| b.h))?;
+        if width <= 0.0 || height <= 0.0 {
+            return Err(Error::InvalidValue);
+        }
+
+        let view_box_matrix = match view_box {
+            Some(view_box) => view_box_matrix(
+                &view_box,
+                width,
+                height,
+                root.attribute("preserveAspectRatio").unwrap_or(""),
+            )?,
+            None => Matrix2D::identity(),
+        };
+        let viewport = view_box.map_or((width, height), |b| (b.w, b.h));
+        let parser = Parser { viewport };
+
+        let mut gradients = HashMap::new();
+        for node in xml.descendants().filter(|node| node.is_element()) {
+            if let Some(id) = node.attribute("id") {
+                if let Some(gradient) = parser.gradient(node)? {
+                    gradients.insert(id.to_owned(), gradient);
+                }
+            }
+        }
+        let (props, _) = parser.properties(root, &Properties::default())?;
+        let nodes = parser.children(root, &props)?;
+
+        Ok(SvgDocument {
+            width,
+            height,
+            view_box: view_box_matrix,
+            nodes,
+            gradients,
+        })
+    }
+
+    /// The width of the document.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// The height of the document.
+    #[inline]
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Renders the document with its top left corner at the origin of the
+    /// context's current user space, one unit per document pixel.
+    ///
+    /// Rendering is clipped to the document's size, the context state is
+    /// left untouched afterwards. Group opacity is applied to each element of
+    /// the group separately, so overlapping elements of translucent groups
+    /// blend with each other.
+    pub fn render(&self, ctx: &mut Context<'_>) -> Result<()> {
+        ctx.with_state(|ctx| {
+            ctx.clip_to(0.0, 0.0, self.width, self.height);
+            ctx.transform(&self.view_box);
+            self.render_nodes(ctx, &self.nodes)
+        })?
+    }
+
+    /// Renders the document scaled to the given size into a new image with
+    /// a transparent background.
+    pub fn to_image(&self, width: i32, height: i32) -> Result<Image> {
+        let mut image = Image::new(width, height, ImageFormat::PRgb32)?;
+        {
+            let mut ctx = Context::new(&mut image)?;
+            ctx.clear_all()?;
+            ctx.scale(width as f64 / self.width, height as f64 / self.height);
+            self.render(&mut ctx)?;
+            ctx.end()?;
+        }
+        Ok(image)
+    }
+
+    fn render_nodes(&self, ctx: &mut Context<'_>, nodes: &[Node]) -> Result<()> {
+        for node in nodes {
+            match node {
+                Node::Group {
+                    transform,
+                    opacity,
+                    children,
+                } => ctx.with_state(|ctx| {
+                    ctx.transform(transform);
+                    ctx.set_global_alpha(ctx.global_alpha() * opacity);
+                    self.render_nodes(ctx, children)
+                })??,
+                Node::Shape(shape) => ctx.with_state(|ctx| self.render_shape(ctx, shape))??,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_shape(&self, ctx: &mut Context<'_>, shape: &Shape) -> Result<()> {
+        let props = &shape.props;
+        ctx.transform(&shape.transform);
+        ctx.set_global_alpha(ctx.global_alpha() * shape.opacity);
+
+        if let Some(style) = self.resolve_paint(&props.fill, props.color, &shape.path) {
+            ctx.set_fill_style(&style);
+            ctx.set_fill_alpha(props.fill_opacity);
+            ctx.set_fill_rule(props.fill_rule);
+            ctx.fill_path(&shape.path)?;
+        }
+        if props.stroke_width > 0.0 {
+            if let Some(style) = self.resolve_paint(&props.stroke, props.color, &shape.path) {
+                ctx.set_stroke_style(&style);
+                ctx.set_stroke_alpha(props.stroke_opacity);
+                ctx.set_stroke_width(props.stroke_width);
+                ctx.set_stroke_join(props.stroke_join);
+                ctx.set_stroke_caps(props.stroke_cap);
+                ctx.set_stroke_miter_limit(props.stroke_miter_limit);
+                ctx.stroke_path(&shape.path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_paint(&self, paint: &PaintValue, color: Rgba32, path: &Path) -> Option<Style> {
+        match paint {
+            PaintValue::None => None,
+            PaintValue::CurrentColor => Some(color.into()),
+            PaintValue::Color(color) => Some((*color).into()),
+            // references to missing gradients paint nothing
+            PaintValue::Url(id) => self
+                .gradients
+                .get(id)?
+                .to_gradient(path)
+                .map(Style::Gradient),
+        }
+    }
+}
+
+/// Maps the `viewBox` onto the viewport according to `preserveAspectRatio`.
+fn view_box_matrix(
+    view_box: &RectD,
+    width: f64,
+    height: f64,
+    preserve_aspect_ratio: &str,
+) -> Result<Matrix2D> {
+    let mut parts = preserve_aspect_ratio.split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+    let slice = match parts.next() {
+        None | Some("meet") => false,
+        Some("slice") => true,
+        Some(_) => return Err(Error::InvalidValue),
+    };
+    let (sx, sy) = (width / view_box.w, height / view_box.h);
+    if align == "none" {
+        return Ok(Matrix2D::new(
+            sx,
+            0.0,
+            0.0,
+            sy,
+            -view_box.x * sx,
+            -view_box.y * sy,
+        ));
+    }
+    let factor = |align: &str| match align {
+        "Min" => Ok(0.0),
+        "Mid" => Ok(0.5),
+        "Max" => Ok(1.0),
+        _ => Err(Error::InvalidValue),
+    };
+    if align.len() != 8 || !align.starts_with('x') || align.as_bytes()[4] != b'Y' {
+        return Err(Error::InvalidValue);
+    }
+    let (ax, ay) = (factor(&align[1..4])?, factor(&align[5..8])?);
+    let scale = if slice { sx.max(sy) } else { sx.min(sy) };
+    Ok(Matrix2D::new(
+        scale,
+        0.0,
+        0.0,
+        scale,
+        (width - view_box.w * scale) * ax - view_box.x * scale,
+        (height - view_box.h * scale) * ay - view_box.y * scale,
+    ))
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Group {
+        transform: Matrix2D,
+        opacity: f64,
+        children: Vec<Node>,
+    },
+    Shape(Shape),
+}
+
+#[derive(Debug, Clone)]
+struct Shape {
+    path: Path,
+    transform: Matrix2D,
+    opacity: f64,
+    props: Properties,
+}
+
+/// A parsed `fill` or `stroke` value.
+#[derive(Debug, Clone, PartialEq)]
+enum PaintValue {
+    None,
+    CurrentColor,
+    Color(Rgba32),
+    Url(String),
+}
+
+/// The inherited properties of an element.
+#[derive(Debug, Clone)]
+struct Properties {
+    color: Rgba32,
+    fill: PaintValue,
+    fill_opacity: f64,
+    fill_rule: FillRule,
+    stroke: PaintValue,
+    stroke_opacity: f64,
+    stroke_width: f64,
+    stroke_join: StrokeJoin,
+    stroke_cap: StrokeCap,
+    stroke_miter_limit: f64,
+}
+
+impl Default for Properties {
+    fn default() -> Self {
+        Properties {
+            color: Rgba32::new(0xFF_00_00_00),
+            fill: PaintValue::Color(Rgba32::new(0xFF_00_00_00)),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: PaintValue::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            stroke_join: StrokeJoin::MiterBevel,
+            stroke_cap: StrokeCap::Butt,
+            stroke_miter_limit: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SvgGradient {
+    gradient: DynamicGradient,
+    object_bounding_box: bool,
+}
+
+impl SvgGradient {
+    /// Creates the gradient for painting the given path, returns `None` if
+    /// the gradient is relative to the bounding box of the path and the box
+    /// is empty.
+    fn to_gradient(&self, path: &Path) -> Option<DynamicGradient> {
+        let mut gradient = self.gradient.clone();
+        if self.object_bounding_box {
+            let bbox = path.bounding_box()?;
+            let (w, h) = (bbox.x1 - bbox.x0, bbox.y1 - bbox.y0);
+            if w <= 0.0 || h <= 0.0 {
+                return None;
+            }
+            gradient.post_transform(&Matrix2D::new(w, 0.0, 0.0, h, bbox.x0, bbox.y0));
+        }
+        Some(gradient)
+    }
+}
+
+struct Parser {
+    /// The size of the user space of the root element, percentages are
+    /// relative to it.
+    viewport: (f64, f64),
+}
+
+impl Parser {
+    fn diagonal(&self) -> f64 {
+        let (w, h) = self.viewport;
+        ((w * w + h * h) / 2.0).sqrt()
+    }
+
+    fn children(&self, node: XmlNode<'_, '_>, props: &Properties) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        for child in node.children().filter(|node| node.is_element()) {
+            if let Some(node) = self.node(child, props)? {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn node(&self, node: XmlNode<'_, '_>, parent: &Properties) -> Result<Option<Node>> {
+        let (props, opacity) = self.properties(node, parent)?;
+        let transform = node
+            .attribute("transform")
+            .map_or(Ok(Matrix2D::identity()), svg_attrs::transform)?;
+        if node.tag_name().name() == "g" {
+            return Ok(Some(Node::Group {
+                transform,
+                opacity,
+                children: self.children(node, &props)?,
+            }));
+        }
+        Ok(self.shape_path(node)?.map(|path| {
+            Node::Shape(Shape {
+                path,
+                transform,
+                opacity,
+                props,
+            })
+        }))
+    }
+
+    /// Resolves the properties of an element from its parent's and returns
+    /// them together with the element's opacity.
+    fn properties(&self, node: XmlNode<'_, '_>, parent: &Properties) -> Result<(Properties, f64)> {
+        let mut props = parent.clone();
+        let mut opacity = 1.0;
+        let attributes = node
+            .attributes()
+            .iter()
+            .map(|attr| (attr.name(), attr.value()));
+        let declarations = svg_attrs::declarations(node.attribute("style").unwrap_or(""));
+        // style declarations take precedence over presentation attributes
+        for (name, value) in attributes.chain(declarations) {
+            if value == "inherit" {
+                continue;
+            }
+            match name {
+                "opacity" => opacity = clamp_unit(svg_attrs::fraction(value)?),
+                "color" => props.color = svg_attrs::color(value)?,
+                "fill" => props.fill = parse_paint(value)?,
+                "fill-opacity" => props.fill_opacity = clamp_unit(svg_attrs::fraction(value)?),
+                "fill-rule" => {
+                    props.fill_rule = match value {
+                        "nonzero" => FillRule::NonZero,
+                        "evenodd" => FillRule::EvenOdd,
+                        _ => return Err(Error::InvalidValue),
+                    }
+                },
+                "stroke" => props.stroke = parse_paint(value)?,
+                "stroke-opacity" => props.stroke_opacity = clamp_unit(svg_attrs::fraction(value)?),
+                "stroke-width" => props.stroke_width = svg_attrs::length(value, self.diagonal())?,
+                "stroke-linejoin" => {
+                    props.stroke_join = match value {
+                        "miter" => StrokeJoin::MiterBevel,
+                        "round" => StrokeJoin::Round,
+                        "bevel" => StrokeJoin::Bevel,
+                        _ => return Err(Error::InvalidValue),
+                    }
+                },
+                "stroke-linecap" => {
+                    props.stroke_cap = match value {
+                        "butt" => StrokeCap::Butt,
+                        "round" => StrokeCap::Round,
+                        "square" => StrokeCap::Square,
+                        _ => return Err(Error::InvalidValue),
+                    }
+                },
+                "stroke-miterlimit" => props.stroke_miter_limit = svg_attrs::number(value)?,
+                _ => {},
+            }
+        }
+        Ok((props, opacity))
+    }
+
+    /// Creates the path of a shape element, returns `None` for all other
+    /// elements.
+    fn shape_path(&self, node: XmlNode<'_, '_>) -> Result<Option<Path>> {
+        let (vw, vh) = self.viewport;
+        let length = |name: &str, reference: f64| {
+            node.attribute(name)
+                .map_or(Ok(0.0), |value| svg_attrs::length(value, reference))
+        };
+        let mut path = Path::new();
+        match node.tag_name().name() {
+            "path" => path = Path::from_svg_path(node.attribute("d").unwrap_or(""))?,
+            "rect" => {
+                let (x, y) = (length("x", vw)?, length("y", vh)?);
+                let (w, h) = (length("width", vw)?, length("height", vh)?);
+                let rx = node
+                    .attribute("rx")
+                    .map(|v| svg_attrs::length(v, vw))
+                    .transpose()?;
+                let ry = node
+                    .attribute("ry")
+                    .map(|v| svg_attrs::length(v, vh))
+                    .transpose()?;
+                // a missing radius defaults to the other one
+                let (rx, ry) = (rx.or(ry), ry.or(rx));
+                let rx = rx.unwrap_or(0.0).min(w / 2.0);
+                let ry = ry.unwrap_or(0.0).min(h / 2.0);
+                if w > 0.0 && h > 0.0 {
+                    if rx > 0.0 && ry > 0.0 {
+                        let rect = RoundRect::new(x, y, w, h, rx, ry);
+                        path.add_geometry(&rect, None, GeometryDirection::Clockwise);
+                    } else {
+                        let rect = RectD::new(x, y, w, h);
+                        path.add_geometry(&rect, None, GeometryDirection::Clockwise);
+                    }
+                }
+            },
+            "circle" => {
+                let r = length("r", self.diagonal())?;
+                if r > 0.0 {
+                    let circle = Circle::new(length("cx", vw)?, length("cy", vh)?, r);
+                    path.add_geometry(&circle, None, GeometryDirection::Clockwise);
+                }
+            },
+            "ellipse" => {
+                let (rx, ry) = (length("rx", vw)?, length("ry", vh)?);
+                if rx > 0.0 && ry > 0.0 {
+                    let ellipse = Ellipse::new(length("cx", vw)?, length("cy", vh)?, rx, ry);
+                    path.add_geometry(&ellipse, None, GeometryDirection::Clockwise);
+                }
+            },
+            "line" => {
+                path.move_to(length("x1", vw)?, length("y1", vh)?);
+                path.line_to(length("x2", vw)?, length("y2", vh)?);
+            },
+            name @ "polyline" | name @ "polygon" => {
+                let points = svg_attrs::number_list(node.attribute("points").unwrap_or(""))?;
+                // a trailing odd coordinate is ignored
+                for (i, point) in points.chunks_exact(2).enumerate() {
+                    if i == 0 {
+                        path.move_to(point[0], point[1]);
+                    } else {
+                        path.line_to(point[0], point[1]);
+                    }
+                }
+                if name == "polygon" && !path.is_empty() {
+                    path.close();
+                }
+            },
+            _ => return Ok(None),
+        }
+        Ok(Some(path))
+    }
+
+    /// Parses a gradient element, returns `None` for all other elements.
+    fn gradient(&self, node: XmlNode<'_, '_>) -> Result<Option<SvgGradient>> {
+        let kind = match node.tag_name().name() {
+            "linearGradient" => SvgGradientKind::Linear,
+            "radialGradient" => SvgGradientKind::Radial,
+            _ => return Ok(None),
+        };
+        let object_bounding_box = node.attribute("gradientUnits") != Some("userSpaceOnUse");
+        let mut attrs = node
+            .attributes()
+            .iter()
+            .map(|attr| (attr.name(), attr.value().to_owned()))
+            .collect::<Vec<_>>();
+        if !object_bounding_box {
+            // percentages in user space are relative to the viewport, which
+            // the gradient parser doesn't know about
+            let (vw, vh) = self.viewport;
+            let coordinates = [
+                ("x1", Some("0%"), vw),
+                ("y1", Some("0%"), vh),
+                ("x2", Some("100%"), vw),
+                ("y2", Some("0%"), vh),
+                ("cx", Some("50%"), vw),
+                ("cy", Some("50%"), vh),
+                ("fx", None, vw),
+                ("fy", None, vh),
+                ("r", Some("50%"), self.diagonal()),
+            ];
+            for &(name, default, reference) in &coordinates {
+                if let Some(value) = node.attribute(name).or(default) {
+                    let value = svg_attrs::length(value, reference)?;
+                    attrs.push((name, value.to_string()));
+                }
+            }
+        }
+        let attrs = attrs
+            .iter()
+            .map(|(name, value)| (*name, &**value))
+            .collect::<Vec<_>>();
+        let stops = node
+            .children()
+            .filter(|node| node.has_tag_name("stop"))
+            .map(|stop| {
+                stop.attributes()
+                    .iter()
+                    .map(|attr| (attr.name(), attr.value()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let stops = stops.iter().map(|stop| &stop[..]).collect::<Vec<_>>();
+        Ok(Some(SvgGradient {
+            gradient: from_svg_attrs(kind, &attrs, &stops)?,
+            object_bounding_box,
+        }))
+    }
+}
+
+fn parse_paint(value: &str) -> Result<PaintValue> {
+    let value = value.trim();
+    if value == "none" {
+        Ok(PaintValue::None)
+    } else if value == "currentColor" {
+        Ok(PaintValue::CurrentColor)
+    } else if let Some(rest) = value.strip_prefix("url(") {
+        let end = rest.find(')').ok_or(Error::InvalidValue)?;
+        let url = rest[..end].trim().trim_matches(|c| c == '\'' || c == '"');
+        url.strip_prefix('#')
+            .map(|id| PaintValue::Url(id.to_owned()))
+            .ok_or(Error::InvalidValue)
+    } else {
+        svg_attrs::color(value).map(PaintValue::Color)
+    }
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    value.max(0.0).min(1.0)
+}
+
+#[cfg(test)]
+mod test_svg {
+    use crate::svg::SvgDocument;
+
+    const DOCUMENT: &str = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="64" height="32" viewBox="0 0 32 16">
+            <defs>
+                <linearGradient id="fade">
+                    <stop offset="0" stop-color="#00f"/>
+                    <stop offset="100%" style="stop-color: blue; stop-opacity: 0.5"/>
+                </linearGradient>
+            </defs>
+            <rect width="16" height="16" fill="red"/>
+            <g transform="translate(16 0)" style="fill: url(#fade)">
+                <circle cx="8" cy="8" r="8"/>
+            </g>
+        </svg>
+    "##;
+
+    #[test]
+    fn test_svg_to_image() {
+        let document = SvgDocument::parse(DOCUMENT).unwrap();
+        assert_eq!((document.width(), document.height()), (64.0, 32.0));
+        let image = document.to_image(64, 32).unwrap();
+        assert_eq!(image.pixel(8, 16), Some(0xFF_FF_00_00));
+        assert_eq!(image.pixel(34, 2), Some(0));
+        let pixel = image.pixel(48, 16).unwrap();
+        assert_eq!(pixel & 0x00_FF_FF_00, 0);
+        assert!(pixel >> 24 > 0x80);
+    }
+
+    #[test]
+    fn test_svg_invalid() {
+        assert!(SvgDocument::parse("<svg/>").is_err());
+        assert!(SvgDocument::parse("<g width='1' height='1'/>").is_err());
+        assert!(SvgDocument::parse("<svg width='1' height='1' fill='nope'/>").is_err());
+        assert!(SvgDocument::parse("<svg width='1' height='1'><path d='L'/></svg>").is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Parsers for the attribute values of SVG documents.
+use std::f64::consts::PI;
+
+use crate::error::{Error, Result};
+use crate::matrix::{Matrix2D, MatrixTransform};
+use crate::rgba::Rgba32;
+
+pub(in crate) fn number(value: &str) -> Result<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or(Error::InvalidValue)
+}
+
+/// Parses a length, percentages are relative to `reference`. Absolute units
+/// are converted at 96 dpi.
+pub(in crate) fn length(value: &str, reference: f64) -> Result<f64> {
+    let value = value.trim();
+    let (num, factor) = if let Some(num) = value.strip_suffix('%') {
+        (num, reference / 100.0)
+    } else {
+        let split = value
+            .rfind(|c: char| c.is_ascii_digit() || c == '.')
+            .map_or(0, |i| i + 1);
+        let factor = match &value[split..] {
+            "" | "px" => 1.0,
+            "pt" => 96.0 / 72.0,
+            "pc" => 16.0,
+            "in" => 96.0,
+            "cm" => 96.0 / 2.54,
+            "mm" => 96.0 / 25.4,
+            _ => return Err(Error::InvalidValue),
+        };
+        (&value[..split], factor)
+    };
+    number(num).map(|n| n * factor)
+}
+
+/// Parses a number or a percentage, used for gradient offsets and opacities.
+pub(in crate) fn fraction(value: &str) -> Result<f64> {
+    let value = value.trim();
+    match value.strip_suffix('%') {
+        Some(num) => number(num).map(|n| n / 100.0),
+        None => number(value),
+    }
+}
+
+pub(in crate) fn number_list(value: &str) -> Result<Vec<f64>> {
+    value
+        .split(|c: char| c == ',' || c.is_ascii_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(number)
+        .collect()
+}
+
+/// Splits the declarations of a `style` attribute into name-value pairs.
+pub(in crate) fn declarations(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style.split(';').filter_map(|decl| {
+        let mut parts = decl.splitn(2, ':');
+        let name = parts.next()?.trim();
+        let value = parts.next()?.trim();
+        if name.is_empty() {
+            None
+        } else {
+            Some((name, value))
+        }
+    })
+}
+
+/// Parses a hex, `rgb()`, `rgba()`, `hsl()`, `hsla()` or named color.
+///
+/// The functional forms accept both the comma separated syntax and the space
+/// separated one with an optional `/ alpha`.
+pub(in crate) fn color(value: &str) -> Result<Rgba32> {
+    let value = value.trim();
+    if value.starts_with('#') {
+        return value.parse();
+    }
+    let (name, args) = match value.find('(') {
+        Some(open) if value.ends_with(')') => (&value[..open], &value[open + 1..value.len() - 1]),
+        _ => return named_color(value).ok_or(Error::InvalidValue),
+    };
+    let args = args
+        .split(|c: char| c == ',' || c == '/' || c.is_ascii_whitespace())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let alpha = match args.len() {
+        3 => 1.0,
+        4 => fraction(args[3])?.max(0.0).min(1.0),
+        _ => return Err(Error::InvalidValue),
+    };
+    let [r, g, b] = match &*name.trim().to_ascii_lowercase() {
+        "rgb" | "rgba" => {
+            let mut components = [0.0; 3];
+            for (component, arg) in components.iter_mut().zip(&args) {
+                *component = match arg.strip_suffix('%') {
+                    Some(percent) => number(percent)? / 100.0,
+                    None => number(arg)? / 255.0,
+                };
+            }
+            components
+        },
+        "hsl" | "hsla" => {
+            let percent = |arg: &str| {
+                let percent = arg.strip_suffix('%').ok_or(Error::InvalidValue)?;
+                number(percent).map(|n| (n / 100.0).max(0.0).min(1.0))
+            };
+            hsl_to_rgb(angle(args[0])?, percent(args[1])?, percent(args[2])?)
+        },
+        _ => return Err(Error::InvalidValue),
+    };
+    let byte = |c: f64| (c * 255.0).round().max(0.0).min(255.0) as u8;
+    Ok(Rgba32::from_rgba(byte(r), byte(g), byte(b), byte(alpha)))
+}
+
+/// Parses an angle into degrees, a plain number is taken as degrees.
+pub(in crate) fn angle(value: &str) -> Result<f64> {
+    let value = value.trim();
+    let split = value
+        .rfind(|c: char| c.is_ascii_digit() || c == '.')
+        .map_or(0, |i| i + 1);
+    let factor = match &value[split..] {
+        "" | "deg" => 1.0,
+        "rad" => 180.0 / PI,
+        "grad" => 0.9,
+        "turn" => 360.0,
+        _ => return Err(Error::InvalidValue),
+    };
+    number(&value[..split]).map(|n| n * factor)
+}
+
+/// Converts a hue in degrees and a saturation and lightness in `0.0..=1.0` to
+/// red, green and blue components in `0.0..=1.0`.
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [f64; 3] {
+    let hue = hue.rem_euclid(360.0) / 30.0;
+    let a = saturation * lightness.min(1.0 - lightness);
+    let channel = |n: f64| {
+        let k = (n + hue) % 12.0;
+        lightness - a * (k - 3.0).min(9.0 - k).min(1.0).max(-1.0)
+    };
+    [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+fn named_color(name: &str) -> Option<Rgba32> {
+    let rgb = match &*name.to_ascii_lowercase() {
+        "transparent" => return Some(Rgba32::new(0)),
+        "black" => 0x00_00_00,
+        "silver" => 0xC0_C0_C0,
+        "gray" | "grey" => 0x80_80_80,
+        "white" => 0xFF_FF_FF,
+        "maroon" => 0x80_00_00,
+        "red" => 0xFF_00_00,
+        "purple" => 0x80_00_80,
+        "fuchsia" | "magenta" => 0xFF_00_FF,
+        "green" => 0x00_80_00,
+        "lime" => 0x00_FF_00,
+        "olive" => 0x80_80_00,
+        "yellow" => 0xFF_FF_00,
+        "navy" => 0x00_00_80,
+        "blue" => 0x00_00_FF,
+        "teal" => 0x00_80_80,
+        "aqua" | "cyan" => 0x00_FF_FF,
+        "orange" => 0xFF_A5_00,
+        _ => return None,
+    };
+    Some(Rgba32::new(0xFF_00_00_00 | rgb))
+}
+
+/// Parses a transform list into a single matrix.
+pub(in crate) fn transform(value: &str) -> Result<Matrix2D> {
+    let mut matrix = Matrix2D::identity();
+    let mut rest = value.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+    while !rest.is_empty() {
+        let open = rest.find('(').ok_or(Error::InvalidValue)?;
+        let close = rest.find(')').ok_or(Error::InvalidValue)?;
+        if close < open {
+            return Err(Error::InvalidValue);
+        }
+        let args = number_list(&rest[open + 1..close])?;
+        let op = match (rest[..open].trim(), &*args) {
+            ("matrix", &[a, b, c, d, e, f]) => Matrix2D::new(a, b, c, d, e, f),
+            ("translate", &[x]) => Matrix2D::translation(x, 0.0),
+            ("translate", &[x, y]) => Matrix2D::translation(x, y),
+            ("scale", &[s]) => Matrix2D::scaling(s, s),
+            ("scale", &[x, y]) => Matrix2D::scaling(x, y),
+            ("rotate", &[a]) => Matrix2D::rotation(a * PI / 180.0, 0.0, 0.0),
+            ("rotate", &[a, x, y]) => Matrix2D::rotation(a * PI / 180.0, x, y),
+            ("skewX", &[a]) => Matrix2D::new(1.0, 0.0, (a * PI / 180.0).tan(), 1.0, 0.0, 0.0),
+            ("skewY", &[a]) => Matrix2D::new(1.0, (a * PI / 180.0).tan(), 0.0, 1.0, 0.0, 0.0),
+            _ => return Err(Error::InvalidValue),
+        };
+        // later transforms of the list are applied to the points first
+        matrix.transform(&op);
+        rest = rest[close + 1..].trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+    }
+    Ok(matrix)
+}